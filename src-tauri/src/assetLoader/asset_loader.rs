@@ -1,8 +1,27 @@
+use futures_util::StreamExt;
+use log::{info, warn};
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
-use std::path::PathBuf;
-use tauri::Manager;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tauri::{Emitter, Manager, State};
+use tokio::io::AsyncWriteExt;
+
+/// Cache budget enforced after every download by `load_asset` (512 MiB)
+pub const DEFAULT_MAX_CACHE_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Download attempts made by `load_asset` unless the caller overrides it
+pub const DEFAULT_DOWNLOAD_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry; doubled for each further attempt (250ms, 500ms, 1s, ...)
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
 
 /// Information about a loaded asset
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -13,6 +32,266 @@ pub struct AssetInfo {
     pub cached: bool,
     /// The type of asset (e.g., "Image", "Video")
     pub asset_type: String,
+    /// Pixel width, for images whose header could be read
+    #[serde(default)]
+    pub width: Option<u32>,
+    /// Pixel height, for images whose header could be read
+    #[serde(default)]
+    pub height: Option<u32>,
+}
+
+impl AssetInfo {
+    /// Describe a cached file, probing image dimensions from its header.
+    /// An unreadable or corrupt image still yields the path, with no dimensions.
+    fn new(file_path: &Path, cached: bool, asset_type: &AssetType) -> Self {
+        let dimensions = match asset_type {
+            AssetType::Image => probe_image_dimensions(file_path),
+            _ => None,
+        };
+        Self {
+            path: file_path.to_string_lossy().to_string(),
+            cached,
+            asset_type: asset_type.display_name(),
+            width: dimensions.map(|(width, _)| width),
+            height: dimensions.map(|(_, height)| height),
+        }
+    }
+}
+
+/// Read an image's dimensions from its header without decoding the pixels
+fn probe_image_dimensions(file_path: &Path) -> Option<(u32, u32)> {
+    image::ImageReader::open(file_path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+/// One entry of a `prefetch_assets` batch
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AssetRequest {
+    pub url: String,
+    pub asset_type: AssetType,
+}
+
+/// How many `prefetch_assets` downloads run at once
+const PREFETCH_CONCURRENCY: usize = 4;
+
+/// Payload of the `asset-progress` event emitted while `load_asset` downloads
+#[derive(Debug, Serialize, Clone)]
+pub struct AssetProgress {
+    pub url: String,
+    /// Bytes written to disk so far
+    pub downloaded: u64,
+    /// Total size from `Content-Length`, if the server sent one
+    pub total: Option<u64>,
+}
+
+/// HTTP validators saved in a `<file>.meta` sidecar next to each downloaded asset
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct CacheMeta {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl CacheMeta {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: HeaderName| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// `If-None-Match` / `If-Modified-Since` for a conditional request
+    fn conditional_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(value) = self
+            .etag
+            .as_deref()
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(IF_NONE_MATCH, value);
+        }
+        if let Some(value) = self
+            .last_modified
+            .as_deref()
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(IF_MODIFIED_SINCE, value);
+        }
+        headers
+    }
+}
+
+/// Sidecar path holding a cached file's `CacheMeta`
+fn meta_path(file_path: &Path) -> PathBuf {
+    let mut path = file_path.as_os_str().to_owned();
+    path.push(".meta");
+    PathBuf::from(path)
+}
+
+/// Saved validators for a cached file, if it has any
+async fn read_cache_meta(file_path: &Path) -> Option<CacheMeta> {
+    let json = tokio::fs::read_to_string(meta_path(file_path)).await.ok()?;
+    let meta: CacheMeta = serde_json::from_str(&json).ok()?;
+    (!meta.is_empty()).then_some(meta)
+}
+
+/// Save validators next to a cached file, removing a stale sidecar when there are none
+async fn write_cache_meta(file_path: &Path, meta: &CacheMeta) {
+    let path = meta_path(file_path);
+    if meta.is_empty() {
+        let _ = tokio::fs::remove_file(path).await;
+    } else if let Ok(json) = serde_json::to_string(meta) {
+        let _ = tokio::fs::write(path, json).await;
+    }
+}
+
+/// Disk usage of one asset type's cache subdirectory
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CacheDirStats {
+    /// Subdirectory name (e.g., "images", "videos")
+    pub subdirectory: String,
+    pub file_count: u64,
+    pub total_bytes: u64,
+}
+
+/// Disk usage of the whole asset cache, as reported by `get_cache_stats`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CacheStats {
+    /// One entry per asset type subdirectory, zeros where it doesn't exist yet
+    pub by_type: Vec<CacheDirStats>,
+    pub file_count: u64,
+    pub total_bytes: u64,
+}
+
+/// When enabled, `load_asset` serves only from cache and never touches the network
+#[derive(Default)]
+pub struct OfflineMode(AtomicBool);
+
+impl OfflineMode {
+    pub fn is_enabled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn set(&self, enabled: bool) {
+        self.0.store(enabled, Ordering::Relaxed);
+    }
+}
+
+/// Cancellation flags for in-progress downloads, keyed by cache filename, and the
+/// cache files in-flight calls have written but not yet returned
+#[derive(Default)]
+pub struct AssetDownloads {
+    flags: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    /// Pin counts; pinned files are never evicted
+    pinned: Mutex<HashMap<PathBuf, usize>>,
+}
+
+impl AssetDownloads {
+    /// Track a new download; the flag is removed again when the guard drops
+    fn begin(&self, key: String) -> DownloadGuard<'_> {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        if let Ok(mut downloads) = self.flags.lock() {
+            downloads.insert(key.clone(), cancelled.clone());
+        }
+        DownloadGuard {
+            downloads: self,
+            key,
+            cancelled,
+        }
+    }
+
+    /// Flag the download for `key`. Returns false if none is in progress.
+    fn cancel(&self, key: &str) -> bool {
+        let Ok(downloads) = self.flags.lock() else {
+            return false;
+        };
+        match downloads.get(key) {
+            Some(cancelled) => {
+                cancelled.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Protect `path` from eviction until the guard drops. Pin before the file
+    /// appears in the cache, so a concurrent eviction that lists it also sees the pin.
+    fn pin(&self, path: PathBuf) -> PinGuard<'_> {
+        if let Ok(mut pinned) = self.pinned.lock() {
+            *pinned.entry(path.clone()).or_insert(0) += 1;
+        }
+        PinGuard {
+            downloads: self,
+            path,
+        }
+    }
+
+    /// Currently pinned files
+    fn pinned(&self) -> HashSet<PathBuf> {
+        self.pinned
+            .lock()
+            .map(|pinned| pinned.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Registration of one in-progress download in `AssetDownloads`
+struct DownloadGuard<'a> {
+    downloads: &'a AssetDownloads,
+    key: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl DownloadGuard<'_> {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for DownloadGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut downloads) = self.downloads.flags.lock() {
+            // A newer download of the same asset may have replaced our flag
+            if downloads
+                .get(&self.key)
+                .is_some_and(|flag| Arc::ptr_eq(flag, &self.cancelled))
+            {
+                downloads.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// Pin on a cache file in `AssetDownloads`, released when dropped
+struct PinGuard<'a> {
+    downloads: &'a AssetDownloads,
+    path: PathBuf,
+}
+
+impl Drop for PinGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut pinned) = self.downloads.pinned.lock() {
+            if let Some(count) = pinned.get_mut(&self.path) {
+                *count -= 1;
+                if *count == 0 {
+                    pinned.remove(&self.path);
+                }
+            }
+        }
+    }
 }
 
 /// Supported asset types for the loader
@@ -60,22 +339,88 @@ impl AssetType {
     }
 }
 
-/// Generate a cache key from URL (hash-based filename)
+/// Map an HTTP `Content-Type` (parameters ignored) to a file extension
+fn extension_for_content_type(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next()?.trim().to_lowercase();
+    let ext = match mime.as_str() {
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/png" => "png",
+        "image/webp" => "webp",
+        "image/gif" => "gif",
+        "image/avif" => "avif",
+        "image/svg+xml" => "svg",
+        "video/mp4" => "mp4",
+        "video/webm" => "webm",
+        "video/quicktime" => "mov",
+        "audio/mpeg" => "mp3",
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        "audio/ogg" => "ogg",
+        "audio/flac" => "flac",
+        "application/pdf" => "pdf",
+        _ => return None,
+    };
+    Some(ext)
+}
+
+/// Hex digits of the URL's SHA-256 kept in cache filenames (128 bits)
+const CACHE_KEY_LEN: usize = 32;
+
+/// Generate a cache key from URL (hash-based file stem). SHA-256 is stable across
+/// toolchains and platforms. Files named by the old 16-digit `DefaultHasher` keys
+/// never match and are simply orphaned until `enforce_cache_limit` evicts them.
+fn url_to_cache_key(url: &str) -> String {
+    sha256_hex(url.as_bytes())[..CACHE_KEY_LEN].to_string()
+}
+
+/// Cache filename using the declared asset type's extension
 fn url_to_filename(url: &str, asset_type: &AssetType) -> String {
-    let mut hasher = DefaultHasher::new();
-    url.hash(&mut hasher);
-    let hash = hasher.finish();
-    format!("{:x}.{}", hash, asset_type.extension())
+    format!("{}.{}", url_to_cache_key(url), asset_type.extension())
 }
 
-/// Get or create the assets directory in app data
-async fn get_assets_dir(app: &tauri::AppHandle, asset_type: &AssetType) -> Result<PathBuf, String> {
+/// Cache filename for an imported local file: a hash of its contents, keeping the
+/// source's extension when it looks sane, else the declared type's extension
+fn content_filename(bytes: &[u8], source_path: &Path, asset_type: &AssetType) -> String {
+    let extension = source_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| !ext.is_empty() && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_else(|| asset_type.extension().to_string());
+    format!("{}.{}", &sha256_hex(bytes)[..CACHE_KEY_LEN], extension)
+}
+
+/// Find the cached file for a URL. The saved extension comes from the response
+/// `Content-Type`, so it may differ from the declared type's extension.
+async fn find_cached_file(assets_dir: &Path, url: &str, asset_type: &AssetType) -> Option<PathBuf> {
+    let declared = assets_dir.join(url_to_filename(url, asset_type));
+    if declared.exists() {
+        return Some(declared);
+    }
+
+    let key = url_to_cache_key(url);
+    let mut entries = tokio::fs::read_dir(assets_dir).await.ok()?;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.file_stem().is_some_and(|stem| stem == key.as_str()) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// The root `assets` directory in app data (holds one subdirectory per asset type)
+fn get_assets_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
     let app_data_dir = app
         .path()
         .app_data_dir()
         .map_err(|e| format!("Failed to get app data dir: {}", e))?;
 
-    let assets_dir = app_data_dir.join("assets").join(asset_type.subdirectory());
+    Ok(app_data_dir.join("assets"))
+}
+
+/// Get or create the assets directory in app data
+async fn get_assets_dir(app: &tauri::AppHandle, asset_type: &AssetType) -> Result<PathBuf, String> {
+    let assets_dir = get_assets_root(app)?.join(asset_type.subdirectory());
 
     // Create directory if it doesn't exist
     tokio::fs::create_dir_all(&assets_dir)
@@ -85,61 +430,377 @@ async fn get_assets_dir(app: &tauri::AppHandle, asset_type: &AssetType) -> Resul
     Ok(assets_dir)
 }
 
+/// A file in the asset cache, as considered for eviction
+#[derive(Debug, Clone)]
+struct CachedFile {
+    path: PathBuf,
+    size: u64,
+    /// Access time, or modified time where atime isn't available
+    last_used: SystemTime,
+}
+
+/// List the cached files under every asset type subdirectory.
+/// In-progress `.part` downloads and `.meta` sidecars are left out.
+async fn list_cached_files(assets_root: &Path) -> Vec<CachedFile> {
+    let mut files = Vec::new();
+    let Ok(mut type_dirs) = tokio::fs::read_dir(assets_root).await else {
+        return files;
+    };
+
+    while let Ok(Some(type_dir)) = type_dirs.next_entry().await {
+        let Ok(mut entries) = tokio::fs::read_dir(type_dir.path()).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if path
+                .extension()
+                .is_some_and(|ext| ext == "part" || ext == "meta")
+            {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata().await else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            let last_used = metadata
+                .accessed()
+                .or_else(|_| metadata.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push(CachedFile {
+                path,
+                size: metadata.len(),
+                last_used,
+            });
+        }
+    }
+    files
+}
+
+/// Pick the least-recently-used files to delete so the total fits in `max_bytes`.
+/// `keep` (the file just written) is never chosen.
+fn select_evictions(
+    mut files: Vec<CachedFile>,
+    max_bytes: u64,
+    keep: &HashSet<PathBuf>,
+) -> Vec<CachedFile> {
+    let mut total: u64 = files.iter().map(|f| f.size).sum();
+    files.sort_by_key(|f| f.last_used);
+
+    let mut evict = Vec::new();
+    for file in files {
+        if total <= max_bytes {
+            break;
+        }
+        if keep.contains(&file.path) {
+            continue;
+        }
+        total -= file.size;
+        evict.push(file);
+    }
+    evict
+}
+
+/// Delete least-recently-used cached files until the cache fits in `max_bytes`,
+/// skipping files pinned in `downloads` by calls still in flight (including other
+/// concurrent `load_asset` calls). Returns the number of bytes freed.
+async fn enforce_limit(assets_root: &Path, max_bytes: u64, downloads: &AssetDownloads) -> u64 {
+    let files = list_cached_files(assets_root).await;
+    // Read pins after listing: any listed file an in-flight call wrote was pinned first
+    let keep = downloads.pinned();
+
+    let mut freed = 0;
+    for file in select_evictions(files, max_bytes, &keep) {
+        if tokio::fs::remove_file(&file.path).await.is_ok() {
+            let _ = tokio::fs::remove_file(meta_path(&file.path)).await;
+            freed += file.size;
+        }
+    }
+    freed
+}
+
+/// Whether a cached file can be reused. Without `max_age_secs` any existing file is valid;
+/// otherwise its modified time must be within the TTL.
+async fn is_cache_fresh(file_path: &Path, max_age_secs: Option<u64>) -> bool {
+    let Ok(metadata) = tokio::fs::metadata(file_path).await else {
+        return false;
+    };
+    let Some(max_age) = max_age_secs else {
+        return true;
+    };
+
+    match metadata.modified().map(|modified| modified.elapsed()) {
+        Ok(Ok(age)) => age <= Duration::from_secs(max_age),
+        // mtime unavailable or in the future (clock skew) - keep the cached file
+        Ok(Err(_)) | Err(_) => true,
+    }
+}
+
+/// Lowercase hex encoding of a digest
+fn to_hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SHA-256 of `bytes` as lowercase hex
+fn sha256_hex(bytes: &[u8]) -> String {
+    to_hex(&Sha256::digest(bytes))
+}
+
+/// Compare a computed hex digest with a caller-supplied one (case and whitespace insensitive)
+fn checksum_matches(actual: &str, expected: &str) -> bool {
+    actual.eq_ignore_ascii_case(expected.trim())
+}
+
+/// Backoff before retrying after failed attempt number `attempt` (1-based)
+fn retry_delay(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY.saturating_mul(1 << attempt.saturating_sub(1).min(16))
+}
+
+/// Convert caller-supplied request headers into a `HeaderMap`.
+/// Errors name the offending header but never echo its (possibly secret) value.
+fn build_header_map(headers: &HashMap<String, String>) -> Result<HeaderMap, String> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| format!("Invalid header name: {}", name))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|_| format!("Invalid header value for {}", name))?;
+        map.insert(name, value);
+    }
+    Ok(map)
+}
+
+/// GET `url` with `extra_headers`, retrying connection errors and 5xx responses with
+/// exponential backoff. 2xx and 304 (for conditional requests) are returned; 4xx
+/// responses and other errors fail immediately; after the last attempt the final
+/// error is returned.
+async fn get_with_retry(
+    client: &reqwest::Client,
+    url: &str,
+    extra_headers: &HeaderMap,
+    max_attempts: u32,
+) -> Result<reqwest::Response, String> {
+    let mut attempt = 1;
+    loop {
+        let request = client.get(url).headers(extra_headers.clone());
+        let error = match request.send().await {
+            Ok(response)
+                if response.status().is_success()
+                    || response.status() == StatusCode::NOT_MODIFIED =>
+            {
+                return Ok(response)
+            }
+            Ok(response) => {
+                let error = format!("HTTP error: {}", response.status());
+                if !response.status().is_server_error() {
+                    return Err(error);
+                }
+                error
+            }
+            Err(e) => {
+                let error = format!("Failed to download asset: {}", e);
+                if !(e.is_connect() || e.is_timeout()) {
+                    return Err(error);
+                }
+                error
+            }
+        };
+
+        if attempt >= max_attempts {
+            return Err(error);
+        }
+        warn!(
+            "[AssetLoader] Attempt {}/{} for {} failed ({}), retrying",
+            attempt, max_attempts, url, error
+        );
+        tokio::time::sleep(retry_delay(attempt)).await;
+        attempt += 1;
+    }
+}
+
+/// Whether a cached file matches the expected checksum (always true without one)
+async fn is_cache_verified(file_path: &Path, expected_sha256: Option<&str>) -> bool {
+    let Some(expected) = expected_sha256 else {
+        return true;
+    };
+    tokio::fs::read(file_path)
+        .await
+        .is_ok_and(|bytes| checksum_matches(&sha256_hex(&bytes), expected))
+}
+
 /// Load an asset from a URL, caching it locally
 ///
 /// # Arguments
 /// * `url` - The URL to download the asset from
 /// * `asset_type` - The type of asset (Image, Video, Audio, Document, or Other)
+/// * `max_age_secs` - Optional. Re-download if the cached file is older than this.
+///                    If None, a cached file is reused forever.
+/// * `expected_sha256` - Optional hex digest. Downloads that don't match are rejected,
+///                       and a cached file that doesn't match is re-downloaded.
+/// * `max_attempts` - Optional. Download attempts before giving up on connection
+///                    errors or 5xx responses (default `DEFAULT_DOWNLOAD_ATTEMPTS`)
+/// * `revalidate` - Optional. When the cached file has a saved `ETag`/`Last-Modified`,
+///                  ask the server whether it changed (`304` keeps the cached copy,
+///                  `200` replaces it) instead of applying `max_age_secs`.
+/// * `headers` - Optional request headers (e.g. `Authorization`, `Referer`). They are not
+///               part of the cache key: the body is the same asset whoever fetched it, so
+///               requests for one URL share a cache entry regardless of credentials.
 /// * `app` - The Tauri app handle (injected automatically)
 ///
 /// # Returns
 /// * `AssetInfo` containing the local path and cache status
 #[tauri::command]
+#[allow(clippy::too_many_arguments)] // Tauri commands take each option as an argument
 pub async fn load_asset(
     url: String,
     asset_type: AssetType,
+    max_age_secs: Option<u64>,
+    expected_sha256: Option<String>,
+    max_attempts: Option<u32>,
+    headers: Option<HashMap<String, String>>,
+    revalidate: Option<bool>,
     app: tauri::AppHandle,
+    downloads: State<'_, AssetDownloads>,
+    offline_mode: State<'_, OfflineMode>,
 ) -> Result<AssetInfo, String> {
     // Get assets directory
     let assets_dir = get_assets_dir(&app, &asset_type).await?;
 
-    // Generate cache filename
-    let filename = url_to_filename(&url, &asset_type);
-    let file_path = assets_dir.join(&filename);
+    // Check if already cached (and not older than the TTL).
+    // Offline, a stale copy beats no copy, so the TTL is ignored.
+    // With saved validators, revalidation replaces the TTL check.
+    let offline = offline_mode.is_enabled();
+    let existing = find_cached_file(&assets_dir, &url, &asset_type).await;
+    let mut conditional = HeaderMap::new();
+    if let Some(file_path) = &existing {
+        if is_cache_verified(file_path, expected_sha256.as_deref()).await {
+            let validators = if revalidate.unwrap_or(false) && !offline {
+                read_cache_meta(file_path).await
+            } else {
+                None
+            };
+            match validators {
+                Some(meta) => conditional = meta.conditional_headers(),
+                None if offline || is_cache_fresh(file_path, max_age_secs).await => {
+                    return Ok(AssetInfo::new(file_path, true, &asset_type));
+                }
+                None => {}
+            }
+        }
+    }
 
-    // Check if already cached
-    if file_path.exists() {
-        return Ok(AssetInfo {
-            path: file_path.to_string_lossy().to_string(),
-            cached: true,
-            asset_type: asset_type.display_name(),
-        });
+    if offline {
+        return Err("Asset not cached and offline mode is enabled".to_string());
+    }
+
+    // Register for cancel_asset_download before any network work
+    let download = downloads.begin(url_to_filename(&url, &asset_type));
+
+    // Download the asset, retrying transient failures
+    let client = reqwest::Client::builder()
+        .default_headers(build_header_map(&headers.unwrap_or_default())?)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let attempts = max_attempts.unwrap_or(DEFAULT_DOWNLOAD_ATTEMPTS).max(1);
+    let mut response = get_with_retry(&client, &url, &conditional, attempts).await?;
+    if download.is_cancelled() {
+        return Err("Download cancelled".to_string());
     }
 
-    // Download the asset
-    let response = reqwest::get(&url)
+    // Unchanged on the server: keep the cached copy and restart its TTL
+    if response.status() == StatusCode::NOT_MODIFIED {
+        let file_path = existing
+            .as_ref()
+            .ok_or_else(|| format!("Unexpected 304 for uncached asset {}", url))?;
+        if let Ok(file) = std::fs::File::options().write(true).open(file_path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        return Ok(AssetInfo::new(file_path, true, &asset_type));
+    }
+    let meta = CacheMeta::from_headers(response.headers());
+
+    // Name the file after what the server actually sent, falling back to the declared type
+    let extension = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(extension_for_content_type)
+        .unwrap_or_else(|| asset_type.extension());
+    let file_path = assets_dir.join(format!("{}.{}", url_to_cache_key(&url), extension));
+    // Keep concurrent evictions away from the file until it has been returned
+    let _pin = downloads.pin(file_path.clone());
+
+    // Stream the body to a partial file, reporting progress per chunk.
+    // Renamed into place only once complete so an interrupted download is never "cached".
+    let part_path = file_path.with_extension(format!("{}.part", extension));
+    let mut file = tokio::fs::File::create(&part_path)
+        .await
+        .map_err(|e| format!("Failed to save asset: {}", e))?;
+
+    let total = response.content_length();
+    let mut downloaded: u64 = 0;
+    let mut hasher = Sha256::new();
+    while let Some(chunk) = response
+        .chunk()
         .await
-        .map_err(|e| format!("Failed to download asset: {}", e))?;
+        .map_err(|e| format!("Failed to read response: {}", e))?
+    {
+        if download.is_cancelled() {
+            drop(file);
+            let _ = tokio::fs::remove_file(&part_path).await;
+            return Err("Download cancelled".to_string());
+        }
 
-    if !response.status().is_success() {
-        return Err(format!("HTTP error: {}", response.status()));
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Failed to save asset: {}", e))?;
+        hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+
+        let _ = app.emit(
+            "asset-progress",
+            AssetProgress {
+                url: url.clone(),
+                downloaded,
+                total,
+            },
+        );
     }
 
-    let bytes = response
-        .bytes()
+    file.flush()
         .await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
+        .map_err(|e| format!("Failed to save asset: {}", e))?;
+    drop(file);
 
-    // Save to disk
-    tokio::fs::write(&file_path, &bytes)
+    // Never cache corrupt data
+    if let Some(expected) = &expected_sha256 {
+        if !checksum_matches(&to_hex(&hasher.finalize()), expected) {
+            let _ = tokio::fs::remove_file(&part_path).await;
+            return Err(format!("Checksum mismatch for {}", url));
+        }
+    }
+
+    tokio::fs::rename(&part_path, &file_path)
         .await
         .map_err(|e| format!("Failed to save asset: {}", e))?;
 
-    Ok(AssetInfo {
-        path: file_path.to_string_lossy().to_string(),
-        cached: false,
-        asset_type: asset_type.display_name(),
-    })
+    write_cache_meta(&file_path, &meta).await;
+
+    // A refresh may have changed the extension - drop the stale copy
+    if let Some(stale) = existing.filter(|path| *path != file_path) {
+        let _ = tokio::fs::remove_file(meta_path(&stale)).await;
+        let _ = tokio::fs::remove_file(stale).await;
+    }
+
+    // Keep the cache within budget, never evicting what we (or a concurrent call) just downloaded
+    let freed = enforce_limit(&get_assets_root(&app)?, DEFAULT_MAX_CACHE_BYTES, &downloads).await;
+    if freed > 0 {
+        info!("[AssetLoader] Evicted {} bytes from the asset cache", freed);
+    }
+
+    Ok(AssetInfo::new(&file_path, false, &asset_type))
 }
 
 /// Clear the asset cache
@@ -181,6 +842,186 @@ pub async fn clear_asset_cache(
     }
 }
 
+/// Evict least-recently-used cached assets until the cache fits in `max_bytes`
+///
+/// # Arguments
+/// * `max_bytes` - Budget for the whole `assets` directory, across all asset types
+/// * `app` - The Tauri app handle (injected automatically)
+///
+/// # Returns
+/// * The number of bytes freed
+#[tauri::command]
+pub async fn enforce_cache_limit(
+    max_bytes: u64,
+    app: tauri::AppHandle,
+    downloads: State<'_, AssetDownloads>,
+) -> Result<u64, String> {
+    let assets_root = get_assets_root(&app)?;
+    Ok(enforce_limit(&assets_root, max_bytes, &downloads).await)
+}
+
+/// Warm the cache with several assets in one call, downloading up to
+/// `PREFETCH_CONCURRENCY` at a time. Already-cached entries resolve immediately.
+///
+/// # Arguments
+/// * `requests` - The `{ url, asset_type }` pairs to load
+/// * `app` - The Tauri app handle (injected automatically)
+///
+/// # Returns
+/// * One `load_asset` result per request, in input order
+#[tauri::command]
+pub async fn prefetch_assets(
+    requests: Vec<AssetRequest>,
+    app: tauri::AppHandle,
+    downloads: State<'_, AssetDownloads>,
+    offline_mode: State<'_, OfflineMode>,
+) -> Result<Vec<Result<AssetInfo, String>>, String> {
+    let results = futures_util::stream::iter(requests)
+        .map(|request| {
+            load_asset(
+                request.url,
+                request.asset_type,
+                None,
+                None,
+                None,
+                None,
+                None,
+                app.clone(),
+                downloads.clone(),
+                offline_mode.clone(),
+            )
+        })
+        .buffered(PREFETCH_CONCURRENCY)
+        .collect()
+        .await;
+    Ok(results)
+}
+
+/// Copy a local file (e.g. one dropped onto a window) into the asset cache
+///
+/// # Arguments
+/// * `source_path` - Path of the file to import
+/// * `asset_type` - The type of asset; selects the cache subdirectory
+/// * `app` - The Tauri app handle (injected automatically)
+///
+/// # Returns
+/// * `AssetInfo` for the cached copy, named by a hash of its contents;
+///   `cached` is true if identical content was already imported
+#[tauri::command]
+pub async fn import_local_asset(
+    source_path: String,
+    asset_type: AssetType,
+    app: tauri::AppHandle,
+    downloads: State<'_, AssetDownloads>,
+) -> Result<AssetInfo, String> {
+    let source = Path::new(&source_path);
+    let metadata = tokio::fs::metadata(source)
+        .await
+        .map_err(|e| format!("Cannot access {}: {}", source_path, e))?;
+    if !metadata.is_file() {
+        return Err(format!("Not a file: {}", source_path));
+    }
+    let bytes = tokio::fs::read(source)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", source_path, e))?;
+
+    let assets_dir = get_assets_dir(&app, &asset_type).await?;
+    let file_path = assets_dir.join(content_filename(&bytes, source, &asset_type));
+
+    let cached = file_path.exists();
+    if !cached {
+        let _pin = downloads.pin(file_path.clone());
+        tokio::fs::write(&file_path, &bytes)
+            .await
+            .map_err(|e| format!("Failed to save asset: {}", e))?;
+        enforce_limit(&get_assets_root(&app)?, DEFAULT_MAX_CACHE_BYTES, &downloads).await;
+    }
+
+    Ok(AssetInfo::new(&file_path, cached, &asset_type))
+}
+
+/// Enable or disable offline mode. While enabled, `load_asset` returns cached files
+/// (ignoring `max_age_secs`) and fails for anything not cached, without any network request.
+///
+/// # Arguments
+/// * `enabled` - `true` to serve only from cache, `false` (the default) to download as usual
+/// * `offline_mode` - Offline flag (injected automatically)
+#[tauri::command]
+pub fn set_offline_mode(enabled: bool, offline_mode: State<'_, OfflineMode>) {
+    offline_mode.set(enabled);
+}
+
+/// Abort an in-progress `load_asset` download. The partial file is deleted and
+/// the originating `load_asset` call returns `Err("Download cancelled")`.
+///
+/// # Arguments
+/// * `url` - The URL passed to `load_asset`
+/// * `asset_type` - The asset type passed to `load_asset`
+/// * `downloads` - In-progress download registry (injected automatically)
+///
+/// # Returns
+/// * `true` if a download was in progress and has been cancelled
+#[tauri::command]
+pub fn cancel_asset_download(
+    url: String,
+    asset_type: AssetType,
+    downloads: State<'_, AssetDownloads>,
+) -> bool {
+    downloads.cancel(&url_to_filename(&url, &asset_type))
+}
+
+/// Count the files and bytes directly inside `dir` (zeros if it doesn't exist)
+async fn dir_stats(dir: &Path) -> (u64, u64) {
+    let (mut file_count, mut total_bytes) = (0, 0);
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return (file_count, total_bytes);
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if let Ok(metadata) = entry.metadata().await {
+            if metadata.is_file() {
+                file_count += 1;
+                total_bytes += metadata.len();
+            }
+        }
+    }
+    (file_count, total_bytes)
+}
+
+/// Report file count and size of the asset cache per asset type, plus a grand total
+///
+/// # Arguments
+/// * `app` - The Tauri app handle (injected automatically)
+///
+/// # Returns
+/// * `CacheStats` with an entry for every asset type subdirectory
+#[tauri::command]
+pub async fn get_cache_stats(app: tauri::AppHandle) -> Result<CacheStats, String> {
+    let assets_root = get_assets_root(&app)?;
+
+    let mut by_type = Vec::new();
+    for asset_type in [
+        AssetType::Image,
+        AssetType::Video,
+        AssetType::Audio,
+        AssetType::Document,
+        AssetType::Other(String::new()),
+    ] {
+        let subdirectory = asset_type.subdirectory().to_string();
+        let (file_count, total_bytes) = dir_stats(&assets_root.join(&subdirectory)).await;
+        by_type.push(CacheDirStats {
+            subdirectory,
+            file_count,
+            total_bytes,
+        });
+    }
+
+    Ok(CacheStats {
+        file_count: by_type.iter().map(|s| s.file_count).sum(),
+        total_bytes: by_type.iter().map(|s| s.total_bytes).sum(),
+        by_type,
+    })
+}
+
 /// Check if an asset is already cached
 ///
 /// # Arguments
@@ -197,10 +1038,10 @@ pub async fn is_asset_cached(
     app: tauri::AppHandle,
 ) -> Result<bool, String> {
     let assets_dir = get_assets_dir(&app, &asset_type).await?;
-    let filename = url_to_filename(&url, &asset_type);
-    let file_path = assets_dir.join(&filename);
 
-    Ok(file_path.exists())
+    Ok(find_cached_file(&assets_dir, &url, &asset_type)
+        .await
+        .is_some())
 }
 
 /// Get the cache path for an asset without downloading
@@ -211,7 +1052,8 @@ pub async fn is_asset_cached(
 /// * `app` - The Tauri app handle (injected automatically)
 ///
 /// # Returns
-/// * The path where the asset would be cached (may or may not exist)
+/// * The path of the cached file if present, otherwise where the asset would be
+///   cached under its declared type (the download may pick a different extension)
 #[tauri::command]
 pub async fn get_asset_cache_path(
     url: String,
@@ -219,17 +1061,240 @@ pub async fn get_asset_cache_path(
     app: tauri::AppHandle,
 ) -> Result<String, String> {
     let assets_dir = get_assets_dir(&app, &asset_type).await?;
-    let filename = url_to_filename(&url, &asset_type);
-    let file_path = assets_dir.join(&filename);
+    let file_path = find_cached_file(&assets_dir, &url, &asset_type)
+        .await
+        .unwrap_or_else(|| assets_dir.join(url_to_filename(&url, &asset_type)));
 
     Ok(file_path.to_string_lossy().to_string())
 }
 
-/// Load a local audio asset (e.g. from source/assets/audio/ambient)
+/// Dev-mode location of the audio assets, relative to the `src-tauri` working directory
+const DEV_AUDIO_DIR: &str = "../src/assets/audio";
+
+/// Resolve the root audio directory (containing `UI/` and `ambient/`).
+/// Bundled builds ship the assets under `<resource_dir>/audio`; when that
+/// directory is missing (e.g. `cargo run` without a bundle) we fall back to the source tree.
+pub fn resolve_audio_dir(resource_dir: Option<PathBuf>) -> PathBuf {
+    match resource_dir.map(|dir| dir.join("audio")) {
+        Some(dir) if dir.is_dir() => dir,
+        _ => PathBuf::from(DEV_AUDIO_DIR),
+    }
+}
+
+/// Load a local audio asset (e.g. from `<audio_dir>/ambient`)
 /// This simulates a centralized asset loader for static content.
-pub fn load_local_audio(filename: &str) -> std::io::Result<Vec<u8>> {
-    // In dev mode, we look in the src directory relative to execution
-    let base_path = "../src/assets/audio/ambient";
-    let path = std::path::Path::new(base_path).join(filename);
-    std::fs::read(path)
+pub fn load_local_audio(base_dir: &Path, filename: &str) -> std::io::Result<Vec<u8>> {
+    std::fs::read(base_dir.join(filename))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension_for_content_type() {
+        assert_eq!(extension_for_content_type("image/png"), Some("png"));
+        assert_eq!(extension_for_content_type("image/jpeg"), Some("jpg"));
+        assert_eq!(
+            extension_for_content_type("Video/MP4; codecs=avc1"),
+            Some("mp4")
+        );
+        assert_eq!(extension_for_content_type("application/octet-stream"), None);
+    }
+
+    fn cached(name: &str, size: u64, secs: u64) -> CachedFile {
+        CachedFile {
+            path: PathBuf::from(name),
+            size,
+            last_used: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+        }
+    }
+
+    #[test]
+    fn test_select_evictions_oldest_first_and_skips_kept() {
+        let files = vec![
+            cached("new.jpg", 40, 30),
+            cached("old.jpg", 40, 10),
+            cached("just-written.png", 40, 0),
+            cached("mid.jpg", 40, 20),
+        ];
+
+        // 160 bytes against a 90 byte budget: two evictions, oldest first,
+        // skipping the file that was just written
+        let keep = HashSet::from([PathBuf::from("just-written.png")]);
+        let evicted = select_evictions(files.clone(), 90, &keep);
+        let names: Vec<_> = evicted.iter().map(|f| f.path.to_str().unwrap()).collect();
+        assert_eq!(names, ["old.jpg", "mid.jpg"]);
+
+        assert!(select_evictions(files, 160, &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_pins_are_counted_per_call() {
+        let downloads = AssetDownloads::default();
+        let path = PathBuf::from("shared.jpg");
+
+        // Two in-flight calls writing the same file: it stays pinned until both return
+        let first = downloads.pin(path.clone());
+        let second = downloads.pin(path.clone());
+        drop(first);
+        assert!(downloads.pinned().contains(&path));
+        drop(second);
+        assert!(downloads.pinned().is_empty());
+    }
+
+    #[test]
+    fn test_cancel_download_flags_only_while_in_progress() {
+        let downloads = AssetDownloads::default();
+        assert!(!downloads.cancel("abc.jpg"));
+
+        let download = downloads.begin("abc.jpg".to_string());
+        assert!(!download.is_cancelled());
+        assert!(downloads.cancel("abc.jpg"));
+        assert!(download.is_cancelled());
+
+        // Finished downloads are no longer cancellable
+        drop(download);
+        assert!(!downloads.cancel("abc.jpg"));
+    }
+
+    #[test]
+    fn test_build_header_map() {
+        let mut headers = HashMap::new();
+        headers.insert("Authorization".to_string(), "Bearer secret".to_string());
+        let map = build_header_map(&headers).unwrap();
+        assert_eq!(map["authorization"], "Bearer secret");
+
+        // Invalid values are rejected without leaking them
+        headers.insert("Referer".to_string(), "bad\nvalue".to_string());
+        let err = build_header_map(&headers).unwrap_err();
+        assert_eq!(err, "Invalid header value for referer");
+    }
+
+    #[test]
+    fn test_cache_meta_validators() {
+        let mut response_headers = HeaderMap::new();
+        response_headers.insert(ETAG, HeaderValue::from_static("\"v1\""));
+        let meta = CacheMeta::from_headers(&response_headers);
+        assert_eq!(meta.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(meta.last_modified, None);
+
+        let conditional = meta.conditional_headers();
+        assert_eq!(conditional[IF_NONE_MATCH], "\"v1\"");
+        assert!(!conditional.contains_key(IF_MODIFIED_SINCE));
+
+        assert!(CacheMeta::from_headers(&HeaderMap::new()).is_empty());
+        assert_eq!(
+            meta_path(Path::new("images/abc.png")),
+            PathBuf::from("images/abc.png.meta")
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_doubles() {
+        assert_eq!(retry_delay(1), Duration::from_millis(250));
+        assert_eq!(retry_delay(2), Duration::from_millis(500));
+        assert_eq!(retry_delay(3), Duration::from_secs(1));
+    }
+
+    /// Serve one canned HTTP response per connection, in order
+    fn serve_responses(responses: Vec<&'static str>) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{}/asset.png", addr)
+    }
+
+    #[test]
+    fn test_get_with_retry_recovers_from_5xx() {
+        let url = serve_responses(vec![
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+        ]);
+
+        let body = tauri::async_runtime::block_on(async {
+            let response = get_with_retry(&reqwest::Client::new(), &url, &HeaderMap::new(), 3)
+                .await
+                .unwrap();
+            response.text().await.unwrap()
+        });
+        assert_eq!(body, "ok");
+    }
+
+    #[test]
+    fn test_get_with_retry_does_not_retry_4xx() {
+        let url = serve_responses(vec![
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ]);
+
+        let client = reqwest::Client::new();
+        let no_headers = HeaderMap::new();
+        let result = tauri::async_runtime::block_on(get_with_retry(&client, &url, &no_headers, 3));
+        assert_eq!(result.unwrap_err(), "HTTP error: 404 Not Found");
+    }
+
+    #[test]
+    fn test_url_to_filename_is_pinned() {
+        assert_eq!(
+            url_to_filename("https://src.hyphaeic.com/1.jpg", &AssetType::Image),
+            "408746641e4db0ad6ba0297ae4ea859f.jpg"
+        );
+    }
+
+    #[test]
+    fn test_content_filename() {
+        let name = content_filename(b"abc", Path::new("/tmp/Photo.PNG"), &AssetType::Image);
+        assert_eq!(name, "ba7816bf8f01cfea414140de5dae2223.png");
+
+        // No usable extension - fall back to the declared type
+        let name = content_filename(b"abc", Path::new("/tmp/photo"), &AssetType::Image);
+        assert_eq!(name, "ba7816bf8f01cfea414140de5dae2223.jpg");
+    }
+
+    #[test]
+    fn test_probe_image_dimensions_is_resilient() {
+        let dir = std::env::temp_dir();
+        let png = dir.join("hyphaeicos_probe_test.png");
+        let corrupt = dir.join("hyphaeicos_probe_corrupt.png");
+
+        // 3x2 RGBA image
+        image::RgbaImage::new(3, 2).save(&png).unwrap();
+        std::fs::write(&corrupt, b"not an image").unwrap();
+
+        let info = AssetInfo::new(&png, true, &AssetType::Image);
+        assert_eq!((info.width, info.height), (Some(3), Some(2)));
+
+        let info = AssetInfo::new(&corrupt, true, &AssetType::Image);
+        assert_eq!((info.width, info.height), (None, None));
+
+        // Only images are probed
+        let info = AssetInfo::new(&png, true, &AssetType::Document);
+        assert_eq!(info.width, None);
+
+        let _ = std::fs::remove_file(png);
+        let _ = std::fs::remove_file(corrupt);
+    }
+
+    #[test]
+    fn test_sha256_checksum() {
+        let digest = sha256_hex(b"abc");
+        assert_eq!(
+            digest,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(checksum_matches(
+            &digest,
+            " BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD\n"
+        ));
+        assert!(!checksum_matches(&digest, &sha256_hex(b"abd")));
+    }
 }
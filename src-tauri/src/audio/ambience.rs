@@ -1,32 +1,204 @@
+use super::AssetLoadFailure;
 use crate::asset_loader::load_local_audio;
+use log::{debug, error, info, trace};
 use rodio::{buffer::SamplesBuffer, Decoder, OutputStreamHandle, Sink, Source};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::Cursor;
-use std::sync::mpsc::{channel, Sender};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-#[derive(Clone, PartialEq, Debug, Eq, Hash, Copy)]
+#[derive(Clone, PartialEq, Debug, Eq, Hash)]
 enum AmbientTrack {
     None,
     Home,
     WindowHeader,
     Terminal,
+    /// Track registered at runtime (e.g. by a plugin/app) under a string id
+    Custom(String),
+}
+
+impl AmbientTrack {
+    /// Name used when reporting the track to the frontend
+    fn name(&self) -> String {
+        match self {
+            AmbientTrack::None => "none".to_string(),
+            AmbientTrack::Home => "home".to_string(),
+            AmbientTrack::WindowHeader => "window_header".to_string(),
+            AmbientTrack::Terminal => "terminal".to_string(),
+            AmbientTrack::Custom(id) => id.clone(),
+        }
+    }
+
+    /// Inverse of `name`; anything that isn't a built-in is treated as a custom id
+    fn from_name(name: &str) -> Self {
+        match name {
+            "none" => AmbientTrack::None,
+            "home" => AmbientTrack::Home,
+            "window_header" => AmbientTrack::WindowHeader,
+            "terminal" => AmbientTrack::Terminal,
+            id => AmbientTrack::Custom(id.to_string()),
+        }
+    }
+}
+
+/// Volume curve applied over a crossfade
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum FadeCurve {
+    /// Volume proportional to fade progress (dips in perceived loudness mid-fade)
+    Linear,
+    /// Outgoing follows cos(t*π/2), incoming sin(t*π/2), keeping total power constant
+    EqualPower,
+}
+
+impl FadeCurve {
+    /// Gain for a sink whose fade-in progress is `t` (0.0 silent, 1.0 fully in).
+    /// An outgoing sink at fade progress `u` has `t = 1 - u`, so for equal-power
+    /// sin((1 - u)*π/2) == cos(u*π/2).
+    fn gain(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            FadeCurve::Linear => t,
+            FadeCurve::EqualPower => (t * std::f32::consts::FRAC_PI_2).sin(),
+        }
+    }
+}
+
+/// Domain whose ambience plays when the engine starts
+pub const STARTUP_DOMAIN: &str = "osbar-nav";
+
+/// Default crossfade duration in seconds
+pub const DEFAULT_FADE_SECS: f32 = 1.5;
+
+/// Allowed crossfade duration range in seconds
+const MIN_FADE_SECS: f32 = 0.05;
+const MAX_FADE_SECS: f32 = 10.0;
+
+/// Time for a duck to pull the ambience fully down to the duck level
+const DUCK_ATTACK_SECS: f32 = 0.05;
+
+/// Time for ambience to ramp from the duck level back to full once a duck ends
+const DUCK_RELEASE_SECS: f32 = 0.3;
+
+/// Move the ducking gain toward `target` for one tick of `dt` seconds:
+/// quickly when dipping, more gently when recovering
+fn step_duck_gain(gain: f32, target: f32, dt: f32) -> f32 {
+    if target < gain {
+        (gain - dt / DUCK_ATTACK_SECS).max(target)
+    } else {
+        (gain + dt / DUCK_RELEASE_SECS).min(target)
+    }
+}
+
+/// Advance one track's fade progress for a fader tick and return its new volume.
+/// The target track fades toward `active_vol`, every other track toward silence.
+/// While muted the volume is 0 at once and progress restarts from silence, so the
+/// target track fades back in once unmuted.
+fn step_track_fade(
+    progress: &mut f32,
+    is_target: bool,
+    muted: bool,
+    step: f32,
+    active_vol: f32,
+    curve: FadeCurve,
+) -> f32 {
+    if muted {
+        *progress = 0.0;
+        return 0.0;
+    }
+
+    *progress = if is_target {
+        (*progress + step).min(1.0)
+    } else {
+        (*progress - step).max(0.0)
+    };
+    active_vol * curve.gain(*progress)
+}
+
+/// How long `get_position` waits for the fade thread (it checks commands every 10ms)
+const POSITION_QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Playback position of the current ambient track
+#[derive(Debug, Clone, Serialize)]
+pub struct AmbiencePosition {
+    /// Track name ("home", "window_header", "terminal" or a custom id)
+    pub track: String,
+    /// Time played since the track's sink started; keeps counting across loops
+    pub position_secs: f64,
+}
+
+/// Commands sent to the fade thread
+enum FaderCommand {
+    /// Crossfade to a new target track
+    SetTarget(AmbientTrack),
+    /// Change the crossfade duration (seconds) for subsequent transitions
+    SetFadeDuration(f32),
+    /// Change the volume curve used for crossfades
+    SetFadeCurve(FadeCurve),
+    /// Add a new (silent, already playing) sink to the fader's sink map
+    AddTrack(AmbientTrack, Sink),
+    /// Swap in freshly loaded sinks for these tracks; the old ones are stopped
+    ReplaceSinks(HashMap<AmbientTrack, Sink>),
+    /// Pause every sink, keeping positions and the current target
+    Pause,
+    /// Resume every sink and continue fading toward the current target
+    Resume,
+    /// Hold the ambience at `level` (0.0 - 1.0 of its volume) until `until`, then ramp back
+    Duck { level: f32, until: Instant },
+    /// Reply with the playback position of this track's sink (None if it has no sink)
+    QueryPosition(AmbientTrack, Sender<Option<Duration>>),
 }
 
 pub struct AmbienceEngine {
     // We utilize a sender one-way channel to communicate with the fade thread
-    fade_tx: Sender<AmbientTrack>,
+    fade_tx: Sender<FaderCommand>,
     current_track: AmbientTrack,
+    /// Ambience channel volume (0.0 - 1.0), read by the fade thread
+    volume: Arc<Mutex<f32>>,
+    /// Crossfade duration last sent to the fade thread, in seconds
+    fade_duration: f32,
+    /// Kept so tracks can be registered after startup
+    stream_handle: OutputStreamHandle,
+    /// Domain pattern -> track. A domain maps to the entry whose pattern equals its id,
+    /// else the longest pattern it contains; unmapped domains are silent.
+    domain_map: HashMap<String, AmbientTrack>,
+    /// Names of tracks with a ready sink (built-in and runtime-registered)
+    loaded_tracks: Vec<String>,
+    /// Built-in tracks that failed to load or decode at startup
+    load_failures: Vec<AssetLoadFailure>,
+    /// Fade settings last sent to the fade thread, carried over by `rebind`
+    fade_curve: FadeCurve,
+    paused: bool,
+    /// Kept so `rebind` can rebuild every sink on a new output stream
+    master_volume: Arc<Mutex<f32>>,
+    muted: Arc<Mutex<bool>>,
+    asset_dir: PathBuf,
+    /// Encoded data of runtime-registered tracks, by id
+    custom_tracks: HashMap<String, Vec<u8>>,
 }
 
 impl AmbienceEngine {
-    pub fn new(stream_handle: OutputStreamHandle) -> Self {
+    pub fn new(
+        stream_handle: OutputStreamHandle,
+        master_volume: Arc<Mutex<f32>>,
+        muted: Arc<Mutex<bool>>,
+        asset_dir: &Path,
+    ) -> Self {
         let (tx, rx) = channel();
 
         // Load assets and initialize Sinks in the main thread (or we could move this to thread)
         // But creating Sinks usually requires the stream handle.
-        let sinks = Self::initialize_sinks(&stream_handle);
+        let (sinks, load_failures) = Self::initialize_sinks(&stream_handle, asset_dir);
+        let mut loaded_tracks: Vec<String> = sinks.keys().map(AmbientTrack::name).collect();
+        loaded_tracks.sort();
+
+        let volume = Arc::new(Mutex::new(1.0));
+        let volume_for_thread = volume.clone();
+        let master_for_thread = master_volume.clone();
+        let muted_for_thread = muted.clone();
 
         // Spawn the Fade Manager thread
         // This thread owns the Sink handles and manages their volume.
@@ -34,12 +206,21 @@ impl AmbienceEngine {
             let mut target_track = AmbientTrack::None;
             // Map of Track -> Sink
             // Note: Sink is not Clone, but it is Send. We move Sinks into this thread.
-            let sink_map = sinks;
+            let mut sink_map = sinks;
+            // Fade-in progress per track (0.0 silent, 1.0 fully in); the curve maps it to volume
+            let mut progress: HashMap<AmbientTrack, f32> =
+                sink_map.keys().map(|track| (track.clone(), 0.0)).collect();
 
             let mut last_tick = std::time::Instant::now();
-            // 2.0 seconds fade for very smooth transition (user complained of stuttering)
+            // 1.5 seconds fade by default for very smooth transition (user complained of stuttering)
             // Stuttering might be due to step size, so delta-time will help.
-            let fade_duration = 1.5;
+            let mut fade_duration = DEFAULT_FADE_SECS;
+            let mut paused = false;
+            let mut fade_curve = FadeCurve::EqualPower;
+            // Ducking multiplier on the ambience volume, eased toward duck_level until duck_until
+            let mut duck_gain = 1.0;
+            let mut duck_level = 1.0;
+            let mut duck_until: Option<Instant> = None;
 
             loop {
                 // Calculation delta time
@@ -47,29 +228,116 @@ impl AmbienceEngine {
                 let dt = now.duration_since(last_tick).as_secs_f32();
                 last_tick = now;
 
-                // 1. Process pending command
-                if let Ok(new_target) = rx.try_recv() {
-                    println!("[Audio] Fader received target: {:?}", new_target);
-                    target_track = new_target;
+                // 1. Process pending commands
+                loop {
+                    let command = match rx.try_recv() {
+                        Ok(command) => command,
+                        Err(TryRecvError::Empty) => break,
+                        // The engine was dropped (e.g. rebuilt by `rebind`); stop its sinks
+                        Err(TryRecvError::Disconnected) => {
+                            debug!("[Audio] Fader thread exiting");
+                            return;
+                        }
+                    };
+                    match command {
+                        FaderCommand::SetTarget(new_target) => {
+                            trace!("[Audio] Fader received target: {:?}", new_target);
+                            target_track = new_target;
+                        }
+                        FaderCommand::SetFadeDuration(secs) => {
+                            trace!("[Audio] Fader duration set to {:.2}s", secs);
+                            fade_duration = secs;
+                        }
+                        FaderCommand::SetFadeCurve(curve) => {
+                            trace!("[Audio] Fader curve set to {:?}", curve);
+                            fade_curve = curve;
+                        }
+                        FaderCommand::AddTrack(track_id, sink) => {
+                            trace!("[Audio] Fader added track: {:?}", track_id);
+                            if paused {
+                                sink.pause();
+                            }
+                            progress.insert(track_id.clone(), 0.0);
+                            sink_map.insert(track_id, sink);
+                        }
+                        FaderCommand::ReplaceSinks(new_sinks) => {
+                            trace!("[Audio] Fader replacing {} sinks", new_sinks.len());
+                            for (track_id, sink) in new_sinks {
+                                if paused {
+                                    sink.pause();
+                                }
+                                // The new sink fades in from silence (from the start of the file)
+                                progress.insert(track_id.clone(), 0.0);
+                                if let Some(old) = sink_map.insert(track_id, sink) {
+                                    old.stop();
+                                }
+                            }
+                        }
+                        FaderCommand::Pause => {
+                            trace!("[Audio] Fader paused");
+                            paused = true;
+                            for sink in sink_map.values() {
+                                sink.pause();
+                            }
+                        }
+                        FaderCommand::Resume => {
+                            trace!("[Audio] Fader resumed");
+                            paused = false;
+                            for sink in sink_map.values() {
+                                sink.play();
+                            }
+                        }
+                        FaderCommand::Duck { level, until } => {
+                            trace!("[Audio] Fader ducking to {:.2}", level);
+                            duck_level = level;
+                            // Overlapping ducks extend the dip rather than cutting it short
+                            duck_until =
+                                Some(duck_until.map_or(until, |current| current.max(until)));
+                        }
+                        FaderCommand::QueryPosition(track_id, reply) => {
+                            let _ = reply.send(sink_map.get(&track_id).map(Sink::get_pos));
+                        }
+                    }
                 }
 
-                // 2. Adjust volumes (Crossfade logic with delta time)
-                // We want to move volume from 0 -> 1 over fade_duration
-                let vol_change = (1.0 / fade_duration) * dt;
+                // While paused, leave volumes untouched; target changes still apply
+                // so resume fades toward whatever track is current by then.
+                if paused {
+                    thread::sleep(Duration::from_millis(10));
+                    continue;
+                }
+
+                // While muted, every sink is held at 0. Sinks keep playing so their
+                // position is kept, and the target track fades back in on unmute.
+                let muted = muted_for_thread.lock().map(|m| *m).unwrap_or(false);
+
+                // 2. Advance fade progress (delta time) and map it through the curve
+                // Progress moves 0 -> 1 over fade_duration
+                let step = (1.0 / fade_duration) * dt;
+
+                // Master volume is the ceiling for the active track, scaled by the
+                // ambience channel volume
+                let master = master_for_thread.lock().map(|v| *v).unwrap_or(1.0);
+                let channel = volume_for_thread.lock().map(|v| *v).unwrap_or(1.0);
+                let duck_target = match duck_until {
+                    Some(until) if now < until => duck_level,
+                    _ => 1.0,
+                };
+                duck_gain = step_duck_gain(duck_gain, duck_target, dt);
+                let active_vol = master * channel * duck_gain;
 
                 for (track_id, sink) in &sink_map {
-                    let current_vol = sink.volume();
-                    let target_vol = if *track_id == target_track { 1.0 } else { 0.0 };
-
-                    if (current_vol - target_vol).abs() > 0.001 {
-                        let new_vol = if current_vol < target_vol {
-                            (current_vol + vol_change).min(target_vol)
-                        } else {
-                            (current_vol - vol_change).max(target_vol)
-                        };
+                    let t = progress.entry(track_id.clone()).or_insert(0.0);
+                    let new_vol = step_track_fade(
+                        t,
+                        *track_id == target_track,
+                        muted,
+                        step,
+                        active_vol,
+                        fade_curve,
+                    );
+                    if (sink.volume() - new_vol).abs() > f32::EPSILON {
                         sink.set_volume(new_vol);
-                    } else if current_vol != target_vol {
-                        sink.set_volume(target_vol);
                     }
                 }
 
@@ -82,16 +350,32 @@ impl AmbienceEngine {
         let mut engine = Self {
             fade_tx: tx,
             current_track: AmbientTrack::None,
+            volume,
+            fade_duration: DEFAULT_FADE_SECS,
+            stream_handle,
+            domain_map: Self::default_domain_map(),
+            loaded_tracks,
+            load_failures,
+            fade_curve: FadeCurve::EqualPower,
+            paused: false,
+            master_volume,
+            muted,
+            asset_dir: asset_dir.to_path_buf(),
+            custom_tracks: HashMap::new(),
         };
 
         // Start default
-        engine.update_context("osbar-nav");
+        engine.update_context(STARTUP_DOMAIN);
         engine
     }
 
-    fn initialize_sinks(stream_handle: &OutputStreamHandle) -> HashMap<AmbientTrack, Sink> {
-        println!("[Audio] Initializing Virtual Timeline Sinks...");
+    fn initialize_sinks(
+        stream_handle: &OutputStreamHandle,
+        asset_dir: &Path,
+    ) -> (HashMap<AmbientTrack, Sink>, Vec<AssetLoadFailure>) {
+        info!("[Audio] Initializing Virtual Timeline Sinks...");
         let mut sink_map = HashMap::new();
+        let mut failures = Vec::new();
 
         // 1. Load Assets
         let assets = [
@@ -101,62 +385,412 @@ impl AmbienceEngine {
         ];
 
         for (track_id, filename) in assets.iter() {
-            match load_local_audio(filename) {
-                Ok(data) => {
-                    let cursor = Cursor::new(data);
-                    match Decoder::new(cursor) {
-                        Ok(decoder) => {
-                            // Decode to PCM - MUST EXTRACT METADATA BEFORE CONSUMING DECODER
-                            let channels = decoder.channels();
-                            let sample_rate = decoder.sample_rate();
-                            let samples: Vec<f32> = decoder.convert_samples::<f32>().collect();
-                            let buffer = SamplesBuffer::new(channels, sample_rate, samples);
-
-                            // Create Sink
-                            match Sink::try_new(stream_handle) {
-                                Ok(sink) => {
-                                    // Start playing silently
-                                    sink.append(buffer.repeat_infinite());
-                                    sink.set_volume(0.0);
-                                    sink.play();
-                                    sink_map.insert(*track_id, sink);
-                                    println!("[Audio] Sink ready (silent): {:?}", track_id);
-                                }
-                                Err(e) => eprintln!("[Audio] Sink creation failed: {}", e),
-                            }
-                        }
-                        Err(e) => eprintln!("[Audio] Decode failed for {}: {}", filename, e),
-                    }
+            let result = load_local_audio(asset_dir, filename)
+                .map_err(|e| format!("Asset load failed: {}", e))
+                .and_then(|data| Self::create_silent_sink(stream_handle, data));
+
+            match result {
+                Ok(sink) => {
+                    sink_map.insert(track_id.clone(), sink);
+                    trace!("[Audio] Sink ready (silent): {:?}", track_id);
+                }
+                Err(e) => {
+                    error!("[Audio] {} ({})", e, filename);
+                    failures.push(AssetLoadFailure {
+                        id: track_id.name(),
+                        path: asset_dir.join(filename).display().to_string(),
+                        error: e,
+                    });
                 }
-                Err(e) => eprintln!("[Audio] Asset load failed for {}: {}", filename, e),
             }
         }
 
-        sink_map
+        (sink_map, failures)
+    }
+
+    /// Initial domain mappings for the built-in tracks
+    fn default_domain_map() -> HashMap<String, AmbientTrack> {
+        [
+            ("osbar", AmbientTrack::Home),
+            ("header", AmbientTrack::WindowHeader),
+            ("terminal", AmbientTrack::Terminal),
+        ]
+        .into_iter()
+        .map(|(pattern, track)| (pattern.to_string(), track))
+        .collect()
+    }
+
+    /// Decode audio bytes to PCM and start them looping on a silent Sink
+    fn create_silent_sink(
+        stream_handle: &OutputStreamHandle,
+        data: Vec<u8>,
+    ) -> Result<Sink, String> {
+        let decoder =
+            Decoder::new(Cursor::new(data)).map_err(|e| format!("Decode failed: {}", e))?;
+
+        // Decode to PCM - MUST EXTRACT METADATA BEFORE CONSUMING DECODER
+        let channels = decoder.channels();
+        let sample_rate = decoder.sample_rate();
+        let samples: Vec<f32> = decoder.convert_samples::<f32>().collect();
+        let buffer = SamplesBuffer::new(channels, sample_rate, samples);
+
+        let sink =
+            Sink::try_new(stream_handle).map_err(|e| format!("Sink creation failed: {}", e))?;
+
+        // Start playing silently
+        sink.append(buffer.repeat_infinite());
+        sink.set_volume(0.0);
+        sink.play();
+        Ok(sink)
+    }
+
+    /// Register an additional looping track at runtime.
+    /// Domains whose id contains `domain_pattern` will fade to this track.
+    pub fn register_track(
+        &mut self,
+        track_id: &str,
+        domain_pattern: &str,
+        data: Vec<u8>,
+    ) -> Result<(), String> {
+        let track = self.add_track(track_id, data)?;
+
+        // Re-registering a pattern points it at the newest track
+        self.domain_map.insert(domain_pattern.to_string(), track);
+
+        debug!(
+            "[Audio] Registered ambient track '{}' for domains matching '{}'",
+            track_id, domain_pattern
+        );
+        Ok(())
+    }
+
+    /// Start a custom track silently on the fader and remember its data for `rebind`
+    fn add_track(&mut self, track_id: &str, data: Vec<u8>) -> Result<AmbientTrack, String> {
+        let track = AmbientTrack::Custom(track_id.to_string());
+        let sink = Self::create_silent_sink(&self.stream_handle, data.clone())
+            .map_err(|e| format!("{} for track '{}'", e, track_id))?;
+
+        self.fade_tx
+            .send(FaderCommand::AddTrack(track.clone(), sink))
+            .map_err(|_| "Ambience fade thread is not running".to_string())?;
+
+        self.custom_tracks.insert(track_id.to_string(), data);
+        if !self.loaded_tracks.iter().any(|name| name == track_id) {
+            self.loaded_tracks.push(track_id.to_string());
+        }
+        Ok(track)
+    }
+
+    /// Rebuild every sink on a new output stream (e.g. after switching devices).
+    ///
+    /// Built-in tracks are reloaded from disk and custom tracks from their kept data;
+    /// volume, fade settings, domain mappings, pause state and the current track carry over.
+    pub fn rebind(&mut self, stream_handle: OutputStreamHandle) {
+        let mut fresh = Self::new(
+            stream_handle,
+            self.master_volume.clone(),
+            self.muted.clone(),
+            &self.asset_dir,
+        );
+        fresh.set_volume(self.volume());
+        fresh.set_fade_duration(self.fade_duration);
+        fresh.set_fade_curve(self.fade_curve);
+
+        for (track_id, data) in std::mem::take(&mut self.custom_tracks) {
+            if let Err(e) = fresh.add_track(&track_id, data) {
+                error!("[Audio] Failed to restore ambient track: {}", e);
+            }
+        }
+        fresh.domain_map = std::mem::take(&mut self.domain_map);
+        fresh.set_target(self.current_track.clone());
+        if self.paused {
+            fresh.pause();
+        }
+
+        // Dropping the old engine closes its channel, which stops its fade thread
+        *self = fresh;
+        info!("[Audio] Ambience rebuilt on new output stream");
+    }
+
+    /// Reload the built-in tracks from disk and swap them into the fader, keeping the
+    /// current track, volume and mappings. Returns how many reloaded; a track that
+    /// fails keeps playing its previous version.
+    pub fn reload_assets(&mut self) -> usize {
+        let (sinks, load_failures) = Self::initialize_sinks(&self.stream_handle, &self.asset_dir);
+        let reloaded = sinks.len();
+
+        for track in sinks.keys() {
+            let name = track.name();
+            if !self.loaded_tracks.contains(&name) {
+                self.loaded_tracks.push(name);
+            }
+        }
+        self.load_failures = load_failures;
+
+        if self
+            .fade_tx
+            .send(FaderCommand::ReplaceSinks(sinks))
+            .is_err()
+        {
+            error!("[Audio] Ambience fade thread is not running");
+            return 0;
+        }
+        info!("[Audio] Reloaded {} ambient tracks", reloaded);
+        reloaded
+    }
+
+    pub fn loaded_tracks(&self) -> &[String] {
+        &self.loaded_tracks
+    }
+
+    pub fn load_failures(&self) -> &[AssetLoadFailure] {
+        &self.load_failures
+    }
+
+    /// Set the ambience channel volume. Clamped to 0.0 - 1.0.
+    pub fn set_volume(&self, volume: f32) {
+        if let Ok(mut v) = self.volume.lock() {
+            *v = volume.clamp(0.0, 1.0);
+        }
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.volume.lock().map(|v| *v).unwrap_or(1.0)
+    }
+
+    /// Set the crossfade duration used for subsequent transitions.
+    /// Clamped to 0.05 - 10.0 seconds.
+    pub fn set_fade_duration(&mut self, secs: f32) {
+        let secs = secs.clamp(MIN_FADE_SECS, MAX_FADE_SECS);
+        self.fade_duration = secs;
+        let _ = self.fade_tx.send(FaderCommand::SetFadeDuration(secs));
+    }
+
+    pub fn fade_duration(&self) -> f32 {
+        self.fade_duration
+    }
+
+    /// Set the volume curve used for subsequent crossfades
+    pub fn set_fade_curve(&mut self, curve: FadeCurve) {
+        self.fade_curve = curve;
+        let _ = self.fade_tx.send(FaderCommand::SetFadeCurve(curve));
+    }
+
+    /// Pause all ambience. The current track stays selected for `resume`.
+    pub fn pause(&mut self) {
+        self.paused = true;
+        let _ = self.fade_tx.send(FaderCommand::Pause);
+    }
+
+    /// Resume ambience after `pause`, fading toward the current track
+    pub fn resume(&mut self) {
+        self.paused = false;
+        let _ = self.fade_tx.send(FaderCommand::Resume);
+    }
+
+    /// Map domains matching `domain_pattern` to a track by name
+    /// ("home", "window_header", "terminal", "none", or a registered custom id).
+    pub fn map_domain(&mut self, domain_pattern: &str, track_id: &str) -> Result<(), String> {
+        let track = AmbientTrack::from_name(track_id);
+        if let AmbientTrack::Custom(id) = &track {
+            if !self.loaded_tracks.contains(id) {
+                return Err(format!("Unknown ambient track '{}'", track_id));
+            }
+        }
+
+        debug!(
+            "[Audio] Mapped domains matching '{}' to ambience {:?}",
+            domain_pattern, track
+        );
+        self.domain_map.insert(domain_pattern.to_string(), track);
+        Ok(())
+    }
+
+    /// Dip the ambience to `level` of its volume for `duration`, then ramp back up
+    pub fn duck(&self, level: f32, duration: Duration) {
+        let _ = self.fade_tx.send(FaderCommand::Duck {
+            level: level.clamp(0.0, 1.0),
+            until: Instant::now() + duration,
+        });
+    }
+
+    /// Current track and how far it has played; None while no track is selected
+    pub fn get_position(&self) -> Option<AmbiencePosition> {
+        if self.current_track == AmbientTrack::None {
+            return None;
+        }
+        let (reply_tx, reply_rx) = channel();
+        self.fade_tx
+            .send(FaderCommand::QueryPosition(
+                self.current_track.clone(),
+                reply_tx,
+            ))
+            .ok()?;
+        let position = reply_rx.recv_timeout(POSITION_QUERY_TIMEOUT).ok()??;
+        Some(AmbiencePosition {
+            track: self.current_track.name(),
+            position_secs: position.as_secs_f64(),
+        })
+    }
+
+    /// Fade every track out. The next `update_context` fades the domain's track back in.
+    pub fn silence(&mut self) {
+        debug!("[Audio] Silencing ambience");
+        self.set_target(AmbientTrack::None);
+    }
+
+    /// Make `track` current and tell the fade thread to crossfade to it
+    fn set_target(&mut self, track: AmbientTrack) {
+        self.current_track = track.clone();
+        let _ = self.fade_tx.send(FaderCommand::SetTarget(track));
     }
 
     /// Called when the active domain changes.
     pub fn update_context(&mut self, domain_id: &str) {
-        // Determine the target track based on domain string patterns.
-        let target_track = if domain_id.contains("osbar") {
-            AmbientTrack::Home
-        } else if domain_id.contains("header") {
-            AmbientTrack::WindowHeader
-        } else if domain_id.contains("terminal") {
-            AmbientTrack::Terminal
-        } else {
-            AmbientTrack::Terminal
-        };
+        let target_track = resolve_track(&self.domain_map, domain_id);
 
         // Only switch if the track actually changes
         if target_track != self.current_track {
-            println!(
+            debug!(
                 "[Audio] Switching ambience: {:?} -> {:?}",
                 self.current_track, target_track
             );
-            self.current_track = target_track;
             // Send command to fade thread
-            let _ = self.fade_tx.send(target_track);
+            self.set_target(target_track);
+        }
+    }
+}
+
+/// Resolve the track for a domain: exact id match first, then the longest
+/// contained pattern (ties broken alphabetically), else silence.
+///
+/// This replaces the old fixed osbar > header > terminal order. An id containing
+/// several built-in patterns now follows pattern length, so "osbar-header" gets the
+/// header track and "terminal-header" the terminal track. Map the full id (or a longer
+/// pattern) with `map_domain_ambience` to pick a different track.
+fn resolve_track(domain_map: &HashMap<String, AmbientTrack>, domain_id: &str) -> AmbientTrack {
+    if let Some(track) = domain_map.get(domain_id) {
+        return track.clone();
+    }
+
+    domain_map
+        .iter()
+        .filter(|(pattern, _)| domain_id.contains(pattern.as_str()))
+        .max_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then_with(|| b.cmp(a)))
+        .map(|(_, track)| track.clone())
+        .unwrap_or(AmbientTrack::None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duck_gain_dips_fast_and_recovers_slowly() {
+        // One 10ms tick covers a fifth of the attack but only a thirtieth of the release
+        let dipped = step_duck_gain(1.0, 0.3, 0.01);
+        assert!((dipped - 0.8).abs() < 1e-6);
+        assert_eq!(step_duck_gain(0.31, 0.3, 0.01), 0.3);
+
+        let recovering = step_duck_gain(0.3, 1.0, 0.01);
+        assert!(recovering > 0.3 && recovering < 0.34);
+        assert_eq!(step_duck_gain(0.3, 1.0, DUCK_RELEASE_SECS), 1.0);
+    }
+
+    #[test]
+    fn test_unmute_fades_target_back_to_full_volume() {
+        let (master, channel) = (0.8, 0.5);
+        let active_vol = master * channel;
+        let step = 0.01 / DEFAULT_FADE_SECS;
+        let curve = FadeCurve::EqualPower;
+
+        // Home is fully faded in, Terminal silent
+        let mut home = 1.0;
+        let mut terminal = 0.0;
+
+        // Muting drops both to 0 immediately
+        assert_eq!(
+            step_track_fade(&mut home, true, true, step, active_vol, curve),
+            0.0
+        );
+        assert_eq!(
+            step_track_fade(&mut terminal, false, true, step, active_vol, curve),
+            0.0
+        );
+
+        // After unmute Home ramps back up over the fade; Terminal stays silent
+        let mut last = 0.0;
+        for _ in 0..(DEFAULT_FADE_SECS / 0.01) as usize + 10 {
+            let home_vol = step_track_fade(&mut home, true, false, step, active_vol, curve);
+            assert!(home_vol >= last);
+            last = home_vol;
+            assert_eq!(
+                step_track_fade(&mut terminal, false, false, step, active_vol, curve),
+                0.0
+            );
+        }
+        assert!((last - active_vol).abs() < 1e-6, "Ended at {}", last);
+    }
+
+    #[test]
+    fn test_fade_curves_hit_endpoints() {
+        for curve in [FadeCurve::Linear, FadeCurve::EqualPower] {
+            assert_eq!(curve.gain(0.0), 0.0);
+            assert!((curve.gain(1.0) - 1.0).abs() < 1e-6);
         }
     }
+
+    #[test]
+    fn test_equal_power_keeps_midpoint_power() {
+        let incoming = FadeCurve::EqualPower.gain(0.5);
+        let outgoing = FadeCurve::EqualPower.gain(1.0 - 0.5);
+        let power = incoming * incoming + outgoing * outgoing;
+        assert!((power - 1.0).abs() < 1e-6);
+
+        // Linear dips to half power at the midpoint
+        let linear = FadeCurve::Linear.gain(0.5);
+        assert!((linear * linear * 2.0 - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_default_domain_mappings() {
+        let map = AmbienceEngine::default_domain_map();
+        assert_eq!(resolve_track(&map, "osbar-nav"), AmbientTrack::Home);
+        assert_eq!(
+            resolve_track(&map, "win-1234-header-nav"),
+            AmbientTrack::WindowHeader
+        );
+        assert_eq!(resolve_track(&map, "terminal-1"), AmbientTrack::Terminal);
+        // Unmapped domains fall back to silence rather than the terminal loop
+        assert_eq!(resolve_track(&map, "testing-dummy-nav"), AmbientTrack::None);
+    }
+
+    #[test]
+    fn test_exact_and_longest_pattern_win() {
+        let mut map = AmbienceEngine::default_domain_map();
+        map.insert("terminal-header".to_string(), AmbientTrack::Home);
+        map.insert("osbar-nav".to_string(), AmbientTrack::Terminal);
+
+        assert_eq!(
+            resolve_track(&map, "terminal-header-nav"),
+            AmbientTrack::Home
+        );
+        assert_eq!(resolve_track(&map, "osbar-nav"), AmbientTrack::Terminal);
+        assert_eq!(resolve_track(&map, "osbar-other"), AmbientTrack::Home);
+    }
+
+    #[test]
+    fn test_multiple_builtin_patterns_use_longest() {
+        let map = AmbienceEngine::default_domain_map();
+
+        // "header" (6) beats "osbar" (5); "terminal" (8) beats "header" (6)
+        assert_eq!(
+            resolve_track(&map, "osbar-header-nav"),
+            AmbientTrack::WindowHeader
+        );
+        assert_eq!(
+            resolve_track(&map, "terminal-header-nav"),
+            AmbientTrack::Terminal
+        );
+    }
 }
@@ -0,0 +1,107 @@
+// Output device selection. rodio's OutputStream has to stay on the thread that
+// opened it, so one thread owns the live stream and hands out handles to it.
+
+use log::{info, warn};
+use rodio::cpal::traits::HostTrait;
+use rodio::{cpal, DeviceTrait, OutputStream, OutputStreamHandle};
+use serde::Serialize;
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+
+/// Names of the output devices on the default audio host
+pub fn list_output_devices() -> Result<Vec<String>, String> {
+    let devices = cpal::default_host()
+        .output_devices()
+        .map_err(|e| format!("Failed to list output devices: {}", e))?;
+    Ok(devices.filter_map(|device| device.name().ok()).collect())
+}
+
+/// Which device an output stream ended up on
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceSelection {
+    /// Device now playing (None if its name couldn't be read)
+    pub device: Option<String>,
+    /// The requested device was missing or failed to open, so the default is used
+    pub fell_back: bool,
+}
+
+type OpenResult = Result<(OutputStreamHandle, DeviceSelection), String>;
+
+struct OpenRequest {
+    device: Option<String>,
+    reply: Sender<OpenResult>,
+}
+
+/// Owns the live OutputStream on a dedicated thread. Opening a new device replaces
+/// (and closes) the previous stream once the new one is up.
+pub struct OutputDevice {
+    tx: Sender<OpenRequest>,
+}
+
+impl OutputDevice {
+    pub fn spawn() -> Self {
+        let (tx, rx) = channel::<OpenRequest>();
+        thread::spawn(move || {
+            // Held only to keep the stream alive; replaced on each successful open
+            let mut _stream: Option<OutputStream> = None;
+            for request in rx {
+                let result =
+                    open_stream(request.device.as_deref()).map(|(stream, handle, selection)| {
+                        _stream = Some(stream);
+                        (handle, selection)
+                    });
+                let _ = request.reply.send(result);
+            }
+        });
+        Self { tx }
+    }
+
+    /// Open `device` by name (None for the system default) and return a handle to it
+    pub fn open(&self, device: Option<&str>) -> OpenResult {
+        let (reply, response) = channel();
+        let request = OpenRequest {
+            device: device.map(str::to_string),
+            reply,
+        };
+        self.tx
+            .send(request)
+            .map_err(|_| "Audio output thread is not running".to_string())?;
+        response
+            .recv()
+            .map_err(|_| "Audio output thread is not running".to_string())?
+    }
+}
+
+/// Open the named device, falling back to the default if it's gone or won't open
+fn open_stream(
+    device: Option<&str>,
+) -> Result<(OutputStream, OutputStreamHandle, DeviceSelection), String> {
+    if let Some(name) = device {
+        let found = cpal::default_host()
+            .output_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|d| d.name().is_ok_and(|n| n == name)));
+        match found.map(|d| OutputStream::try_from_device(&d)) {
+            Some(Ok((stream, handle))) => {
+                info!("[Audio] Output device set to '{}'", name);
+                let selection = DeviceSelection {
+                    device: Some(name.to_string()),
+                    fell_back: false,
+                };
+                return Ok((stream, handle, selection));
+            }
+            Some(Err(e)) => warn!("[Audio] Failed to open output device '{}': {}", name, e),
+            None => warn!("[Audio] Output device '{}' not found", name),
+        }
+    }
+
+    let (stream, handle) = OutputStream::try_default()
+        .map_err(|e| format!("Failed to open default audio output: {}", e))?;
+    let selection = DeviceSelection {
+        device: cpal::default_host()
+            .default_output_device()
+            .and_then(|d| d.name().ok()),
+        fell_back: device.is_some(),
+    };
+    Ok((stream, handle, selection))
+}
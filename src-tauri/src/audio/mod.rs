@@ -1,48 +1,431 @@
 pub mod ambience;
+pub mod device;
 pub mod sfx;
 
-use self::ambience::AmbienceEngine;
+use self::ambience::{AmbienceEngine, AmbiencePosition, FadeCurve, DEFAULT_FADE_SECS};
+use self::device::{DeviceSelection, OutputDevice};
 use self::sfx::SfxEngine;
-use rodio::{OutputStream, OutputStreamHandle};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// An audio asset that could not be loaded at startup
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetLoadFailure {
+    /// Logical id (SFX id or ambient track name)
+    pub id: String,
+    /// Path that was attempted
+    pub path: String,
+    /// Read or decode error
+    pub error: String,
+}
+
+/// Which audio assets are available, so the frontend can warn about degraded audio
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioLoadReport {
+    pub sfx_loaded: Vec<String>,
+    pub sfx_failed: Vec<AssetLoadFailure>,
+    pub ambience_loaded: Vec<String>,
+    pub ambience_failed: Vec<AssetLoadFailure>,
+}
+
+/// Fraction of its volume the ambience dips to while a ducking SFX plays
+pub const DEFAULT_DUCK_LEVEL: f32 = 0.3;
+
+/// How long the ambience stays ducked after a ducking SFX starts
+const DUCK_DURATION: Duration = Duration::from_millis(400);
+
+/// Settings file written by `save_settings`, inside the app config dir
+pub const SETTINGS_FILE: &str = "audio_settings.json";
+
+/// User-adjustable audio preferences that survive restarts.
+/// Missing fields (or a missing file) keep the built-in defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master_volume: f32,
+    pub sfx_volume: f32,
+    pub ambience_volume: f32,
+    pub fade_duration_secs: f32,
+    pub muted: bool,
+    pub enabled: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            sfx_volume: 1.0,
+            ambience_volume: 1.0,
+            fade_duration_secs: DEFAULT_FADE_SECS,
+            muted: false,
+            enabled: true,
+        }
+    }
+}
 
 /// Central controller for the audio system.
 pub struct AudioSystem {
-    // Only keep the handle, which is Send + Sync (internally Arc)
-    stream_handle: OutputStreamHandle,
+    /// Thread owning the live OutputStream; the engines only keep handles to it,
+    /// which are Send + Sync (internally Arc)
+    output: OutputDevice,
+
+    /// Master volume (0.0 - 1.0), shared with both engines
+    master_volume: Arc<Mutex<f32>>,
+    /// Mute flag, shared with the ambience fader (sinks keep playing silently)
+    muted: Arc<Mutex<bool>>,
+    /// Master switch: when off, SFX and domain changes are ignored and ambience fades to silence
+    enabled: bool,
+    /// Latest domain context, kept while disabled so ambience resumes for it
+    current_domain: String,
+    /// Where `save_settings`/`load_settings` keep preferences; None disables persistence
+    settings_path: Option<PathBuf>,
+    /// SFX ids that duck the ambience when played (domain switches by default)
+    duck_ids: HashSet<String>,
+    /// Ambience level while ducked (0.0 - 1.0 of its volume)
+    duck_level: f32,
 
     sfx: SfxEngine,
     ambience: AmbienceEngine,
 }
 
 impl AudioSystem {
-    /// Returns (AudioSystem, OutputStream).
-    /// IMPORTANT: The caller MUST keep the OutputStream alive, but it cannot be shared across threads.
-    pub fn new() -> (Self, OutputStream) {
+    /// Opens the default output device; the stream lives on its own thread.
+    ///
+    /// `audio_dir` is the root audio asset directory containing `UI/` and `ambient/`.
+    /// Saved preferences are restored from `settings_path` if the file exists.
+    pub fn new(audio_dir: &Path, settings_path: Option<PathBuf>) -> Self {
         // Initialize audio device
-        let (stream, stream_handle) =
-            OutputStream::try_default().expect("Failed to get default audio output");
+        let output = OutputDevice::spawn();
+        let (stream_handle, _) = output
+            .open(None)
+            .expect("Failed to get default audio output");
+
+        let master_volume = Arc::new(Mutex::new(1.0));
+        let muted = Arc::new(Mutex::new(false));
+
+        let sfx = SfxEngine::new(
+            stream_handle.clone(),
+            master_volume.clone(),
+            &audio_dir.join("UI"),
+        );
+        let ambience = AmbienceEngine::new(
+            stream_handle,
+            master_volume.clone(),
+            muted.clone(),
+            &audio_dir.join("ambient"),
+        );
+
+        let mut system = Self {
+            output,
+            master_volume,
+            muted,
+            enabled: true,
+            current_domain: ambience::STARTUP_DOMAIN.to_string(),
+            settings_path,
+            duck_ids: HashSet::from(["domain_switch".to_string()]),
+            duck_level: DEFAULT_DUCK_LEVEL,
+            sfx,
+            ambience,
+        };
+        if let Err(e) = system.load_settings() {
+            warn!("[Audio] Keeping default settings: {}", e);
+        }
+
+        info!("[Audio] System initialized");
 
-        let sfx = SfxEngine::new(stream_handle.clone());
-        let ambience = AmbienceEngine::new(stream_handle.clone());
+        system
+    }
+
+    /// Switch playback to the named output device (None for the system default).
+    ///
+    /// SFX move to the new stream and ambience is rebuilt on it, keeping volumes and
+    /// the current track. A missing device falls back to the default (`fell_back`).
+    pub fn set_output_device(&mut self, device: Option<&str>) -> Result<DeviceSelection, String> {
+        let (stream_handle, selection) = self.output.open(device)?;
+        self.sfx.set_stream_handle(stream_handle.clone());
+        self.ambience.rebind(stream_handle);
+        Ok(selection)
+    }
+
+    /// Current preferences, as `save_settings` would write them
+    pub fn settings(&self) -> AudioSettings {
+        AudioSettings {
+            master_volume: self.master_volume.lock().map(|v| *v).unwrap_or(1.0),
+            sfx_volume: self.sfx.volume(),
+            ambience_volume: self.ambience.volume(),
+            fade_duration_secs: self.ambience.fade_duration(),
+            muted: self.is_muted(),
+            enabled: self.enabled,
+        }
+    }
 
-        println!("[Audio] System initialized");
+    /// Apply a full set of preferences
+    pub fn apply_settings(&mut self, settings: &AudioSettings) {
+        self.set_master_volume(settings.master_volume);
+        self.set_sfx_volume(settings.sfx_volume);
+        self.set_ambience_volume(settings.ambience_volume);
+        self.set_fade_duration(settings.fade_duration_secs);
+        self.set_muted(settings.muted);
+        self.set_enabled(settings.enabled);
+    }
+
+    /// Whether preferences have been saved before (they then win over hyphaeic.toml)
+    pub fn has_saved_settings(&self) -> bool {
+        self.settings_path.as_deref().is_some_and(Path::exists)
+    }
+
+    /// Write the current preferences to the settings file
+    pub fn save_settings(&self) -> Result<(), String> {
+        let path = self
+            .settings_path
+            .as_deref()
+            .ok_or("No config directory to save audio settings in")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let json = serde_json::to_string_pretty(&self.settings())
+            .map_err(|e| format!("Failed to serialize audio settings: {}", e))?;
+        std::fs::write(path, json)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        debug!("[Audio] Saved settings to {}", path.display());
+        Ok(())
+    }
+
+    /// Restore preferences from the settings file; returns false if there is none
+    pub fn load_settings(&mut self) -> Result<bool, String> {
+        let Some(path) = self.settings_path.clone().filter(|path| path.exists()) else {
+            return Ok(false);
+        };
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let settings: AudioSettings = serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        self.apply_settings(&settings);
+        info!("[Audio] Restored settings from {}", path.display());
+        Ok(true)
+    }
+
+    /// Scale the whole soundscape (SFX and ambience). Clamped to 0.0 - 1.0.
+    pub fn set_master_volume(&self, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        if let Ok(mut master) = self.master_volume.lock() {
+            *master = volume;
+        }
+        debug!("[Audio] Master volume set to {:.2}", volume);
+    }
+
+    /// Set the SFX channel volume (0.0 - 1.0)
+    pub fn set_sfx_volume(&mut self, volume: f32) {
+        self.sfx.set_volume(volume);
+        debug!("[Audio] SFX volume set to {:.2}", volume.clamp(0.0, 1.0));
+    }
+
+    /// Set the minimum interval between repeat plays of an SFX id (capped at 1000 ms)
+    pub fn set_sfx_min_interval(&mut self, id: &str, ms: u64) {
+        let ms = ms.min(1000);
+        self.sfx.set_min_interval(id, Duration::from_millis(ms));
+        debug!("[Audio] SFX '{}' min interval set to {}ms", id, ms);
+    }
+
+    /// Set the ambience channel volume (0.0 - 1.0)
+    pub fn set_ambience_volume(&self, volume: f32) {
+        self.ambience.set_volume(volume);
+        debug!(
+            "[Audio] Ambience volume set to {:.2}",
+            volume.clamp(0.0, 1.0)
+        );
+    }
+
+    /// Register an additional ambient loop for domains matching `domain_pattern`
+    pub fn register_ambient_track(
+        &mut self,
+        track_id: &str,
+        domain_pattern: &str,
+        data: Vec<u8>,
+    ) -> Result<(), String> {
+        self.ambience.register_track(track_id, domain_pattern, data)
+    }
+
+    /// Map domains matching `domain_pattern` to an ambient track by name
+    pub fn map_domain_ambience(
+        &mut self,
+        domain_pattern: &str,
+        track_id: &str,
+    ) -> Result<(), String> {
+        self.ambience.map_domain(domain_pattern, track_id)
+    }
+
+    /// Ids of the SFX that loaded and can be played, sorted
+    pub fn sfx_ids(&self) -> Vec<String> {
+        self.sfx.loaded_ids()
+    }
 
-        (
-            Self {
-                stream_handle,
-                sfx,
-                ambience,
-            },
-            stream,
-        )
+    pub fn has_sfx(&self, id: &str) -> bool {
+        self.sfx.is_loaded(id)
+    }
+
+    /// Report which SFX and ambient tracks loaded and which failed (with attempted paths)
+    pub fn get_load_report(&self) -> AudioLoadReport {
+        AudioLoadReport {
+            sfx_loaded: self.sfx.loaded_ids(),
+            sfx_failed: self.sfx.load_failures().to_vec(),
+            ambience_loaded: self.ambience.loaded_tracks().to_vec(),
+            ambience_failed: self.ambience.load_failures().to_vec(),
+        }
+    }
+
+    /// Set the ambience crossfade duration in seconds (0.05 - 10.0)
+    pub fn set_fade_duration(&mut self, secs: f32) {
+        self.ambience.set_fade_duration(secs);
+    }
+
+    /// Choose the volume curve for ambience crossfades
+    pub fn set_fade_curve(&mut self, curve: FadeCurve) {
+        self.ambience.set_fade_curve(curve);
+        debug!("[Audio] Fade curve set to {:?}", curve);
+    }
+
+    /// Pause background ambience (e.g. for focus mode or video playback)
+    pub fn pause_ambience(&mut self) {
+        self.ambience.pause();
+    }
+
+    /// Resume background ambience with the same track selection
+    pub fn resume_ambience(&mut self) {
+        self.ambience.resume();
+    }
+
+    /// Current ambient track and its playback position, for visualizers
+    pub fn ambience_position(&self) -> Option<AmbiencePosition> {
+        self.ambience.get_position()
+    }
+
+    /// Silence everything instantly without tearing down the audio graph.
+    /// Ambience sinks keep their playback position and fade back in on unmute.
+    pub fn set_muted(&self, muted: bool) {
+        if let Ok(mut flag) = self.muted.lock() {
+            *flag = muted;
+        }
+        debug!("[Audio] Muted: {}", muted);
+    }
+
+    fn is_muted(&self) -> bool {
+        self.muted.lock().map(|m| *m).unwrap_or(false)
+    }
+
+    /// Turn all audio on or off. Off fades ambience to silence and drops SFX
+    /// (including `domain_switch`); on resumes ambience for the current domain.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled == self.enabled {
+            return;
+        }
+        self.enabled = enabled;
+        if enabled {
+            self.ambience.update_context(&self.current_domain);
+        } else {
+            self.ambience.silence();
+        }
+        info!("[Audio] Audio enabled: {}", enabled);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Whether SFX should play right now (enabled and not muted)
+    fn sfx_audible(&self) -> bool {
+        self.enabled && !self.is_muted()
+    }
+
+    /// Choose which SFX ids duck the ambience and how far (level clamped to 0.0 - 1.0).
+    /// An empty list turns ducking off.
+    pub fn set_ducking(&mut self, ids: Vec<String>, level: f32) {
+        self.duck_ids = ids.into_iter().collect();
+        self.duck_level = level.clamp(0.0, 1.0);
+        debug!(
+            "[Audio] Ducking for {:?} at {:.2}",
+            self.duck_ids, self.duck_level
+        );
+    }
+
+    /// Dip the ambience if `id` is one of the ducking SFX. Only called once the sound
+    /// is queued, so a throttled or missing SFX doesn't dip the ambience.
+    fn duck_for(&self, id: &str) {
+        if self.duck_ids.contains(id) {
+            self.ambience.duck(self.duck_level, DUCK_DURATION);
+        }
     }
 
     pub fn play_sfx(&self, id: &str) {
-        self.sfx.play(id);
+        if !self.sfx_audible() {
+            return;
+        }
+        if self.sfx.play(id) {
+            self.duck_for(id);
+        }
+    }
+
+    /// Play an SFX at a different speed/pitch (1.0 = normal; also changes duration)
+    pub fn play_sfx_with_speed(&self, id: &str, speed: f32) {
+        if !self.sfx_audible() {
+            return;
+        }
+        if self.sfx.play_with_speed(id, speed) {
+            self.duck_for(id);
+        }
+    }
+
+    /// Play an SFX panned between -1.0 (left) and 1.0 (right)
+    pub fn play_sfx_panned(&self, id: &str, pan: f32) {
+        if !self.sfx_audible() {
+            return;
+        }
+        if self.sfx.play_panned(id, pan) {
+            self.duck_for(id);
+        }
+    }
+
+    /// Play an SFX with its own volume (0.0 - 1.0, on top of the SFX and master
+    /// volumes) and pan (-1.0 left to 1.0 right)
+    pub fn play_sfx_ex(&self, id: &str, volume: f32, pan: f32) {
+        if !self.sfx_audible() {
+            return;
+        }
+        if self.sfx.play_with_gain(id, volume, pan) {
+            self.duck_for(id);
+        }
+    }
+
+    /// Re-read SFX and built-in ambience files from disk; returns how many loaded.
+    /// The current ambient track, volumes and mappings are kept.
+    pub fn reload_assets(&mut self) -> usize {
+        let sfx = self.sfx.reload_assets();
+        let ambience = self.ambience.reload_assets();
+        info!(
+            "[Audio] Reloaded {} SFX and {} ambient assets",
+            sfx, ambience
+        );
+        sfx + ambience
+    }
+
+    /// Stop every SFX currently playing (ambience is unaffected); returns how many stopped
+    pub fn stop_all_sfx(&self) -> usize {
+        let stopped = self.sfx.stop_all();
+        debug!("[Audio] Stopped {} SFX", stopped);
+        stopped
     }
 
     pub fn on_domain_change(&mut self, domain_id: &str) {
+        self.current_domain = domain_id.to_string();
+        if !self.enabled {
+            return;
+        }
+
         // SFX feedback for the switch itself
         self.play_sfx("domain_switch");
 
@@ -53,3 +436,22 @@ impl AudioSystem {
 
 // Global state wrapper
 pub struct AudioState(pub Arc<Mutex<AudioSystem>>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_file_defaults() {
+        let settings: AudioSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings, AudioSettings::default());
+        assert_eq!(settings.fade_duration_secs, DEFAULT_FADE_SECS);
+        assert!(settings.enabled && !settings.muted);
+
+        let settings: AudioSettings =
+            serde_json::from_str(r#"{ "master_volume": 0.25, "muted": true }"#).unwrap();
+        assert_eq!(settings.master_volume, 0.25);
+        assert!(settings.muted);
+        assert_eq!(settings.sfx_volume, 1.0);
+    }
+}
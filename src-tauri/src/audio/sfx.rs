@@ -1,22 +1,63 @@
-use rodio::{Decoder, OutputStreamHandle, Source};
-use std::collections::HashMap;
+use super::AssetLoadFailure;
+use log::{error, info, trace};
+use rodio::{buffer::SamplesBuffer, Decoder, OutputStreamHandle, Sample, Sink, Source};
+use std::collections::{HashMap, VecDeque};
 use std::fs::File;
 use std::io::BufReader;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
+
+/// Longest SFX we keep decoded in memory; UI sounds are well under this
+const MAX_SFX_SECS: f32 = 5.0;
+
+/// Most SFX kept playing at once; starting another stops the oldest
+const MAX_ACTIVE_SFX: usize = 32;
+
+/// A sound decoded once at preload time
+struct DecodedSample {
+    channels: u16,
+    sample_rate: u32,
+    pcm: Vec<f32>,
+}
 
 /// SfxEngine handles low-latency sound effects.
-/// It pre-loads samples into memory to ensure instant playback.
+/// It pre-decodes samples into memory to ensure instant playback.
 pub struct SfxEngine {
     stream_handle: OutputStreamHandle,
-    samples: HashMap<String, Vec<u8>>,
+    /// Directory the samples are (re)loaded from
+    asset_dir: PathBuf,
+    samples: HashMap<String, DecodedSample>,
+    /// Master volume shared with the AudioSystem
+    master_volume: Arc<Mutex<f32>>,
+    /// SFX channel volume (0.0 - 1.0), multiplied with the master volume
+    volume: f32,
+    /// Assets that failed to preload
+    load_failures: Vec<AssetLoadFailure>,
+    /// Drops repeat plays of the same id that arrive too quickly
+    throttle: SfxThrottle,
+    /// One sink per play, oldest first, so sounds can be stopped; finished sinks
+    /// are pruned on the next play
+    active: Mutex<VecDeque<Sink>>,
 }
 
 impl SfxEngine {
-    pub fn new(stream_handle: OutputStreamHandle) -> Self {
+    /// `asset_dir` is the directory holding the UI sound files (`<audio_dir>/UI`)
+    pub fn new(
+        stream_handle: OutputStreamHandle,
+        master_volume: Arc<Mutex<f32>>,
+        asset_dir: &Path,
+    ) -> Self {
         let mut engine = Self {
             stream_handle,
+            asset_dir: asset_dir.to_path_buf(),
             samples: HashMap::new(),
+            master_volume,
+            volume: 1.0,
+            load_failures: Vec::new(),
+            throttle: SfxThrottle::new(),
+            active: Mutex::new(VecDeque::new()),
         };
 
         // We load assets here. In a real app we might want to do this async or lazy,
@@ -26,8 +67,16 @@ impl SfxEngine {
         engine
     }
 
-    fn preload_assets(&mut self) {
-        println!("[Audio] Preloading SFX assets...");
+    /// Decode every UI sound from `asset_dir`, returning how many loaded.
+    /// A sound that fails keeps its previously loaded sample, if any.
+    fn preload_assets(&mut self) -> usize {
+        let asset_dir = self.asset_dir.clone();
+        info!(
+            "[Audio] Preloading SFX assets from {}...",
+            asset_dir.display()
+        );
+        self.load_failures.clear();
+        let mut loaded = 0;
 
         // Map logical IDs to filenames
         let assets = [
@@ -35,43 +84,403 @@ impl SfxEngine {
             ("domain_switch", "cursorDomainSwitch.wav"),
             ("click", "cursorClick.wav"),
             ("resize", "windowSizeChange.mp3"),
+            ("boundary", "cursorBoundary.wav"),
         ];
 
-        // Base path: src/assets/audio/UI
-        // Note: In production bundle this needs resource handling, but for dev we use direct path
-        let base_path = "../src/assets/audio/UI";
-
         for (id, filename) in assets.iter() {
-            let path = format!("{}/{}", base_path, filename);
-            match std::fs::read(&path) {
-                Ok(data) => {
-                    self.samples.insert(id.to_string(), data);
-                    println!("[Audio] Loaded: {}", id);
+            let path = asset_dir.join(filename);
+            let result = std::fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(Self::decode);
+
+            match result {
+                Ok(sample) => {
+                    trace!(
+                        "[Audio] Loaded: {} ({} samples @ {}Hz)",
+                        id,
+                        sample.pcm.len(),
+                        sample.sample_rate
+                    );
+                    self.samples.insert(id.to_string(), sample);
+                    loaded += 1;
                 }
                 Err(e) => {
-                    eprintln!("[Audio] Failed to load {}: {}", path, e);
+                    error!("[Audio] Failed to load {}: {}", path.display(), e);
+                    self.load_failures.push(AssetLoadFailure {
+                        id: id.to_string(),
+                        path: path.display().to_string(),
+                        error: e.to_string(),
+                    });
                 }
             }
         }
+
+        info!("[Audio] Loaded {}/{} SFX samples", loaded, assets.len());
+        loaded
     }
 
-    pub fn play(&self, id: &str) {
-        if let Some(data) = self.samples.get(id) {
-            let cursor = std::io::Cursor::new(data.clone());
+    /// Re-read the sound files from disk (e.g. after editing them); returns how many loaded
+    pub fn reload_assets(&mut self) -> usize {
+        self.stop_all();
+        self.preload_assets()
+    }
 
-            // Decode on the fly (fast from memory) or cached decoded ??
-            // Rodio's Decoder is fast enough for wav usually, but for ultimate speed
-            // we could cache the decoded samples if they are raw.
-            // For now, re-decoding from memory buffer is good balance.
-            match Decoder::new(cursor) {
-                Ok(source) => {
-                    // Play event - this clones the source effectively
-                    let _ = self.stream_handle.play_raw(source.convert_samples());
+    /// Decode file bytes to PCM, rejecting anything too long to be a UI sound
+    fn decode(data: Vec<u8>) -> Result<DecodedSample, String> {
+        let decoder = Decoder::new(std::io::Cursor::new(data))
+            .map_err(|e| format!("Decode failed: {}", e))?;
+
+        // Extract metadata before the decoder is consumed
+        let channels = decoder.channels();
+        let sample_rate = decoder.sample_rate();
+        let max_samples = (MAX_SFX_SECS * sample_rate as f32) as usize * channels as usize;
+
+        let pcm: Vec<f32> = decoder
+            .convert_samples::<f32>()
+            .take(max_samples + 1)
+            .collect();
+        if pcm.len() > max_samples {
+            return Err(format!("Longer than {}s", MAX_SFX_SECS));
+        }
+
+        Ok(DecodedSample {
+            channels,
+            sample_rate,
+            pcm,
+        })
+    }
+
+    /// Ids of all preloaded samples, sorted
+    pub fn loaded_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.samples.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Whether a sample with this id is loaded and playable
+    pub fn is_loaded(&self, id: &str) -> bool {
+        self.samples.contains_key(id)
+    }
+
+    pub fn load_failures(&self) -> &[AssetLoadFailure] {
+        &self.load_failures
+    }
+
+    /// Set the SFX channel volume. Clamped to 0.0 - 1.0.
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.volume
+    }
+
+    /// Play on a different output stream; sounds on the old one are stopped
+    pub fn set_stream_handle(&mut self, stream_handle: OutputStreamHandle) {
+        self.stop_all();
+        self.stream_handle = stream_handle;
+    }
+
+    /// Play a sound; returns whether it was queued (false if throttled or missing)
+    pub fn play(&self, id: &str) -> bool {
+        self.play_with(id, 1.0, 0.0, 1.0)
+    }
+
+    /// Play a sound at a different playback speed (clamped to 0.25 - 4.0).
+    ///
+    /// This is a resampling-based pitch shift: speeds above 1.0 raise the pitch
+    /// *and* shorten the sound, speeds below 1.0 lower it and lengthen it.
+    pub fn play_with_speed(&self, id: &str, speed: f32) -> bool {
+        self.play_with(id, speed, 0.0, 1.0)
+    }
+
+    /// Play a sound panned between -1.0 (left) and 1.0 (right); 0.0 is centered
+    pub fn play_panned(&self, id: &str, pan: f32) -> bool {
+        self.play_with(id, 1.0, pan, 1.0)
+    }
+
+    /// Play a sound at a per-play gain (clamped to 0.0 - 1.0, multiplied with the
+    /// channel and master volumes) and pan (-1.0 - 1.0)
+    pub fn play_with_gain(&self, id: &str, gain: f32, pan: f32) -> bool {
+        self.play_with(id, 1.0, pan, gain)
+    }
+
+    /// Set the minimum time between two plays of `id`; faster repeats are dropped
+    pub fn set_min_interval(&mut self, id: &str, interval: Duration) {
+        self.throttle.set_interval(id, interval);
+    }
+
+    fn play_with(&self, id: &str, speed: f32, pan: f32, gain: f32) -> bool {
+        // Holding a key repeats plays far faster than the sounds can finish
+        if !self.throttle.allow(id, Instant::now()) {
+            return false;
+        }
+
+        let speed = speed.clamp(0.25, 4.0);
+        let pan = pan.clamp(-1.0, 1.0);
+        let gain = gain.clamp(0.0, 1.0);
+
+        if let Some(sample) = self.samples.get(id) {
+            // Play straight from the cached PCM - no decoding on the hot path
+            let master = self.master_volume.lock().map(|v| *v).unwrap_or(1.0);
+            let volume = master * self.volume * gain;
+
+            let source =
+                SamplesBuffer::new(sample.channels, sample.sample_rate, sample.pcm.clone())
+                    .amplify(volume)
+                    .speed(speed);
+
+            match Sink::try_new(&self.stream_handle) {
+                Ok(sink) => {
+                    sink.append(Panned::new(source, pan));
+                    self.track(sink);
+                    true
+                }
+                Err(e) => {
+                    error!("[Audio] Failed to create SFX sink: {}", e);
+                    false
                 }
-                Err(e) => eprintln!("[Audio] Decode error for {}: {}", id, e),
             }
         } else {
-            eprintln!("[Audio] Sound not found: {}", id);
+            error!("[Audio] Sound not found: {}", id);
+            false
         }
     }
+
+    /// Keep a playing sink's handle, dropping finished ones and capping the pool
+    fn track(&self, sink: Sink) {
+        let Ok(mut active) = self.active.lock() else {
+            // Without the pool the sound can't be stopped, but it still plays out
+            sink.detach();
+            return;
+        };
+        active.retain(|sink| !sink.empty());
+        if active.len() >= MAX_ACTIVE_SFX {
+            if let Some(oldest) = active.pop_front() {
+                oldest.stop();
+            }
+        }
+        active.push_back(sink);
+    }
+
+    /// Stop every sound still playing; returns how many were cut off
+    pub fn stop_all(&self) -> usize {
+        let Ok(mut active) = self.active.lock() else {
+            return 0;
+        };
+        let playing = active.iter().filter(|sink| !sink.empty()).count();
+        for sink in active.drain(..) {
+            sink.stop();
+        }
+        playing
+    }
+}
+
+/// Default minimum interval between repeat plays of the same SFX
+const DEFAULT_MIN_INTERVAL: Duration = Duration::from_millis(40);
+
+/// Per-id rate limiter. Ids are tracked independently, so a burst of "nav"
+/// never blocks a "click".
+struct SfxThrottle {
+    /// Per-id overrides of DEFAULT_MIN_INTERVAL
+    intervals: HashMap<String, Duration>,
+    last_played: Mutex<HashMap<String, Instant>>,
+}
+
+impl SfxThrottle {
+    fn new() -> Self {
+        let intervals = [
+            ("click", Duration::from_millis(20)),
+            ("resize", Duration::from_millis(120)),
+        ]
+        .into_iter()
+        .map(|(id, interval)| (id.to_string(), interval))
+        .collect();
+
+        Self {
+            intervals,
+            last_played: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn set_interval(&mut self, id: &str, interval: Duration) {
+        self.intervals.insert(id.to_string(), interval);
+    }
+
+    /// Returns true (and records the play) if `id` hasn't played within its interval
+    fn allow(&self, id: &str, now: Instant) -> bool {
+        let interval = self
+            .intervals
+            .get(id)
+            .copied()
+            .unwrap_or(DEFAULT_MIN_INTERVAL);
+
+        let Ok(mut last_played) = self.last_played.lock() else {
+            return true;
+        };
+        if let Some(last) = last_played.get(id) {
+            if now.duration_since(*last) < interval {
+                return false;
+            }
+        }
+        last_played.insert(id.to_string(), now);
+        true
+    }
+}
+
+/// Stereo balance wrapper.
+/// Mono input is upmixed to stereo; the side opposite the pan direction is attenuated,
+/// so a centered pan (0.0) plays exactly like the unwrapped source.
+struct Panned<S> {
+    input: S,
+    input_channels: u16,
+    left_gain: f32,
+    right_gain: f32,
+    /// Right-channel sample waiting to be emitted (mono upmix only)
+    pending_right: Option<f32>,
+    /// Channel of the next sample (multi-channel input only)
+    next_channel: u16,
+}
+
+impl<S: Source<Item = f32>> Panned<S> {
+    fn new(input: S, pan: f32) -> Self {
+        let input_channels = input.channels();
+        Self {
+            input,
+            input_channels,
+            left_gain: (1.0 - pan).min(1.0),
+            right_gain: (1.0 + pan).min(1.0),
+            pending_right: None,
+            next_channel: 0,
+        }
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for Panned<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.input_channels == 1 {
+            if let Some(right) = self.pending_right.take() {
+                return Some(right);
+            }
+            let sample = self.input.next()?;
+            self.pending_right = Some(sample.amplify(self.right_gain));
+            return Some(sample.amplify(self.left_gain));
+        }
+
+        let sample = self.input.next()?;
+        let gain = match self.next_channel {
+            0 => self.left_gain,
+            1 => self.right_gain,
+            _ => 1.0,
+        };
+        self.next_channel = (self.next_channel + 1) % self.input_channels.max(1);
+        Some(sample.amplify(gain))
+    }
+}
+
+impl<S: Source<Item = f32>> Source for Panned<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        if self.input_channels == 1 {
+            let pending = usize::from(self.pending_right.is_some());
+            self.input.current_frame_len().map(|n| n * 2 + pending)
+        } else {
+            self.input.current_frame_len()
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        if self.input_channels == 1 {
+            2
+        } else {
+            self.input_channels
+        }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal 16-bit mono PCM WAV of `secs` seconds of silence
+    fn silent_wav(sample_rate: u32, secs: f32) -> Vec<u8> {
+        let frames = (sample_rate as f32 * secs) as u32;
+        let data_len = frames * 2;
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        wav.resize(wav.len() + data_len as usize, 0);
+        wav
+    }
+
+    #[test]
+    fn test_decode_caches_pcm() {
+        let sample = SfxEngine::decode(silent_wav(8000, 0.1)).unwrap();
+        assert_eq!(sample.channels, 1);
+        assert_eq!(sample.sample_rate, 8000);
+        assert_eq!(sample.pcm.len(), 800);
+    }
+
+    #[test]
+    fn test_decode_rejects_long_sounds() {
+        let err = SfxEngine::decode(silent_wav(8000, MAX_SFX_SECS + 1.0))
+            .err()
+            .unwrap();
+        assert!(err.contains("Longer than"), "Unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_throttle_drops_fast_repeats() {
+        let throttle = SfxThrottle::new();
+        let start = Instant::now();
+
+        assert!(throttle.allow("nav", start));
+        assert!(!throttle.allow("nav", start + Duration::from_millis(10)));
+        assert!(throttle.allow("nav", start + DEFAULT_MIN_INTERVAL));
+    }
+
+    #[test]
+    fn test_throttle_ids_are_independent() {
+        let throttle = SfxThrottle::new();
+        let start = Instant::now();
+
+        assert!(throttle.allow("nav", start));
+        assert!(throttle.allow("click", start));
+        assert!(!throttle.allow("nav", start + Duration::from_millis(5)));
+    }
+
+    #[test]
+    fn test_throttle_per_id_intervals() {
+        let mut throttle = SfxThrottle::new();
+        throttle.set_interval("nav", Duration::from_millis(100));
+        let start = Instant::now();
+
+        assert!(throttle.allow("nav", start));
+        assert!(!throttle.allow("nav", start + Duration::from_millis(60)));
+
+        // "click" is snappier than the default, "resize" slower
+        assert!(throttle.allow("click", start));
+        assert!(throttle.allow("click", start + Duration::from_millis(25)));
+        assert!(throttle.allow("resize", start));
+        assert!(!throttle.allow("resize", start + Duration::from_millis(60)));
+    }
 }
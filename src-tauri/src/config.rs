@@ -0,0 +1,227 @@
+// Startup configuration loaded from hyphaeic.toml in the app config dir
+
+use crate::audio::ambience::{DEFAULT_FADE_SECS, STARTUP_DOMAIN};
+use crate::error::HyphaeicError;
+use crate::input_handler::spatial::DEFAULT_SPATIAL_WEIGHT;
+use crate::input_handler::DuplicateOrderPolicy;
+use crate::pty::DEFAULT_MAX_SESSIONS;
+use crate::state::DEFAULT_SLOT_COUNT;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Config file name inside the app config dir
+pub const CONFIG_FILE: &str = "hyphaeic.toml";
+
+/// Default window for dropping repeated same-direction navigation shortcuts
+pub const DEFAULT_REPEAT_COOLDOWN_MS: u64 = 50;
+
+/// Longest accepted repeat cooldown; beyond this a held key would barely navigate
+pub const MAX_REPEAT_COOLDOWN_MS: u64 = 1000;
+
+/// Backend settings. Every field is optional in the file; anything left out keeps
+/// the built-in default, so a missing file behaves exactly like no configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub audio: AudioConfig,
+    pub windows: WindowConfig,
+    pub navigation: NavigationConfig,
+    pub terminal: TerminalConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioConfig {
+    /// Master audio switch (false keeps all SFX and ambience off)
+    pub enabled: bool,
+    pub master_volume: f32,
+    pub sfx_volume: f32,
+    pub ambience_volume: f32,
+    /// Ambience crossfade duration in seconds
+    pub fade_duration_secs: f32,
+    /// Domain whose ambience plays before any domain is focused
+    pub startup_domain: String,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            master_volume: 1.0,
+            sfx_volume: 1.0,
+            ambience_volume: 1.0,
+            fade_duration_secs: DEFAULT_FADE_SECS,
+            startup_domain: STARTUP_DOMAIN.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    /// Number of compositor slots (read at startup only)
+    pub slot_count: usize,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            slot_count: DEFAULT_SLOT_COUNT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NavigationConfig {
+    /// Layout used when register_domain is called without one
+    /// ("grid", "list-vertical", "list-horizontal" or "spatial")
+    pub default_layout_mode: String,
+    /// Grid columns used when a grid layout doesn't specify them
+    pub grid_columns: usize,
+    pub spatial_weight: f64,
+    /// What registering a button with an already-used order does
+    /// ("reassign" to the next free order with a warning, or "reject")
+    pub duplicate_order: DuplicateOrderPolicy,
+    /// Milliseconds in which a repeated same-direction shortcut press is ignored (0 disables)
+    pub repeat_cooldown_ms: u64,
+}
+
+impl Default for NavigationConfig {
+    fn default() -> Self {
+        Self {
+            default_layout_mode: "grid".to_string(),
+            grid_columns: 3,
+            spatial_weight: DEFAULT_SPATIAL_WEIGHT,
+            duplicate_order: DuplicateOrderPolicy::Reassign,
+            repeat_cooldown_ms: DEFAULT_REPEAT_COOLDOWN_MS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerminalConfig {
+    pub max_sessions: usize,
+    /// Shell program for new sessions; None uses bash (PowerShell on Windows)
+    pub shell: Option<String>,
+}
+
+impl Default for TerminalConfig {
+    fn default() -> Self {
+        Self {
+            max_sessions: DEFAULT_MAX_SESSIONS,
+            shell: None,
+        }
+    }
+}
+
+impl Config {
+    /// Parse a config from TOML text, rejecting values the backend can't run with
+    pub fn parse(text: &str) -> Result<Self, HyphaeicError> {
+        let config: Self = toml::from_str(text)
+            .map_err(|e| HyphaeicError::InvalidInput(format!("Invalid {}: {}", CONFIG_FILE, e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), HyphaeicError> {
+        let invalid = |message: String| {
+            Err(HyphaeicError::InvalidInput(format!(
+                "Invalid {}: {}",
+                CONFIG_FILE, message
+            )))
+        };
+
+        if self.windows.slot_count == 0 {
+            return invalid("windows.slot_count must be at least 1".to_string());
+        }
+        if self.navigation.grid_columns == 0 {
+            return invalid("navigation.grid_columns must be at least 1".to_string());
+        }
+        if self.navigation.repeat_cooldown_ms > MAX_REPEAT_COOLDOWN_MS {
+            return invalid(format!(
+                "navigation.repeat_cooldown_ms must be at most {}",
+                MAX_REPEAT_COOLDOWN_MS
+            ));
+        }
+        Ok(())
+    }
+
+    /// Load the config file from `config_dir`; a missing file yields the defaults
+    pub fn load(config_dir: &Path) -> Result<Self, HyphaeicError> {
+        let path = config_path(config_dir);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| HyphaeicError::Io(format!("Failed to read {}: {}", path.display(), e)))?;
+        Self::parse(&text)
+    }
+}
+
+pub fn config_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(CONFIG_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_file_uses_defaults() {
+        let dir = std::env::temp_dir().join(format!("hyphaeic-config-{}", uuid::Uuid::new_v4()));
+        assert_eq!(Config::load(&dir).unwrap(), Config::default());
+        assert_eq!(Config::default().windows.slot_count, DEFAULT_SLOT_COUNT);
+        assert_eq!(Config::default().audio.startup_domain, "osbar-nav");
+    }
+
+    #[test]
+    fn test_partial_file_keeps_other_defaults() {
+        let config = Config::parse(
+            r#"
+            [audio]
+            master_volume = 0.5
+
+            [navigation]
+            duplicate_order = "reject"
+
+            [terminal]
+            shell = "zsh"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.audio.master_volume, 0.5);
+        assert_eq!(config.audio.sfx_volume, 1.0);
+        assert_eq!(config.terminal.shell.as_deref(), Some("zsh"));
+        assert_eq!(config.terminal.max_sessions, DEFAULT_MAX_SESSIONS);
+        assert_eq!(
+            config.navigation,
+            NavigationConfig {
+                duplicate_order: DuplicateOrderPolicy::Reject,
+                ..NavigationConfig::default()
+            }
+        );
+
+        assert!(Config::parse("[audio]\nmaster_volume = \"loud\"").is_err());
+    }
+
+    #[test]
+    fn test_rejects_unusable_values() {
+        for text in [
+            "[navigation]\ngrid_columns = 0",
+            "[windows]\nslot_count = 0",
+            "[navigation]\nrepeat_cooldown_ms = 60000",
+        ] {
+            assert!(
+                matches!(Config::parse(text), Err(HyphaeicError::InvalidInput(_))),
+                "Accepted: {}",
+                text
+            );
+        }
+
+        let config = Config::parse("[navigation]\nrepeat_cooldown_ms = 0").unwrap();
+        assert_eq!(config.navigation.repeat_cooldown_ms, 0);
+    }
+}
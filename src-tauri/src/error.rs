@@ -0,0 +1,184 @@
+// Structured error type shared by the navigator, window state and PTY layers
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+/// Error returned by backend commands.
+///
+/// Serialized as a tagged object `{ kind, message }` so the frontend can branch on
+/// `kind` (e.g. `"DomainExists"`) instead of matching message text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HyphaeicError {
+    DomainNotFound(String),
+    DomainExists(String),
+    ButtonNotFound {
+        domain_id: String,
+        button_id: String,
+    },
+    ButtonExists {
+        domain_id: String,
+        button_id: String,
+    },
+    LabelNotFound {
+        domain_id: String,
+        label: String,
+    },
+    WindowNotFound(String),
+    WindowClosing(String),
+    WindowNotHidden(String),
+    NoClosedWindows,
+    /// Every compositor slot is occupied
+    SlotFull,
+    SlotNotFound(usize),
+    SlotOccupied {
+        slot: usize,
+        window_id: String,
+    },
+    PtyNotFound(String),
+    PtyLimitReached(usize),
+    /// No SFX with this id is loaded
+    SoundNotFound(String),
+    /// The PTY backend failed (spawn, I/O, signals)
+    Pty(String),
+    /// A mutex was poisoned by a panicking thread; names the guarded resource
+    LockPoisoned(&'static str),
+    InvalidInput(String),
+    Serialization(String),
+    Io(String),
+    /// Errors from layers that still report plain strings (audio, asset loader, Tauri)
+    Other(String),
+}
+
+impl HyphaeicError {
+    /// Variant name, used as the `kind` tag in serialized errors
+    pub fn kind(&self) -> &'static str {
+        match self {
+            HyphaeicError::DomainNotFound(_) => "DomainNotFound",
+            HyphaeicError::DomainExists(_) => "DomainExists",
+            HyphaeicError::ButtonNotFound { .. } => "ButtonNotFound",
+            HyphaeicError::ButtonExists { .. } => "ButtonExists",
+            HyphaeicError::LabelNotFound { .. } => "LabelNotFound",
+            HyphaeicError::WindowNotFound(_) => "WindowNotFound",
+            HyphaeicError::WindowClosing(_) => "WindowClosing",
+            HyphaeicError::WindowNotHidden(_) => "WindowNotHidden",
+            HyphaeicError::NoClosedWindows => "NoClosedWindows",
+            HyphaeicError::SlotFull => "SlotFull",
+            HyphaeicError::SlotNotFound(_) => "SlotNotFound",
+            HyphaeicError::SlotOccupied { .. } => "SlotOccupied",
+            HyphaeicError::PtyNotFound(_) => "PtyNotFound",
+            HyphaeicError::PtyLimitReached(_) => "PtyLimitReached",
+            HyphaeicError::SoundNotFound(_) => "SoundNotFound",
+            HyphaeicError::Pty(_) => "Pty",
+            HyphaeicError::LockPoisoned(_) => "LockPoisoned",
+            HyphaeicError::InvalidInput(_) => "InvalidInput",
+            HyphaeicError::Serialization(_) => "Serialization",
+            HyphaeicError::Io(_) => "Io",
+            HyphaeicError::Other(_) => "Other",
+        }
+    }
+}
+
+impl fmt::Display for HyphaeicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HyphaeicError::DomainNotFound(id) => write!(f, "Domain '{}' not found", id),
+            HyphaeicError::DomainExists(id) => write!(f, "Domain '{}' already exists", id),
+            HyphaeicError::ButtonNotFound {
+                domain_id,
+                button_id,
+            } => write!(
+                f,
+                "Button '{}' not found in domain '{}'",
+                button_id, domain_id
+            ),
+            HyphaeicError::ButtonExists {
+                domain_id,
+                button_id,
+            } => write!(
+                f,
+                "Button '{}' already exists in domain '{}'",
+                button_id, domain_id
+            ),
+            HyphaeicError::LabelNotFound { domain_id, label } => write!(
+                f,
+                "No button labelled '{}' in domain '{}'",
+                label, domain_id
+            ),
+            HyphaeicError::WindowNotFound(id) => write!(f, "Window not found: {}", id),
+            HyphaeicError::WindowClosing(id) => write!(f, "Window {} is closing", id),
+            HyphaeicError::WindowNotHidden(id) => write!(f, "Window {} is not hidden", id),
+            HyphaeicError::NoClosedWindows => write!(f, "No recently closed windows"),
+            HyphaeicError::SlotFull => {
+                write!(f, "No available slots - all compositor slots are occupied")
+            }
+            HyphaeicError::SlotNotFound(slot) => {
+                write!(f, "Compositor slot {} does not exist", slot)
+            }
+            HyphaeicError::SlotOccupied { slot, window_id } => write!(
+                f,
+                "Compositor slot {} is occupied by window {}",
+                slot, window_id
+            ),
+            HyphaeicError::PtyNotFound(id) => write!(f, "Session {} not found", id),
+            HyphaeicError::PtyLimitReached(max) => {
+                write!(f, "Maximum PTY session limit ({}) reached", max)
+            }
+            HyphaeicError::SoundNotFound(id) => write!(f, "Sound '{}' is not loaded", id),
+            HyphaeicError::LockPoisoned(resource) => write!(f, "Failed to lock {}", resource),
+            HyphaeicError::Pty(message)
+            | HyphaeicError::InvalidInput(message)
+            | HyphaeicError::Serialization(message)
+            | HyphaeicError::Io(message)
+            | HyphaeicError::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for HyphaeicError {}
+
+impl Serialize for HyphaeicError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("HyphaeicError", 2)?;
+        error.serialize_field("kind", self.kind())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
+    }
+}
+
+impl From<String> for HyphaeicError {
+    fn from(message: String) -> Self {
+        HyphaeicError::Other(message)
+    }
+}
+
+impl From<tauri::Error> for HyphaeicError {
+    fn from(err: tauri::Error) -> Self {
+        HyphaeicError::Other(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for HyphaeicError {
+    fn from(err: serde_json::Error) -> Self {
+        HyphaeicError::Serialization(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_as_tagged_object() {
+        let err = HyphaeicError::DomainExists("osbar-nav".to_string());
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            serde_json::json!({
+                "kind": "DomainExists",
+                "message": "Domain 'osbar-nav' already exists",
+            })
+        );
+
+        let err = HyphaeicError::LockPoisoned("navigator");
+        assert_eq!(serde_json::to_value(&err).unwrap()["kind"], "LockPoisoned");
+    }
+}
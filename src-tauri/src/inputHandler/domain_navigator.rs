@@ -1,9 +1,17 @@
 // Main domain navigation logic
 
-use super::spatial::{find_nearest_in_direction, navigate_grid, navigate_list};
+use super::spatial::{
+    find_adjacent_by_edge, find_nearest_in_direction, navigate_grid, navigate_list,
+    SpatialCandidate, DEFAULT_SPATIAL_WEIGHT,
+};
 use super::types::*;
+use crate::error::HyphaeicError;
+use log::{debug, trace, warn};
 use std::collections::HashMap;
 
+/// Domain focus falls back to when the active domain goes away
+pub const FALLBACK_DOMAIN: &str = "osbar-nav";
+
 /// Main domain navigation state manager
 pub struct DomainNavigator {
     domains: HashMap<String, Domain>,
@@ -14,6 +22,13 @@ pub struct DomainNavigator {
     saved_cursor_positions: HashMap<String, CursorPosition>,
     /// Saved active domain ID when it gets unregistered
     saved_active_domain: Option<String>,
+    /// Last element the cursor was on in each domain, used on re-entry by
+    /// domains with `restore_focus` enabled
+    last_focused: HashMap<String, String>,
+    /// Penalty multiplier for perpendicular offset in spatial navigation
+    spatial_weight: f64,
+    /// How a button registered with an already-used `order` is handled
+    duplicate_order: DuplicateOrderPolicy,
 }
 
 impl DomainNavigator {
@@ -24,6 +39,9 @@ impl DomainNavigator {
             cursor_position: None,
             saved_cursor_positions: HashMap::new(),
             saved_active_domain: None,
+            last_focused: HashMap::new(),
+            spatial_weight: DEFAULT_SPATIAL_WEIGHT,
+            duplicate_order: DuplicateOrderPolicy::default(),
         }
     }
 
@@ -33,9 +51,9 @@ impl DomainNavigator {
         domain_id: String,
         parent_id: Option<String>,
         layout_mode: LayoutMode,
-    ) -> Result<(), String> {
+    ) -> Result<(), HyphaeicError> {
         if self.domains.contains_key(&domain_id) {
-            return Err(format!("Domain '{}' already exists", domain_id));
+            return Err(HyphaeicError::DomainExists(domain_id));
         }
 
         let domain = Domain::new(domain_id.clone(), parent_id, layout_mode);
@@ -64,11 +82,14 @@ impl DomainNavigator {
 
     /// Unregister a domain
     /// Unregister a domain
-    pub fn unregister_domain(&mut self, domain_id: &str) -> Result<Option<CursorPosition>, String> {
-        println!("[UNREGISTER_DOMAIN] domain: {}", domain_id);
+    pub fn unregister_domain(
+        &mut self,
+        domain_id: &str,
+    ) -> Result<Option<CursorPosition>, HyphaeicError> {
+        trace!("[UNREGISTER_DOMAIN] domain: {}", domain_id);
 
         if !self.domains.contains_key(domain_id) {
-            return Err(format!("Domain '{}' not found", domain_id));
+            return Err(HyphaeicError::DomainNotFound(domain_id.to_string()));
         }
 
         // If cursor was in this domain, save it for restoration
@@ -89,21 +110,24 @@ impl DomainNavigator {
 
             // Fallback: Default to OSBar if active domain is lost
             // This prevents "lost navigation" when closing windows
-            if let Some(osbar) = self.domains.get("osbar-nav") {
-                if !osbar.buttons.is_empty() {
-                    println!("[UNREGISTER_DOMAIN] Active domain lost, falling back to osbar-nav");
-                    self.active_domain_id = Some("osbar-nav".to_string());
-
-                    if let Some(first_btn) = osbar.buttons.first() {
-                        let new_cursor = CursorPosition {
-                            domain_id: "osbar-nav".to_string(),
-                            element_id: first_btn.id.clone(),
-                            element_type: ElementType::Button,
-                        };
-                        self.cursor_position = Some(new_cursor.clone());
-                        cursor_change = Some(new_cursor);
-                    }
-                }
+            let first_btn = self
+                .fallback_domain()
+                .and_then(|osbar| osbar.buttons.first())
+                .map(|button| button.id.clone());
+            if let Some(first_btn) = first_btn {
+                debug!(
+                    "[UNREGISTER_DOMAIN] Active domain lost, falling back to {}",
+                    FALLBACK_DOMAIN
+                );
+                self.active_domain_id = Some(FALLBACK_DOMAIN.to_string());
+
+                let new_cursor = CursorPosition {
+                    domain_id: FALLBACK_DOMAIN.to_string(),
+                    element_id: first_btn,
+                    element_type: ElementType::Button,
+                };
+                self.cursor_position = Some(new_cursor.clone());
+                cursor_change = Some(new_cursor);
             }
         }
 
@@ -112,7 +136,7 @@ impl DomainNavigator {
         // Clean up saved cursor for this domain since it no longer exists
         // This prevents stale entries from causing issues
         self.saved_cursor_positions.remove(domain_id);
-        println!(
+        trace!(
             "[UNREGISTER_DOMAIN] Cleaned up saved cursor, remaining: {:?}",
             self.saved_cursor_positions.keys().collect::<Vec<_>>()
         );
@@ -120,6 +144,13 @@ impl DomainNavigator {
         Ok(cursor_change)
     }
 
+    /// The fallback domain (osbar-nav), if it's registered and has buttons to focus
+    pub fn fallback_domain(&self) -> Option<&Domain> {
+        self.domains
+            .get(FALLBACK_DOMAIN)
+            .filter(|domain| !domain.buttons.is_empty())
+    }
+
     /// Register a button within a domain
     pub fn register_button(
         &mut self,
@@ -127,20 +158,23 @@ impl DomainNavigator {
         button_id: String,
         bounds: Option<Rect>,
         order: usize,
-    ) -> Result<(), String> {
-        println!(
+        label: Option<String>,
+    ) -> Result<(), HyphaeicError> {
+        trace!(
             "[REGISTER_BUTTON] domain: {}, button: {}, order: {}",
-            domain_id, button_id, order
+            domain_id,
+            button_id,
+            order
         );
-        println!(
+        trace!(
             "[REGISTER_BUTTON] Active domain: {:?}",
             self.active_domain_id
         );
-        println!(
+        trace!(
             "[REGISTER_BUTTON] Current cursor: {:?}",
             self.cursor_position
         );
-        println!(
+        trace!(
             "[REGISTER_BUTTON] Saved cursors: {:?}",
             self.saved_cursor_positions
         );
@@ -148,21 +182,25 @@ impl DomainNavigator {
         let domain = self
             .domains
             .get_mut(&domain_id)
-            .ok_or_else(|| format!("Domain '{}' not found", domain_id))?;
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.clone()))?;
 
         // Check if button already exists
         if domain.buttons.iter().any(|b| b.id == button_id) {
-            return Err(format!(
-                "Button '{}' already exists in domain '{}'",
-                button_id, domain_id
-            ));
+            return Err(HyphaeicError::ButtonExists {
+                domain_id,
+                button_id,
+            });
         }
 
+        let taken: Vec<usize> = domain.buttons.iter().map(|b| b.order).collect();
+        let order = resolve_order(&taken, order, self.duplicate_order, &domain_id, &button_id)?;
+
         let button = ButtonElement {
             id: button_id.clone(),
             bounds,
             enabled: true,
             order,
+            label,
         };
 
         domain.buttons.push(button);
@@ -170,7 +208,7 @@ impl DomainNavigator {
         // Sort buttons by order
         domain.buttons.sort_by_key(|b| b.order);
 
-        println!(
+        trace!(
             "[REGISTER_BUTTON] Domain now has {} buttons",
             domain.buttons.len()
         );
@@ -178,10 +216,10 @@ impl DomainNavigator {
         // Check if we have a saved cursor position for this domain
         if self.active_domain_id.as_ref() == Some(&domain_id) {
             if let Some(saved_cursor) = self.saved_cursor_positions.get(&domain_id) {
-                println!("[REGISTER_BUTTON] Found saved cursor: {:?}", saved_cursor);
+                trace!("[REGISTER_BUTTON] Found saved cursor: {:?}", saved_cursor);
                 // If this is the button we were on, restore cursor
                 if saved_cursor.element_id == button_id {
-                    println!("[REGISTER_BUTTON] ✓ RESTORING cursor to {}", button_id);
+                    trace!("[REGISTER_BUTTON] ✓ RESTORING cursor to {}", button_id);
                     self.cursor_position = Some(CursorPosition {
                         domain_id: domain_id.clone(),
                         element_id: button_id.clone(),
@@ -193,7 +231,7 @@ impl DomainNavigator {
                 } else {
                     // There's a saved cursor waiting for a different button
                     // Don't set cursor to first element - wait for the correct button to register
-                    println!(
+                    trace!(
                         "[REGISTER_BUTTON] Saved cursor exists for different button, waiting..."
                     );
                     return Ok(());
@@ -202,7 +240,7 @@ impl DomainNavigator {
 
             // If no cursor position and no saved cursor and this is the first element, set cursor to it
             if self.cursor_position.is_none() && domain.element_count() == 1 {
-                println!(
+                trace!(
                     "[REGISTER_BUTTON] ✓ Setting cursor to first element: {}",
                     button_id
                 );
@@ -214,17 +252,110 @@ impl DomainNavigator {
             }
         }
 
-        println!("[REGISTER_BUTTON] Final cursor: {:?}", self.cursor_position);
+        trace!("[REGISTER_BUTTON] Final cursor: {:?}", self.cursor_position);
+        Ok(())
+    }
+
+    /// Register several buttons in one pass, sorting once and restoring the
+    /// saved cursor after the whole batch. Fails without changes on any duplicate id.
+    pub fn register_buttons(
+        &mut self,
+        domain_id: String,
+        buttons: Vec<ButtonSpec>,
+    ) -> Result<(), HyphaeicError> {
+        trace!(
+            "[REGISTER_BUTTONS] domain: {}, count: {}",
+            domain_id,
+            buttons.len()
+        );
+
+        let domain = self
+            .domains
+            .get_mut(&domain_id)
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.clone()))?;
+
+        let mut taken: Vec<usize> = domain.buttons.iter().map(|b| b.order).collect();
+        for (i, spec) in buttons.iter().enumerate() {
+            let duplicate = domain.buttons.iter().any(|b| b.id == spec.id)
+                || buttons[..i].iter().any(|b| b.id == spec.id);
+            if duplicate {
+                return Err(HyphaeicError::ButtonExists {
+                    domain_id: domain_id.clone(),
+                    button_id: spec.id.clone(),
+                });
+            }
+            let order = resolve_order(
+                &taken,
+                spec.order,
+                self.duplicate_order,
+                &domain_id,
+                &spec.id,
+            )?;
+            taken.push(order);
+        }
+
+        let was_empty = domain.element_count() == 0;
+        let batch_ids: Vec<String> = buttons.iter().map(|b| b.id.clone()).collect();
+
+        // The batch's resolved orders follow the existing buttons' in `taken`
+        let orders = taken.split_off(domain.buttons.len());
+        domain.buttons.extend(
+            buttons
+                .into_iter()
+                .zip(orders)
+                .map(|(spec, order)| ButtonElement {
+                    id: spec.id,
+                    bounds: spec.bounds,
+                    enabled: true,
+                    order,
+                    label: spec.label,
+                }),
+        );
+        domain.buttons.sort_by_key(|b| b.order);
+
+        if self.active_domain_id.as_ref() != Some(&domain_id) {
+            return Ok(());
+        }
+
+        if let Some(saved_cursor) = self.saved_cursor_positions.get(&domain_id) {
+            // Restore only if the saved button arrived in this batch; otherwise keep waiting
+            if batch_ids.contains(&saved_cursor.element_id) {
+                trace!(
+                    "[REGISTER_BUTTONS] ✓ RESTORING cursor to {}",
+                    saved_cursor.element_id
+                );
+                self.cursor_position = Some(saved_cursor.clone());
+                self.saved_cursor_positions.remove(&domain_id);
+            }
+            return Ok(());
+        }
+
+        if self.cursor_position.is_none() && was_empty {
+            if let Some(first) = domain.buttons.first() {
+                self.cursor_position = Some(CursorPosition {
+                    domain_id: domain_id.clone(),
+                    element_id: first.id.clone(),
+                    element_type: ElementType::Button,
+                });
+            }
+        }
+
         Ok(())
     }
 
-    /// Unregister a button
-    pub fn unregister_button(&mut self, domain_id: &str, button_id: &str) -> Result<(), String> {
-        println!(
+    /// Unregister a button. Returns true when this removed the last element of the
+    /// active domain, leaving navigation nowhere to go.
+    pub fn unregister_button(
+        &mut self,
+        domain_id: &str,
+        button_id: &str,
+    ) -> Result<bool, HyphaeicError> {
+        trace!(
             "[UNREGISTER_BUTTON] domain: {}, button: {}",
-            domain_id, button_id
+            domain_id,
+            button_id
         );
-        println!(
+        trace!(
             "[UNREGISTER_BUTTON] Current cursor: {:?}",
             self.cursor_position
         );
@@ -232,19 +363,22 @@ impl DomainNavigator {
         let domain = self
             .domains
             .get_mut(domain_id)
-            .ok_or_else(|| format!("Domain '{}' not found", domain_id))?;
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.to_string()))?;
 
         let index = domain
             .buttons
             .iter()
             .position(|b| b.id == button_id)
-            .ok_or_else(|| format!("Button '{}' not found in domain '{}'", button_id, domain_id))?;
+            .ok_or_else(|| HyphaeicError::ButtonNotFound {
+                domain_id: domain_id.to_string(),
+                button_id: button_id.to_string(),
+            })?;
 
         // If cursor was on this button, save it for restoration when button re-registers
         // (e.g., during resize, window state change, etc.)
         if let Some(cursor) = &self.cursor_position {
             if cursor.domain_id == domain_id && cursor.element_id == button_id {
-                println!(
+                trace!(
                     "[UNREGISTER_BUTTON] ✓ SAVING cursor position for {}",
                     button_id
                 );
@@ -258,16 +392,24 @@ impl DomainNavigator {
         }
 
         domain.buttons.remove(index);
-        println!(
+        trace!(
             "[UNREGISTER_BUTTON] Domain now has {} buttons",
             domain.buttons.len()
         );
-        println!(
+        trace!(
             "[UNREGISTER_BUTTON] Saved cursors: {:?}",
             self.saved_cursor_positions
         );
 
-        Ok(())
+        let emptied =
+            domain.element_count() == 0 && self.active_domain_id.as_deref() == Some(domain_id);
+        if emptied {
+            debug!(
+                "[UNREGISTER_BUTTON] Active domain '{}' is now empty",
+                domain_id
+            );
+        }
+        Ok(emptied)
     }
 
     /// Update button bounds without unregistering (used during resize)
@@ -277,22 +419,50 @@ impl DomainNavigator {
         domain_id: &str,
         button_id: &str,
         bounds: Option<Rect>,
-    ) -> Result<(), String> {
+    ) -> Result<(), HyphaeicError> {
         let domain = self
             .domains
             .get_mut(domain_id)
-            .ok_or_else(|| format!("Domain '{}' not found", domain_id))?;
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.to_string()))?;
 
         let button = domain
             .buttons
             .iter_mut()
             .find(|b| b.id == button_id)
-            .ok_or_else(|| format!("Button '{}' not found in domain '{}'", button_id, domain_id))?;
+            .ok_or_else(|| HyphaeicError::ButtonNotFound {
+                domain_id: domain_id.to_string(),
+                button_id: button_id.to_string(),
+            })?;
 
         button.bounds = bounds;
         Ok(())
     }
 
+    /// Enable or disable a button. Disabled buttons are skipped by navigation.
+    pub fn set_button_enabled(
+        &mut self,
+        domain_id: &str,
+        button_id: &str,
+        enabled: bool,
+    ) -> Result<(), HyphaeicError> {
+        let domain = self
+            .domains
+            .get_mut(domain_id)
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.to_string()))?;
+
+        let button = domain
+            .buttons
+            .iter_mut()
+            .find(|b| b.id == button_id)
+            .ok_or_else(|| HyphaeicError::ButtonNotFound {
+                domain_id: domain_id.to_string(),
+                button_id: button_id.to_string(),
+            })?;
+
+        button.enabled = enabled;
+        Ok(())
+    }
+
     // DEPRECATED: Gate system replaced by spatial boundary navigation
     // /// Register a gate within a domain
     // pub fn register_gate(
@@ -349,22 +519,20 @@ impl DomainNavigator {
     // }
 
     /// Set the active domain
-    pub fn set_active_domain(&mut self, domain_id: String) -> Result<(), String> {
+    pub fn set_active_domain(&mut self, domain_id: String) -> Result<(), HyphaeicError> {
         if !self.domains.contains_key(&domain_id) {
-            return Err(format!("Domain '{}' not found", domain_id));
+            return Err(HyphaeicError::DomainNotFound(domain_id));
         }
 
         self.active_domain_id = Some(domain_id.clone());
 
-        // Set cursor to first element if available
-        if let Some(domain) = self.domains.get(&domain_id) {
-            if let Some((element_type, element_id)) = domain.get_element_at_index(0) {
-                self.cursor_position = Some(CursorPosition {
-                    domain_id,
-                    element_id,
-                    element_type,
-                });
-            }
+        // Set cursor to the domain's entry element if available
+        if let Some((element_type, element_id)) = self.entry_element(&domain_id) {
+            self.move_cursor(CursorPosition {
+                domain_id,
+                element_id,
+                element_type,
+            });
         }
 
         Ok(())
@@ -385,19 +553,19 @@ impl DomainNavigator {
         &mut self,
         domain_id: &str,
         element_id: &str,
-    ) -> Result<ElementType, String> {
+    ) -> Result<ElementType, HyphaeicError> {
         // Verify domain exists
         let domain = self
             .domains
             .get(domain_id)
-            .ok_or_else(|| format!("Domain '{}' not found", domain_id))?;
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.to_string()))?;
 
         // Verify element exists (buttons only, gates deprecated)
         if !domain.buttons.iter().any(|b| b.id == element_id) {
-            return Err(format!(
-                "Element '{}' not found in domain '{}'",
-                element_id, domain_id
-            ));
+            return Err(HyphaeicError::ButtonNotFound {
+                domain_id: domain_id.to_string(),
+                button_id: element_id.to_string(),
+            });
         }
         let element_type = ElementType::Button;
 
@@ -405,7 +573,7 @@ impl DomainNavigator {
         self.active_domain_id = Some(domain_id.to_string());
 
         // Update cursor position
-        self.cursor_position = Some(CursorPosition {
+        self.move_cursor(CursorPosition {
             domain_id: domain_id.to_string(),
             element_id: element_id.to_string(),
             element_type: element_type.clone(),
@@ -416,59 +584,34 @@ impl DomainNavigator {
 
     /// Handle WASD input and navigate
     pub fn handle_wasd_input(&mut self, key: WASDKey) -> NavigationResult {
-        println!(
+        trace!(
             "[NAV DEBUG] handle_wasd_input: key={:?}, active_domain={:?}, cursor={:?}",
-            key, self.active_domain_id, self.cursor_position
+            key,
+            self.active_domain_id,
+            self.cursor_position
         );
 
         let Some(active_domain_id) = self.active_domain_id.clone() else {
-            println!("[NAV DEBUG]   -> No active domain!");
+            trace!("[NAV DEBUG]   -> No active domain!");
             return NavigationResult::NoActiveDomain;
         };
 
-        // First, calculate the next index without holding a borrow
-        let (element_count, current_index, layout_mode) = {
-            let Some(domain) = self.domains.get(&active_domain_id) else {
+        match self.domains.get(&active_domain_id) {
+            None => {
                 return NavigationResult::Error {
                     message: format!("Active domain '{}' not found", active_domain_id),
                 };
-            };
-
-            if domain.element_count() == 0 {
-                return NavigationResult::BoundaryReached;
-            }
-
-            let current_index = if let Some(cursor) = &self.cursor_position {
-                domain.find_element_index(&cursor.element_id).unwrap_or(0)
-            } else {
-                0
-            };
-
-            (
-                domain.element_count(),
-                current_index,
-                domain.layout_mode.clone(),
-            )
-        };
-
-        // Navigate based on layout mode
-        let next_index = match &layout_mode {
-            LayoutMode::Grid { columns } => {
-                navigate_grid(current_index, element_count, *columns, key)
-            }
-            LayoutMode::List { direction } => {
-                let is_vertical = matches!(direction, ListDirection::Vertical);
-                navigate_list(current_index, element_count, is_vertical, key)
             }
-            LayoutMode::Spatial => {
-                // For spatial, we need to access the domain again
-                let domain = self.domains.get(&active_domain_id).unwrap();
-                self.navigate_spatial(domain, current_index, key)
+            Some(domain) if domain.element_count() == 0 => {
+                return NavigationResult::BoundaryReached {
+                    reason: BlockReason::EmptyDomain,
+                };
             }
-        };
+            Some(_) => {}
+        }
 
         // Update cursor position
-        if let Some(new_index) = next_index {
+        if let Some(new_index) = self.next_index_in_domain(&active_domain_id, key) {
             // Get element info and gate info before updating
             let element_info = {
                 let domain = self.domains.get(&active_domain_id).unwrap();
@@ -482,7 +625,7 @@ impl DomainNavigator {
                 }
 
                 // Update cursor position
-                self.cursor_position = Some(CursorPosition {
+                self.move_cursor(CursorPosition {
                     domain_id: active_domain_id.clone(),
                     element_id: element_id.clone(),
                     element_type: element_type.clone(),
@@ -497,34 +640,153 @@ impl DomainNavigator {
         }
 
         // No element to navigate to within this domain - check for adjacent domains
-        // First, determine which direction is the boundary based on the key pressed
+        match self.exit_target(&active_domain_id, key) {
+            Ok((boundary_direction, target_domain_id)) => NavigationResult::DomainBoundaryCrossed {
+                from_domain: active_domain_id,
+                to_domain: target_domain_id,
+                direction: format!("{:?}", boundary_direction).to_lowercase(),
+            },
+            Err(reason) => NavigationResult::BoundaryReached { reason },
+        }
+    }
+
+    /// Report the `(domain_id, element_id)` a move in `key`'s direction would select,
+    /// without moving the cursor. Crossing into an adjacent domain reports the element
+    /// the cursor would land on there.
+    pub fn peek_neighbor(&self, key: WASDKey) -> Option<(String, String)> {
+        let active_domain_id = self.active_domain_id.as_ref()?;
+        let domain = self.domains.get(active_domain_id)?;
+        if domain.element_count() == 0 {
+            return None;
+        }
+
+        if let Some(index) = self.next_index_in_domain(active_domain_id, key) {
+            let (_, element_id) = domain.get_element_at_index(index)?;
+            return Some((active_domain_id.clone(), element_id));
+        }
+
+        let (_, target_domain_id) = self.exit_target(active_domain_id, key).ok()?;
+        let (_, element_id) = self.entry_element(&target_domain_id)?;
+        Some((target_domain_id, element_id))
+    }
+
+    /// Index a move would select within `domain_id` based on its layout mode.
+    /// Grid/list keep stepping in the pressed direction past disabled elements
+    /// until an enabled one or the boundary.
+    fn next_index_in_domain(&self, domain_id: &str, key: WASDKey) -> Option<usize> {
+        let domain = self.domains.get(domain_id)?;
+        let element_count = domain.element_count();
+
+        let current_index = match &self.cursor_position {
+            Some(cursor) => domain.find_element_index(&cursor.element_id).unwrap_or(0),
+            None => 0,
+        };
+
+        match &domain.layout_mode {
+            LayoutMode::Grid { columns } => step_until_enabled(domain, current_index, |index| {
+                navigate_grid(index, element_count, *columns, key)
+            }),
+            LayoutMode::List { direction } => {
+                let is_vertical = matches!(direction, ListDirection::Vertical);
+                step_until_enabled(domain, current_index, |index| {
+                    navigate_list(index, element_count, is_vertical, domain.wrap, key)
+                })
+            }
+            LayoutMode::Spatial => self.navigate_spatial(domain, current_index, key),
+        }
+    }
+
+    /// Domain reachable from `domain_id` in each direction ("up", "down", "left", "right"),
+    /// or None where nothing lies that way or the edge is locked. Doesn't move the cursor.
+    pub fn adjacent_domains(
+        &self,
+        domain_id: &str,
+    ) -> Result<HashMap<String, Option<String>>, HyphaeicError> {
+        if !self.domains.contains_key(domain_id) {
+            return Err(HyphaeicError::DomainNotFound(domain_id.to_string()));
+        }
+
+        Ok([
+            ("up", WASDKey::W),
+            ("down", WASDKey::S),
+            ("left", WASDKey::A),
+            ("right", WASDKey::D),
+        ]
+        .into_iter()
+        .map(|(name, key)| {
+            let target = self.exit_target(domain_id, key).ok().map(|(_, id)| id);
+            (name.to_string(), target)
+        })
+        .collect())
+    }
+
+    /// Adjacent domain (and the edge being crossed) when leaving `domain_id` in `key`'s
+    /// direction, or why the move can't leave the domain
+    fn exit_target(
+        &self,
+        domain_id: &str,
+        key: WASDKey,
+    ) -> Result<(GateDirection, String), BlockReason> {
+        // Determine which direction is the boundary based on the key pressed.
+        // Diagonals only move within a domain; they never cross a boundary.
         let boundary_direction = match key {
             WASDKey::W => GateDirection::Top,
             WASDKey::S => GateDirection::Bottom,
             WASDKey::A => GateDirection::Left,
             WASDKey::D => GateDirection::Right,
+            WASDKey::Q | WASDKey::E | WASDKey::Z | WASDKey::C => return Err(BlockReason::Edge),
         };
 
         // Check if domain allows exit in this direction
-        let can_exit = {
-            let domain = self.domains.get(&active_domain_id).unwrap();
-            domain.can_exit_direction(&boundary_direction)
-        };
+        let domain = self
+            .domains
+            .get(domain_id)
+            .ok_or(BlockReason::NoAdjacentDomain)?;
+        if !domain.can_exit_direction(&boundary_direction) {
+            return Err(BlockReason::BoundaryLock);
+        }
 
-        if !can_exit {
-            return NavigationResult::BoundaryReached;
+        // An explicit neighbor wins over spatial inference while it has elements to land on
+        if let Some(neighbor_id) = domain.neighbors.get(&boundary_direction) {
+            let enterable = self
+                .domains
+                .get(neighbor_id)
+                .is_some_and(|neighbor| neighbor.element_count() > 0);
+            if enterable {
+                return Ok((boundary_direction, neighbor_id.clone()));
+            }
         }
 
         // Try to find an adjacent domain
-        if let Some(target_domain_id) = self.find_adjacent_domain(&active_domain_id, key) {
-            return NavigationResult::DomainBoundaryCrossed {
-                from_domain: active_domain_id,
-                to_domain: target_domain_id,
-                direction: format!("{:?}", boundary_direction).to_lowercase(),
-            };
+        let target_domain_id = self
+            .find_adjacent_domain(domain_id, key)
+            .ok_or(BlockReason::NoAdjacentDomain)?;
+        Ok((boundary_direction, target_domain_id))
+    }
+
+    /// Element the cursor lands on when entering `domain_id`: the last focused
+    /// element for `restore_focus` domains (if it still exists), else the first
+    fn entry_element(&self, domain_id: &str) -> Option<(ElementType, String)> {
+        let domain = self.domains.get(domain_id)?;
+
+        if domain.restore_focus {
+            let last_index = self
+                .last_focused
+                .get(domain_id)
+                .and_then(|element_id| domain.find_element_index(element_id));
+            if let Some(index) = last_index {
+                return domain.get_element_at_index(index);
+            }
         }
 
-        NavigationResult::BoundaryReached
+        domain.get_element_at_index(0)
+    }
+
+    /// Move the cursor and remember it as the domain's last focused element
+    fn move_cursor(&mut self, position: CursorPosition) {
+        self.last_focused
+            .insert(position.domain_id.clone(), position.element_id.clone());
+        self.cursor_position = Some(position);
     }
 
     /// Find an adjacent domain in the given direction using spatial bounds
@@ -532,17 +794,22 @@ impl DomainNavigator {
         let current_domain = self.domains.get(current_domain_id)?;
         let current_bounds = current_domain.bounds?;
 
-        println!(
+        trace!(
             "[NAV DEBUG] find_adjacent_domain: from='{}' direction={:?}",
-            current_domain_id, direction
+            current_domain_id,
+            direction
         );
-        println!(
+        trace!(
             "[NAV DEBUG]   current_bounds: x={}, y={}, w={}, h={}",
-            current_bounds.x, current_bounds.y, current_bounds.width, current_bounds.height
+            current_bounds.x,
+            current_bounds.y,
+            current_bounds.width,
+            current_bounds.height
         );
 
         // Collect all other domains with bounds
-        let candidates: Vec<(String, Rect)> = self
+        // Domains have no order, so equally distant ones tie-break on id
+        let candidates: Vec<SpatialCandidate> = self
             .domains
             .iter()
             .filter(|(id, domain)| {
@@ -550,7 +817,7 @@ impl DomainNavigator {
             })
             .map(|(id, domain)| {
                 let b = domain.bounds.unwrap();
-                println!(
+                trace!(
                     "[NAV DEBUG]   candidate '{}': x={}, y={}, w={}, h={}, elements={}",
                     id,
                     b.x,
@@ -559,19 +826,19 @@ impl DomainNavigator {
                     b.height,
                     domain.element_count()
                 );
-                (id.clone(), b)
+                (id.clone(), b, 0)
             })
             .collect();
 
         if candidates.is_empty() {
-            println!("[NAV DEBUG]   No candidates with bounds!");
+            trace!("[NAV DEBUG]   No candidates with bounds!");
             return None;
         }
 
-        // Use spatial algorithm to find nearest domain in direction
+        // Compare facing edges so long sidebars route to the domain they actually border
         let result =
-            super::spatial::find_nearest_in_direction(&current_bounds, &candidates, direction);
-        println!("[NAV DEBUG]   Result: {:?}", result);
+            find_adjacent_by_edge(&current_bounds, &candidates, direction, self.spatial_weight);
+        trace!("[NAV DEBUG]   Result: {:?}", result);
         result
     }
 
@@ -589,18 +856,23 @@ impl DomainNavigator {
         let current_element = domain.buttons[current_index].bounds?;
 
         // Collect all candidate buttons with bounds
-        let mut candidates: Vec<(String, Rect)> = Vec::new();
+        let mut candidates: Vec<SpatialCandidate> = Vec::new();
 
         for (idx, button) in domain.buttons.iter().enumerate() {
-            if idx != current_index {
+            if idx != current_index && button.enabled {
                 if let Some(bounds) = button.bounds {
-                    candidates.push((button.id.clone(), bounds));
+                    candidates.push((button.id.clone(), bounds, button.order));
                 }
             }
         }
 
         // Find nearest element in direction
-        let nearest_id = find_nearest_in_direction(&current_element, &candidates, direction)?;
+        let nearest_id = find_nearest_in_direction(
+            &current_element,
+            &candidates,
+            direction,
+            self.spatial_weight,
+        )?;
 
         // Find the index of this element
         domain.find_element_index(&nearest_id)
@@ -621,8 +893,7 @@ impl DomainNavigator {
         let from_domain = self.active_domain_id.clone().unwrap_or_default();
 
         // Get first element in target domain
-        let target_domain = self.domains.get(target_domain_id).unwrap();
-        let (element_type, element_id) = match target_domain.get_element_at_index(0) {
+        let (element_type, element_id) = match self.entry_element(target_domain_id) {
             Some(e) => e,
             None => {
                 return NavigationResult::Error {
@@ -633,7 +904,7 @@ impl DomainNavigator {
 
         // Switch!
         self.active_domain_id = Some(target_domain_id.to_string());
-        self.cursor_position = Some(CursorPosition {
+        self.move_cursor(CursorPosition {
             domain_id: target_domain_id.to_string(),
             element_id: element_id.clone(),
             element_type: element_type.clone(),
@@ -646,30 +917,177 @@ impl DomainNavigator {
         }
     }
 
+    /// Switch to the active domain's parent, placing the cursor on its entry element.
+    /// Gives a consistent "escape up one level" regardless of spatial adjacency.
+    pub fn go_to_parent(&mut self) -> NavigationResult {
+        let Some(active_domain_id) = self.active_domain_id.clone() else {
+            return NavigationResult::NoActiveDomain;
+        };
+
+        let parent_id = match self.domains.get(&active_domain_id) {
+            Some(domain) => domain.parent_id.clone(),
+            None => {
+                return NavigationResult::Error {
+                    message: format!("Active domain '{}' not found", active_domain_id),
+                }
+            }
+        };
+
+        match parent_id {
+            Some(parent_id) => self.switch_to_domain(&parent_id),
+            None => NavigationResult::Error {
+                message: format!("Domain '{}' has no parent", active_domain_id),
+            },
+        }
+    }
+
+    /// Domain that focus returns to when `domain_id` is dismissed (e.g. a menu that just
+    /// launched a window): its parent if that has elements, else the fallback domain (osbar-nav)
+    pub fn dismiss_target(&self, domain_id: &str) -> Result<String, HyphaeicError> {
+        let domain = self
+            .domains
+            .get(domain_id)
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.to_string()))?;
+
+        let parent = domain.parent_id.clone().filter(|parent_id| {
+            self.domains
+                .get(parent_id)
+                .is_some_and(|parent| parent.element_count() > 0)
+        });
+        parent
+            .or_else(|| {
+                self.fallback_domain()
+                    .filter(|fallback| fallback.id != domain_id)
+                    .map(|fallback| fallback.id.clone())
+            })
+            .ok_or_else(|| {
+                HyphaeicError::InvalidInput(format!(
+                    "Domain '{}' has no parent or {} to return focus to",
+                    domain_id, FALLBACK_DOMAIN
+                ))
+            })
+    }
+
+    /// Switch to the next (or previous) domain that has elements, ordered by id and
+    /// wrapping around, regardless of bounds. Used for Tab/Shift-Tab cycling.
+    pub fn cycle_domain(&mut self, forward: bool) -> NavigationResult {
+        let mut domain_ids: Vec<&String> = self
+            .domains
+            .iter()
+            .filter(|(_, domain)| domain.element_count() > 0)
+            .map(|(id, _)| id)
+            .collect();
+        domain_ids.sort();
+
+        let count = domain_ids.len();
+        if count == 0 {
+            return NavigationResult::BoundaryReached {
+                reason: BlockReason::EmptyDomain,
+            };
+        }
+
+        let current = self
+            .active_domain_id
+            .as_ref()
+            .and_then(|active| domain_ids.iter().position(|id| *id == active));
+        let next = match (current, forward) {
+            (Some(i), true) => (i + 1) % count,
+            (Some(i), false) => (i + count - 1) % count,
+            (None, true) => 0,
+            (None, false) => count - 1,
+        };
+
+        if current == Some(next) {
+            // The active domain is the only one with elements
+            return NavigationResult::BoundaryReached {
+                reason: BlockReason::NoAdjacentDomain,
+            };
+        }
+
+        let target_domain_id = domain_ids[next].clone();
+        self.switch_to_domain(&target_domain_id)
+    }
+
     /// Update domain bounds (for spatial navigation between domains)
     pub fn update_domain_bounds(
         &mut self,
         domain_id: &str,
         bounds: Option<Rect>,
-    ) -> Result<(), String> {
+    ) -> Result<(), HyphaeicError> {
         let domain = self
             .domains
             .get_mut(domain_id)
-            .ok_or_else(|| format!("Domain '{}' not found", domain_id))?;
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.to_string()))?;
 
         if let Some(b) = &bounds {
-            println!(
+            trace!(
                 "[NAV DEBUG] update_domain_bounds: '{}' => x={}, y={}, w={}, h={}",
-                domain_id, b.x, b.y, b.width, b.height
+                domain_id,
+                b.x,
+                b.y,
+                b.width,
+                b.height
             );
         } else {
-            println!("[NAV DEBUG] update_domain_bounds: '{}' => None", domain_id);
+            trace!("[NAV DEBUG] update_domain_bounds: '{}' => None", domain_id);
         }
 
         domain.bounds = bounds;
         Ok(())
     }
 
+    /// Apply bounds to many domains at once (e.g. after a window resize).
+    /// Unknown domain ids are skipped and returned; the rest are still applied.
+    pub fn update_domains_bounds(&mut self, updates: Vec<(String, Option<Rect>)>) -> Vec<String> {
+        let total = updates.len();
+        let mut missing = Vec::new();
+        for (domain_id, bounds) in updates {
+            match self.domains.get_mut(&domain_id) {
+                Some(domain) => domain.bounds = bounds,
+                None => missing.push(domain_id),
+            }
+        }
+        trace!(
+            "[NAV DEBUG] update_domains_bounds: {} updated, {} unknown",
+            total - missing.len(),
+            missing.len()
+        );
+        missing
+    }
+
+    /// Get the bounds of a button, if it has been measured
+    pub fn get_button_bounds(&self, domain_id: &str, button_id: &str) -> Option<Rect> {
+        self.domains
+            .get(domain_id)?
+            .buttons
+            .iter()
+            .find(|b| b.id == button_id)?
+            .bounds
+    }
+
+    /// Get the bounds of a domain, if it has been measured
+    pub fn get_domain_bounds(&self, domain_id: &str) -> Option<Rect> {
+        self.domains.get(domain_id)?.bounds
+    }
+
+    /// Registered buttons of a domain, in navigation order
+    pub fn get_domain_buttons(&self, domain_id: &str) -> Result<Vec<ButtonElement>, HyphaeicError> {
+        self.domains
+            .get(domain_id)
+            .map(|domain| domain.buttons.clone())
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.to_string()))
+    }
+
+    /// Bounds of an element, if it's registered and has been measured
+    pub fn element_bounds(&self, domain_id: &str, element_id: &str) -> Option<Rect> {
+        self.domains
+            .get(domain_id)?
+            .buttons
+            .iter()
+            .find(|b| b.id == element_id)?
+            .bounds
+    }
+
     /// Get domain information for debugging
     pub fn get_domain_info(&self, domain_id: &str) -> Option<Domain> {
         self.domains.get(domain_id).cloned()
@@ -680,73 +1098,417 @@ impl DomainNavigator {
         self.domains.keys().cloned().collect()
     }
 
+    /// Clone the entire navigation state into a single serializable struct
+    pub fn snapshot(&self) -> NavigationSnapshot {
+        NavigationSnapshot {
+            domains: self.domains.clone(),
+            active_domain_id: self.active_domain_id.clone(),
+            cursor_position: self.cursor_position.clone(),
+            saved_cursor_positions: self.saved_cursor_positions.clone(),
+            saved_active_domain: self.saved_active_domain.clone(),
+            last_focused: self.last_focused.clone(),
+            spatial_weight: self.spatial_weight,
+        }
+    }
+
+    /// Replace the entire navigation state with a previously captured snapshot.
+    /// Dangling references are repaired rather than rejected: the cursor falls back to
+    /// the active domain's first element, and saved state for missing domains is dropped.
+    pub fn load_snapshot(&mut self, snapshot: NavigationSnapshot) {
+        let mut domains = snapshot.domains;
+        for (id, domain) in domains.iter_mut() {
+            domain.id = id.clone();
+            if domain.current_index >= domain.element_count() {
+                domain.current_index = 0;
+            }
+        }
+
+        let cursor_valid = |cursor: &CursorPosition| {
+            domains
+                .get(&cursor.domain_id)
+                .is_some_and(|d| d.find_element_index(&cursor.element_id).is_some())
+        };
+
+        let mut active_domain_id = snapshot
+            .active_domain_id
+            .filter(|id| domains.contains_key(id));
+        let mut cursor_position = snapshot.cursor_position.filter(cursor_valid);
+
+        match &cursor_position {
+            // Keep the active domain consistent with where the cursor is
+            Some(cursor) => active_domain_id = Some(cursor.domain_id.clone()),
+            None => {
+                cursor_position = active_domain_id.as_ref().and_then(|id| {
+                    let (element_type, element_id) = domains.get(id)?.get_element_at_index(0)?;
+                    Some(CursorPosition {
+                        domain_id: id.clone(),
+                        element_id,
+                        element_type,
+                    })
+                });
+            }
+        }
+
+        let mut saved_cursor_positions = snapshot.saved_cursor_positions;
+        saved_cursor_positions.retain(|id, _| domains.contains_key(id));
+        let mut last_focused = snapshot.last_focused;
+        last_focused.retain(|id, _| domains.contains_key(id));
+
+        trace!(
+            "[LOAD_SNAPSHOT] {} domains, active: {:?}, cursor: {:?}",
+            domains.len(),
+            active_domain_id,
+            cursor_position
+        );
+
+        self.domains = domains;
+        self.active_domain_id = active_domain_id;
+        self.cursor_position = cursor_position;
+        self.saved_cursor_positions = saved_cursor_positions;
+        self.saved_active_domain = snapshot.saved_active_domain;
+        self.last_focused = last_focused;
+        if self.set_spatial_weight(snapshot.spatial_weight).is_err() {
+            self.spatial_weight = DEFAULT_SPATIAL_WEIGHT;
+        }
+    }
+
     /// Update the layout mode of a domain
     pub fn update_layout_mode(
         &mut self,
         domain_id: &str,
         layout_mode: LayoutMode,
-    ) -> Result<(), String> {
+    ) -> Result<(), HyphaeicError> {
         let domain = self
             .domains
             .get_mut(domain_id)
-            .ok_or_else(|| format!("Domain '{}' not found", domain_id))?;
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.to_string()))?;
 
         domain.layout_mode = layout_mode;
         Ok(())
     }
-}
 
-impl Default for DomainNavigator {
-    fn default() -> Self {
-        Self::new()
+    /// Move the cursor to the first enabled element of the active domain (Home).
+    /// Returns None (and leaves the cursor alone) if there is nothing to focus.
+    pub fn focus_first(&mut self) -> Option<CursorPosition> {
+        self.focus_edge(false)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_domain_registration() {
-        let mut nav = DomainNavigator::new();
-
-        nav.register_domain(
-            "test-domain".to_string(),
-            None,
-            LayoutMode::List {
-                direction: ListDirection::Vertical,
-            },
-        )
-        .unwrap();
 
-        assert_eq!(nav.get_active_domain_id(), Some("test-domain".to_string()));
+    /// Move the cursor to the last enabled element of the active domain (End)
+    pub fn focus_last(&mut self) -> Option<CursorPosition> {
+        self.focus_edge(true)
     }
 
-    #[test]
-    fn test_button_registration() {
-        let mut nav = DomainNavigator::new();
+    fn focus_edge(&mut self, last: bool) -> Option<CursorPosition> {
+        let domain_id = self.active_domain_id.clone()?;
+        let domain = self.domains.get(&domain_id)?;
 
-        nav.register_domain(
-            "test-domain".to_string(),
-            None,
-            LayoutMode::List {
-                direction: ListDirection::Vertical,
-            },
-        )
-        .unwrap();
+        let mut enabled = (0..domain.element_count()).filter(|&i| domain.is_element_enabled(i));
+        let index = if last {
+            enabled.next_back()?
+        } else {
+            enabled.next()?
+        };
+        let (element_type, element_id) = domain.get_element_at_index(index)?;
 
-        nav.register_button("test-domain".to_string(), "btn-1".to_string(), None, 0)
-            .unwrap();
+        if let Some(domain_mut) = self.domains.get_mut(&domain_id) {
+            domain_mut.current_index = index;
+        }
 
-        let cursor = nav.get_cursor_position().unwrap();
-        assert_eq!(cursor.element_id, "btn-1");
-        assert_eq!(cursor.element_type, ElementType::Button);
+        let position = CursorPosition {
+            domain_id,
+            element_id,
+            element_type,
+        };
+        self.move_cursor(position.clone());
+        Some(position)
     }
 
-    #[test]
-    fn test_list_navigation() {
-        let mut nav = DomainNavigator::new();
-
+    /// Move the cursor to the first enabled button in `domain_id` whose label matches
+    /// (case-insensitive), making that domain active
+    pub fn focus_button_by_label(
+        &mut self,
+        domain_id: &str,
+        label: &str,
+    ) -> Result<CursorPosition, HyphaeicError> {
+        let domain = self
+            .domains
+            .get(domain_id)
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.to_string()))?;
+
+        let button_id = domain
+            .find_button_by_label(label)
+            .map(|b| b.id.clone())
+            .ok_or_else(|| HyphaeicError::LabelNotFound {
+                domain_id: domain_id.to_string(),
+                label: label.to_string(),
+            })?;
+
+        let position = CursorPosition {
+            domain_id: domain_id.to_string(),
+            element_id: button_id,
+            element_type: ElementType::Button,
+        };
+        self.active_domain_id = Some(domain_id.to_string());
+        self.move_cursor(position.clone());
+        Ok(position)
+    }
+
+    /// Move the cursor to the element at `index` (registration order) in `domain_id`,
+    /// making that domain active
+    pub fn set_cursor_index(
+        &mut self,
+        domain_id: &str,
+        index: usize,
+    ) -> Result<CursorPosition, HyphaeicError> {
+        let domain = self
+            .domains
+            .get(domain_id)
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.to_string()))?;
+
+        let (element_type, element_id) = domain.get_element_at_index(index).ok_or_else(|| {
+            HyphaeicError::InvalidInput(format!(
+                "Index {} is out of range for domain '{}' ({} elements)",
+                index,
+                domain_id,
+                domain.element_count()
+            ))
+        })?;
+
+        if let Some(domain_mut) = self.domains.get_mut(domain_id) {
+            domain_mut.current_index = index;
+        }
+
+        let position = CursorPosition {
+            domain_id: domain_id.to_string(),
+            element_id,
+            element_type,
+        };
+        self.active_domain_id = Some(domain_id.to_string());
+        self.move_cursor(position.clone());
+        Ok(position)
+    }
+
+    /// Choose what registering a button with an already-used `order` does
+    pub fn set_duplicate_order_policy(&mut self, policy: DuplicateOrderPolicy) {
+        self.duplicate_order = policy;
+    }
+
+    /// Set how strongly spatial navigation prefers aligned targets over closer ones
+    /// (perpendicular offset multiplier, default 2.0)
+    pub fn set_spatial_weight(&mut self, multiplier: f64) -> Result<(), HyphaeicError> {
+        if !multiplier.is_finite() || multiplier < 0.0 {
+            return Err(HyphaeicError::InvalidInput(format!(
+                "Invalid spatial weight {} (expected a non-negative number)",
+                multiplier
+            )));
+        }
+        self.spatial_weight = multiplier;
+        Ok(())
+    }
+
+    /// Replace the directions the cursor may not exit a domain through.
+    /// All directions are validated first, so an invalid one leaves the locks unchanged.
+    pub fn set_boundary_lock(
+        &mut self,
+        domain_id: &str,
+        directions: &[String],
+    ) -> Result<(), HyphaeicError> {
+        let mut locks = Vec::new();
+        for direction in directions {
+            let parsed = GateDirection::from_str(direction).ok_or_else(|| {
+                HyphaeicError::InvalidInput(format!(
+                    "Invalid boundary direction '{}' (expected top, bottom, left or right)",
+                    direction
+                ))
+            })?;
+            if !locks.contains(&parsed) {
+                locks.push(parsed);
+            }
+        }
+
+        let domain = self
+            .domains
+            .get_mut(domain_id)
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.to_string()))?;
+
+        domain.boundary_lock = locks;
+        Ok(())
+    }
+
+    /// Make leaving `domain_id` through `direction` ("top", "bottom", "left", "right")
+    /// enter `target_domain_id`, instead of the domain inferred from bounds.
+    /// `None` removes the neighbor so spatial inference applies again.
+    pub fn set_domain_neighbor(
+        &mut self,
+        domain_id: &str,
+        direction: &str,
+        target_domain_id: Option<String>,
+    ) -> Result<(), HyphaeicError> {
+        let parsed = GateDirection::from_str(direction).ok_or_else(|| {
+            HyphaeicError::InvalidInput(format!(
+                "Invalid neighbor direction '{}' (expected top, bottom, left or right)",
+                direction
+            ))
+        })?;
+        if target_domain_id.as_deref() == Some(domain_id) {
+            return Err(HyphaeicError::InvalidInput(format!(
+                "Domain '{}' can't be its own neighbor",
+                domain_id
+            )));
+        }
+
+        let domain = self
+            .domains
+            .get_mut(domain_id)
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.to_string()))?;
+
+        match target_domain_id {
+            Some(target) => {
+                debug!(
+                    "[NAV] Domain '{}' {:?} neighbor set to '{}'",
+                    domain_id, parsed, target
+                );
+                domain.neighbors.insert(parsed, target);
+            }
+            None => {
+                domain.neighbors.remove(&parsed);
+            }
+        }
+        Ok(())
+    }
+
+    /// Opt a domain into returning to its last focused element on re-entry
+    pub fn set_domain_restore_focus(
+        &mut self,
+        domain_id: &str,
+        restore_focus: bool,
+    ) -> Result<(), HyphaeicError> {
+        let domain = self
+            .domains
+            .get_mut(domain_id)
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.to_string()))?;
+
+        domain.restore_focus = restore_focus;
+        Ok(())
+    }
+
+    /// Enable or disable wrap-around for a list domain
+    pub fn set_domain_wrap(&mut self, domain_id: &str, wrap: bool) -> Result<(), HyphaeicError> {
+        let domain = self
+            .domains
+            .get_mut(domain_id)
+            .ok_or_else(|| HyphaeicError::DomainNotFound(domain_id.to_string()))?;
+
+        domain.wrap = wrap;
+        Ok(())
+    }
+}
+
+/// Order for a new button given the orders already `taken` in its domain: the requested
+/// one if free, else (per `policy`) the next free order after it or an error
+fn resolve_order(
+    taken: &[usize],
+    order: usize,
+    policy: DuplicateOrderPolicy,
+    domain_id: &str,
+    button_id: &str,
+) -> Result<usize, HyphaeicError> {
+    if !taken.contains(&order) {
+        return Ok(order);
+    }
+    match policy {
+        DuplicateOrderPolicy::Reject => Err(HyphaeicError::InvalidInput(format!(
+            "Order {} is already used in domain '{}' (registering '{}')",
+            order, domain_id, button_id
+        ))),
+        DuplicateOrderPolicy::Reassign => {
+            let free = (order + 1..)
+                .find(|candidate| !taken.contains(candidate))
+                .unwrap_or(order);
+            warn!(
+                "[REGISTER_BUTTON] Order {} is already used in domain '{}'; '{}' gets order {}",
+                order, domain_id, button_id, free
+            );
+            Ok(free)
+        }
+    }
+}
+
+/// Repeatedly apply `step` from `start` until it lands on an enabled element.
+/// Returns None at the boundary, or if every element is disabled (the step count is
+/// bounded so wrapping lists can't loop forever).
+fn step_until_enabled(
+    domain: &Domain,
+    start: usize,
+    mut step: impl FnMut(usize) -> Option<usize>,
+) -> Option<usize> {
+    let mut index = start;
+    for _ in 0..domain.element_count() {
+        index = step(index)?;
+        if domain.is_element_enabled(index) {
+            return Some(index);
+        }
+    }
+    None
+}
+
+impl Default for DomainNavigator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_registration() {
+        let mut nav = DomainNavigator::new();
+
+        nav.register_domain(
+            "test-domain".to_string(),
+            None,
+            LayoutMode::List {
+                direction: ListDirection::Vertical,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(nav.get_active_domain_id(), Some("test-domain".to_string()));
+    }
+
+    #[test]
+    fn test_button_registration() {
+        let mut nav = DomainNavigator::new();
+
+        nav.register_domain(
+            "test-domain".to_string(),
+            None,
+            LayoutMode::List {
+                direction: ListDirection::Vertical,
+            },
+        )
+        .unwrap();
+
+        nav.register_button(
+            "test-domain".to_string(),
+            "btn-1".to_string(),
+            None,
+            0,
+            None,
+        )
+        .unwrap();
+
+        let cursor = nav.get_cursor_position().unwrap();
+        assert_eq!(cursor.element_id, "btn-1");
+        assert_eq!(cursor.element_type, ElementType::Button);
+    }
+
+    #[test]
+    fn test_list_navigation() {
+        let mut nav = DomainNavigator::new();
+
         nav.register_domain(
             "test-domain".to_string(),
             None,
@@ -758,8 +1520,14 @@ mod tests {
 
         // Add 3 buttons
         for i in 0..3 {
-            nav.register_button("test-domain".to_string(), format!("btn-{}", i), None, i)
-                .unwrap();
+            nav.register_button(
+                "test-domain".to_string(),
+                format!("btn-{}", i),
+                None,
+                i,
+                None,
+            )
+            .unwrap();
         }
 
         // Should start at first button
@@ -784,50 +1552,1056 @@ mod tests {
 
         // Try to go past end - should hit boundary
         let result = nav.handle_wasd_input(WASDKey::S);
-        assert!(matches!(result, NavigationResult::BoundaryReached));
+        assert!(matches!(
+            result,
+            NavigationResult::BoundaryReached {
+                reason: BlockReason::NoAdjacentDomain
+            }
+        ));
     }
 
     #[test]
-    fn test_button_unregister_reregister_preserves_cursor() {
+    fn test_boundary_reached_reports_reason() {
+        let mut nav = DomainNavigator::new();
+
+        nav.register_domain("grid".to_string(), None, LayoutMode::Grid { columns: 2 })
+            .unwrap();
+        let result = nav.handle_wasd_input(WASDKey::S);
+        assert!(matches!(
+            result,
+            NavigationResult::BoundaryReached {
+                reason: BlockReason::EmptyDomain
+            }
+        ));
+
+        nav.register_button("grid".to_string(), "btn-0".to_string(), None, 0, None)
+            .unwrap();
+        let result = nav.handle_wasd_input(WASDKey::E);
+        assert!(matches!(
+            result,
+            NavigationResult::BoundaryReached {
+                reason: BlockReason::Edge
+            }
+        ));
+        let result = nav.handle_wasd_input(WASDKey::D);
+        assert!(matches!(
+            result,
+            NavigationResult::BoundaryReached {
+                reason: BlockReason::NoAdjacentDomain
+            }
+        ));
+        assert_eq!(
+            serde_json::to_value(result).unwrap(),
+            serde_json::json!({ "type": "BoundaryReached", "reason": "no_adjacent_domain" })
+        );
+    }
+
+    #[test]
+    fn test_list_wrap_around() {
         let mut nav = DomainNavigator::new();
 
         nav.register_domain(
-            "test-domain".to_string(),
+            "menu".to_string(),
             None,
             LayoutMode::List {
-                direction: ListDirection::Horizontal,
+                direction: ListDirection::Vertical,
             },
         )
         .unwrap();
+        for i in 0..3 {
+            nav.register_button("menu".to_string(), format!("btn-{}", i), None, i, None)
+                .unwrap();
+        }
+        nav.set_domain_wrap("menu", true).unwrap();
 
-        // Add 3 buttons (like window header buttons: minimize, maximize, close)
-        nav.register_button("test-domain".to_string(), "btn-min".to_string(), None, 0)
+        // Up from the first element wraps to the last
+        let result = nav.handle_wasd_input(WASDKey::W);
+        if let NavigationResult::CursorMoved { element_id, .. } = result {
+            assert_eq!(element_id, "btn-2");
+        } else {
+            panic!("Expected CursorMoved");
+        }
+
+        // Down from the last element wraps back to the first
+        let result = nav.handle_wasd_input(WASDKey::S);
+        if let NavigationResult::CursorMoved { element_id, .. } = result {
+            assert_eq!(element_id, "btn-0");
+        } else {
+            panic!("Expected CursorMoved");
+        }
+
+        // Turning wrap off restores the boundary
+        nav.set_domain_wrap("menu", false).unwrap();
+        let result = nav.handle_wasd_input(WASDKey::W);
+        assert!(matches!(
+            result,
+            NavigationResult::BoundaryReached {
+                reason: BlockReason::NoAdjacentDomain
+            }
+        ));
+    }
+
+    #[test]
+    fn test_list_wrap_takes_priority_over_domain_crossing() {
+        let mut nav = DomainNavigator::new();
+        let list = LayoutMode::List {
+            direction: ListDirection::Vertical,
+        };
+
+        nav.register_domain("menu".to_string(), None, list.clone())
             .unwrap();
-        nav.register_button("test-domain".to_string(), "btn-max".to_string(), None, 1)
+        nav.register_domain("below".to_string(), None, list)
             .unwrap();
-        nav.register_button("test-domain".to_string(), "btn-close".to_string(), None, 2)
+        for i in 0..2 {
+            nav.register_button("menu".to_string(), format!("menu-{}", i), None, i, None)
+                .unwrap();
+        }
+        nav.register_button("below".to_string(), "below-0".to_string(), None, 0, None)
             .unwrap();
+        nav.update_domain_bounds(
+            "menu",
+            Some(Rect {
+                x: 0.0,
+                y: 0.0,
+                width: 100.0,
+                height: 100.0,
+            }),
+        )
+        .unwrap();
+        nav.update_domain_bounds(
+            "below",
+            Some(Rect {
+                x: 0.0,
+                y: 120.0,
+                width: 100.0,
+                height: 100.0,
+            }),
+        )
+        .unwrap();
 
-        // Navigate to middle button (maximize)
-        nav.handle_wasd_input(WASDKey::D);
-        let cursor = nav.get_cursor_position().unwrap();
-        assert_eq!(cursor.element_id, "btn-max");
+        // Move to the last element of the menu
+        nav.handle_wasd_input(WASDKey::S);
 
-        // Simulate resize: unregister all buttons
-        nav.unregister_button("test-domain", "btn-min").unwrap();
-        nav.unregister_button("test-domain", "btn-max").unwrap();
-        nav.unregister_button("test-domain", "btn-close").unwrap();
+        // Without wrap, pressing down crosses into the domain below
+        let result = nav.handle_wasd_input(WASDKey::S);
+        assert!(matches!(
+            result,
+            NavigationResult::DomainBoundaryCrossed { ref to_domain, .. } if to_domain == "below"
+        ));
 
-        // Cursor should be cleared after unregistering the focused button
-        assert!(nav.get_cursor_position().is_none());
+        // With wrap, the list wraps instead
+        nav.set_domain_wrap("menu", true).unwrap();
+        let result = nav.handle_wasd_input(WASDKey::S);
+        if let NavigationResult::CursorMoved { element_id, .. } = result {
+            assert_eq!(element_id, "menu-0");
+        } else {
+            panic!("Expected CursorMoved");
+        }
+    }
 
-        // Re-register all buttons (simulating re-registration after resize)
-        nav.register_button("test-domain".to_string(), "btn-min".to_string(), None, 0)
+    #[test]
+    fn test_navigation_skips_disabled_buttons() {
+        let mut nav = DomainNavigator::new();
+
+        nav.register_domain(
+            "test-domain".to_string(),
+            None,
+            LayoutMode::List {
+                direction: ListDirection::Vertical,
+            },
+        )
+        .unwrap();
+        for i in 0..3 {
+            nav.register_button(
+                "test-domain".to_string(),
+                format!("btn-{}", i),
+                None,
+                i,
+                None,
+            )
+            .unwrap();
+        }
+        nav.set_button_enabled("test-domain", "btn-1", false)
             .unwrap();
-        nav.register_button("test-domain".to_string(), "btn-max".to_string(), None, 1)
+
+        // S skips the disabled middle button
+        let result = nav.handle_wasd_input(WASDKey::S);
+        if let NavigationResult::CursorMoved { element_id, .. } = result {
+            assert_eq!(element_id, "btn-2");
+        } else {
+            panic!("Expected CursorMoved");
+        }
+
+        // And W skips it on the way back
+        let result = nav.handle_wasd_input(WASDKey::W);
+        if let NavigationResult::CursorMoved { element_id, .. } = result {
+            assert_eq!(element_id, "btn-0");
+        } else {
+            panic!("Expected CursorMoved");
+        }
+
+        // Nothing enabled below the cursor once btn-2 is disabled too
+        nav.set_button_enabled("test-domain", "btn-2", false)
             .unwrap();
-        nav.register_button("test-domain".to_string(), "btn-close".to_string(), None, 2)
+        let result = nav.handle_wasd_input(WASDKey::S);
+        assert!(matches!(
+            result,
+            NavigationResult::BoundaryReached {
+                reason: BlockReason::NoAdjacentDomain
+            }
+        ));
+    }
+
+    #[test]
+    fn test_peek_neighbor_does_not_move_cursor() {
+        let mut nav = DomainNavigator::new();
+
+        nav.register_domain(
+            "test-domain".to_string(),
+            None,
+            LayoutMode::List {
+                direction: ListDirection::Vertical,
+            },
+        )
+        .unwrap();
+        for i in 0..2 {
+            nav.register_button(
+                "test-domain".to_string(),
+                format!("btn-{}", i),
+                None,
+                i,
+                None,
+            )
             .unwrap();
+        }
+
+        assert_eq!(
+            nav.peek_neighbor(WASDKey::S),
+            Some(("test-domain".to_string(), "btn-1".to_string()))
+        );
+        assert_eq!(nav.peek_neighbor(WASDKey::W), None);
+
+        // Peeking leaves the cursor where it was
+        let cursor = nav.get_cursor_position().unwrap();
+        assert_eq!(cursor.element_id, "btn-0");
+
+        // And agrees with the actual move
+        let result = nav.handle_wasd_input(WASDKey::S);
+        if let NavigationResult::CursorMoved { element_id, .. } = result {
+            assert_eq!(element_id, "btn-1");
+        } else {
+            panic!("Expected CursorMoved");
+        }
+    }
+
+    #[test]
+    fn test_go_to_parent() {
+        let mut nav = DomainNavigator::new();
+        let list = LayoutMode::List {
+            direction: ListDirection::Vertical,
+        };
+
+        nav.register_domain("root".to_string(), None, list.clone())
+            .unwrap();
+        nav.register_domain("child".to_string(), Some("root".to_string()), list)
+            .unwrap();
+        nav.register_button("root".to_string(), "root-btn".to_string(), None, 0, None)
+            .unwrap();
+        nav.register_button("child".to_string(), "child-btn".to_string(), None, 0, None)
+            .unwrap();
+
+        nav.set_active_domain("child".to_string()).unwrap();
+
+        let result = nav.go_to_parent();
+        if let NavigationResult::DomainSwitched {
+            from_domain,
+            to_domain,
+            new_element_id,
+        } = result
+        {
+            assert_eq!(from_domain, "child");
+            assert_eq!(to_domain, "root");
+            assert_eq!(new_element_id, "root-btn");
+        } else {
+            panic!("Expected DomainSwitched");
+        }
+        assert_eq!(nav.get_active_domain_id().as_deref(), Some("root"));
+        assert_eq!(nav.get_cursor_position().unwrap().element_id, "root-btn");
+
+        // The root has no parent, so going up again is an error and stays put
+        let result = nav.go_to_parent();
+        assert!(matches!(result, NavigationResult::Error { .. }));
+        assert_eq!(nav.get_active_domain_id().as_deref(), Some("root"));
+    }
+
+    #[test]
+    fn test_dismiss_target_prefers_parent_then_fallback() {
+        let mut nav = DomainNavigator::new();
+        let list = LayoutMode::List {
+            direction: ListDirection::Vertical,
+        };
+
+        let domains = [
+            (FALLBACK_DOMAIN, None),
+            ("apps", None),
+            ("apps-menu", Some("apps")),
+            ("popup", Some("empty-parent")),
+            ("empty-parent", None),
+        ];
+        for (id, parent) in domains {
+            nav.register_domain(id.to_string(), parent.map(str::to_string), list.clone())
+                .unwrap();
+        }
+        for id in [FALLBACK_DOMAIN, "apps", "apps-menu", "popup"] {
+            nav.register_button(id.to_string(), format!("{}-btn", id), None, 0, None)
+                .unwrap();
+        }
+
+        nav.set_active_domain("apps-menu".to_string()).unwrap();
+        let target = nav.dismiss_target("apps-menu").unwrap();
+        assert_eq!(target, "apps");
+        assert!(matches!(
+            nav.switch_to_domain(&target),
+            NavigationResult::DomainSwitched { from_domain, to_domain, .. }
+                if from_domain == "apps-menu" && to_domain == "apps"
+        ));
+
+        // A parent with nothing to focus falls back to the osbar
+        assert_eq!(nav.dismiss_target("popup").unwrap(), FALLBACK_DOMAIN);
+
+        // The osbar itself has nowhere to return to
+        assert!(matches!(
+            nav.dismiss_target(FALLBACK_DOMAIN),
+            Err(HyphaeicError::InvalidInput(_))
+        ));
+        assert_eq!(
+            nav.dismiss_target("missing").unwrap_err(),
+            HyphaeicError::DomainNotFound("missing".to_string())
+        );
+    }
+
+    #[test]
+    fn test_dismiss_target_errors_without_parent_or_fallback() {
+        let mut nav = DomainNavigator::new();
+        let list = LayoutMode::List {
+            direction: ListDirection::Vertical,
+        };
+
+        // An empty osbar and an empty parent leave nowhere to send focus
+        for (id, parent) in [
+            (FALLBACK_DOMAIN, None),
+            ("apps", None),
+            ("menu", Some("apps")),
+        ] {
+            nav.register_domain(id.to_string(), parent.map(str::to_string), list.clone())
+                .unwrap();
+        }
+        nav.register_button("menu".to_string(), "launch".to_string(), None, 0, None)
+            .unwrap();
+
+        assert!(matches!(
+            nav.dismiss_target("menu"),
+            Err(HyphaeicError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_restore_focus_on_reentry() {
+        let mut nav = DomainNavigator::new();
+        let list = LayoutMode::List {
+            direction: ListDirection::Vertical,
+        };
+
+        nav.register_domain("menu".to_string(), None, list.clone())
+            .unwrap();
+        nav.register_domain("other".to_string(), None, list)
+            .unwrap();
+        for i in 0..3 {
+            nav.register_button("menu".to_string(), format!("menu-{}", i), None, i, None)
+                .unwrap();
+        }
+        nav.register_button("other".to_string(), "other-0".to_string(), None, 0, None)
+            .unwrap();
+
+        // Move to the last menu element, then leave
+        nav.handle_wasd_input(WASDKey::S);
+        nav.handle_wasd_input(WASDKey::S);
+        nav.switch_to_domain("other");
+
+        // Default behavior: re-entry starts at the top
+        nav.switch_to_domain("menu");
+        assert_eq!(nav.get_cursor_position().unwrap().element_id, "menu-0");
+
+        // Opted in: re-entry returns to the last focused element
+        nav.set_domain_restore_focus("menu", true).unwrap();
+        nav.set_cursor_position("menu", "menu-2").unwrap();
+        nav.switch_to_domain("other");
+        nav.switch_to_domain("menu");
+        assert_eq!(nav.get_cursor_position().unwrap().element_id, "menu-2");
+
+        // Falls back to the first element if the remembered one is gone
+        nav.switch_to_domain("other");
+        nav.unregister_button("menu", "menu-2").unwrap();
+        nav.set_active_domain("menu".to_string()).unwrap();
+        assert_eq!(nav.get_cursor_position().unwrap().element_id, "menu-0");
+    }
+
+    #[test]
+    fn test_focus_button_by_label() {
+        let mut nav = DomainNavigator::new();
+
+        nav.register_domain("grid".to_string(), None, LayoutMode::Grid { columns: 3 })
+            .unwrap();
+        let labels = ["Files", "Terminal", "Settings"];
+        for (i, label) in labels.iter().enumerate() {
+            nav.register_button(
+                "grid".to_string(),
+                format!("btn-{}", i),
+                None,
+                i,
+                Some(label.to_string()),
+            )
+            .unwrap();
+        }
+
+        let position = nav.focus_button_by_label("grid", "terminal").unwrap();
+        assert_eq!(position.element_id, "btn-1");
+        assert_eq!(nav.get_cursor_position().unwrap().element_id, "btn-1");
+
+        // Disabled buttons don't match
+        nav.set_button_enabled("grid", "btn-2", false).unwrap();
+        assert!(nav.focus_button_by_label("grid", "Settings").is_err());
+        assert!(nav.focus_button_by_label("grid", "Missing").is_err());
+        assert_eq!(nav.get_cursor_position().unwrap().element_id, "btn-1");
+    }
+
+    #[test]
+    fn test_set_cursor_index() {
+        let mut nav = DomainNavigator::new();
+
+        nav.register_domain("list".to_string(), None, LayoutMode::Grid { columns: 1 })
+            .unwrap();
+        for i in 0..3 {
+            nav.register_button("list".to_string(), format!("btn-{}", i), None, i, None)
+                .unwrap();
+        }
+
+        let position = nav.set_cursor_index("list", 2).unwrap();
+        assert_eq!(position.element_id, "btn-2");
+        assert_eq!(nav.get_cursor_position().unwrap().element_id, "btn-2");
+
+        // Navigation continues from the new index
+        let result = nav.handle_wasd_input(WASDKey::W);
+        assert!(
+            matches!(result, NavigationResult::CursorMoved { element_id, .. } if element_id == "btn-1")
+        );
+
+        let err = nav.set_cursor_index("list", 3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Index 3 is out of range for domain 'list' (3 elements)"
+        );
+        assert!(matches!(
+            nav.set_cursor_index("missing", 0),
+            Err(HyphaeicError::DomainNotFound(_))
+        ));
+        assert_eq!(nav.get_cursor_position().unwrap().element_id, "btn-1");
+    }
+
+    #[test]
+    fn test_get_domain_buttons() {
+        let mut nav = DomainNavigator::new();
+
+        nav.register_domain("grid".to_string(), None, LayoutMode::Grid { columns: 2 })
+            .unwrap();
+        let bounds = Rect {
+            x: 10.0,
+            y: 20.0,
+            width: 100.0,
+            height: 40.0,
+        };
+        nav.register_button("grid".to_string(), "second".to_string(), None, 1, None)
+            .unwrap();
+        nav.register_button(
+            "grid".to_string(),
+            "first".to_string(),
+            Some(bounds),
+            0,
+            Some("First".to_string()),
+        )
+        .unwrap();
+        nav.set_button_enabled("grid", "second", false).unwrap();
+
+        let buttons = nav.get_domain_buttons("grid").unwrap();
+        let ids: Vec<&str> = buttons.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, ["first", "second"]);
+        assert_eq!(buttons[0].bounds.unwrap().width, 100.0);
+        assert!(buttons[0].enabled);
+        assert!(!buttons[1].enabled);
+
+        assert_eq!(
+            nav.get_domain_buttons("missing").unwrap_err(),
+            HyphaeicError::DomainNotFound("missing".to_string())
+        );
+    }
+
+    #[test]
+    fn test_update_domains_bounds_skips_unknown() {
+        let mut nav = DomainNavigator::new();
+        for id in ["left", "right"] {
+            nav.register_domain(id.to_string(), None, LayoutMode::Grid { columns: 1 })
+                .unwrap();
+        }
+        let rect = |x: f64| Rect {
+            x,
+            y: 0.0,
+            width: 100.0,
+            height: 100.0,
+        };
+
+        let missing = nav.update_domains_bounds(vec![
+            ("left".to_string(), Some(rect(0.0))),
+            ("gone".to_string(), Some(rect(50.0))),
+            ("right".to_string(), Some(rect(200.0))),
+        ]);
+        assert_eq!(missing, ["gone"]);
+        assert_eq!(nav.get_domain_info("left").unwrap().bounds.unwrap().x, 0.0);
+        assert_eq!(
+            nav.get_domain_info("right").unwrap().bounds.unwrap().x,
+            200.0
+        );
+
+        let missing = nav.update_domains_bounds(vec![("left".to_string(), None)]);
+        assert!(missing.is_empty());
+        assert!(nav.get_domain_info("left").unwrap().bounds.is_none());
+    }
+
+    #[test]
+    fn test_duplicate_order_is_reassigned_or_rejected() {
+        let mut nav = DomainNavigator::new();
+
+        nav.register_domain("list".to_string(), None, LayoutMode::Grid { columns: 1 })
+            .unwrap();
+        for id in ["first", "second"] {
+            nav.register_button("list".to_string(), id.to_string(), None, 1, None)
+                .unwrap();
+        }
+        nav.register_button("list".to_string(), "zero".to_string(), None, 0, None)
+            .unwrap();
+
+        let order_of = |nav: &DomainNavigator| -> Vec<(String, usize)> {
+            nav.get_domain_buttons("list")
+                .unwrap()
+                .into_iter()
+                .map(|b| (b.id, b.order))
+                .collect()
+        };
+        let expected = |pairs: &[(&str, usize)]| -> Vec<(String, usize)> {
+            pairs.iter().map(|(id, o)| (id.to_string(), *o)).collect()
+        };
+        assert_eq!(
+            order_of(&nav),
+            expected(&[("zero", 0), ("first", 1), ("second", 2)])
+        );
+
+        // Batches resolve against the domain and earlier buttons in the same batch
+        let spec = |id: &str, order| ButtonSpec {
+            id: id.to_string(),
+            bounds: None,
+            order,
+            label: None,
+        };
+        nav.register_buttons(
+            "list".to_string(),
+            vec![spec("third", 2), spec("fourth", 2)],
+        )
+        .unwrap();
+        assert_eq!(
+            order_of(&nav),
+            expected(&[
+                ("zero", 0),
+                ("first", 1),
+                ("second", 2),
+                ("third", 3),
+                ("fourth", 4)
+            ])
+        );
+
+        nav.set_duplicate_order_policy(DuplicateOrderPolicy::Reject);
+        let err = nav
+            .register_button("list".to_string(), "late".to_string(), None, 1, None)
+            .unwrap_err();
+        assert!(matches!(err, HyphaeicError::InvalidInput(_)));
+        assert!(nav
+            .register_buttons("list".to_string(), vec![spec("a", 9), spec("b", 9)])
+            .is_err());
+        assert_eq!(nav.get_domain_buttons("list").unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_focus_first_and_last_skip_disabled_edges() {
+        let mut nav = DomainNavigator::new();
+
+        nav.register_domain("grid".to_string(), None, LayoutMode::Grid { columns: 2 })
+            .unwrap();
+
+        // Empty domain: nothing to focus
+        assert!(nav.focus_first().is_none());
+        assert!(nav.focus_last().is_none());
+
+        for i in 0..4 {
+            nav.register_button("grid".to_string(), format!("btn-{}", i), None, i, None)
+                .unwrap();
+        }
+        nav.set_button_enabled("grid", "btn-0", false).unwrap();
+        nav.set_button_enabled("grid", "btn-3", false).unwrap();
+
+        assert_eq!(nav.focus_last().unwrap().element_id, "btn-2");
+        assert_eq!(nav.get_cursor_position().unwrap().element_id, "btn-2");
+        assert_eq!(nav.focus_first().unwrap().element_id, "btn-1");
+        assert_eq!(nav.get_cursor_position().unwrap().element_id, "btn-1");
+    }
+
+    #[test]
+    fn test_set_boundary_lock() {
+        let mut nav = DomainNavigator::new();
+        let list = LayoutMode::List {
+            direction: ListDirection::Horizontal,
+        };
+
+        nav.register_domain("modal".to_string(), None, list.clone())
+            .unwrap();
+        nav.register_domain("neighbor".to_string(), None, list)
+            .unwrap();
+        nav.register_button("modal".to_string(), "modal-0".to_string(), None, 0, None)
+            .unwrap();
+        nav.register_button(
+            "neighbor".to_string(),
+            "neighbor-0".to_string(),
+            None,
+            0,
+            None,
+        )
+        .unwrap();
+        nav.update_domain_bounds(
+            "modal",
+            Some(Rect {
+                x: 0.0,
+                y: 0.0,
+                width: 100.0,
+                height: 100.0,
+            }),
+        )
+        .unwrap();
+        nav.update_domain_bounds(
+            "neighbor",
+            Some(Rect {
+                x: 120.0,
+                y: 0.0,
+                width: 100.0,
+                height: 100.0,
+            }),
+        )
+        .unwrap();
+
+        nav.set_boundary_lock("modal", &["Left".to_string(), "right".to_string()])
+            .unwrap();
+        let result = nav.handle_wasd_input(WASDKey::D);
+        assert!(matches!(
+            result,
+            NavigationResult::BoundaryReached {
+                reason: BlockReason::BoundaryLock
+            }
+        ));
+
+        // An invalid direction is rejected and the existing locks stay in place
+        let err = nav
+            .set_boundary_lock("modal", &["up".to_string()])
+            .unwrap_err();
+        assert!(
+            matches!(&err, HyphaeicError::InvalidInput(message) if message.contains("'up'")),
+            "Unexpected error: {}",
+            err
+        );
+        let result = nav.handle_wasd_input(WASDKey::D);
+        assert!(matches!(
+            result,
+            NavigationResult::BoundaryReached {
+                reason: BlockReason::BoundaryLock
+            }
+        ));
+
+        // Clearing the locks lets the cursor escape again
+        nav.set_boundary_lock("modal", &[]).unwrap();
+        let result = nav.handle_wasd_input(WASDKey::D);
+        assert!(matches!(
+            result,
+            NavigationResult::DomainBoundaryCrossed { ref to_domain, .. } if to_domain == "neighbor"
+        ));
+    }
+
+    #[test]
+    fn test_adjacent_domains_respects_boundary_lock() {
+        let mut nav = DomainNavigator::new();
+        let list = LayoutMode::List {
+            direction: ListDirection::Horizontal,
+        };
+        let layout = [
+            ("center", 120.0, 120.0),
+            ("above", 120.0, 0.0),
+            ("right", 240.0, 120.0),
+        ];
+        for (id, x, y) in layout {
+            nav.register_domain(id.to_string(), None, list.clone())
+                .unwrap();
+            nav.register_button(id.to_string(), format!("{}-0", id), None, 0, None)
+                .unwrap();
+            nav.update_domain_bounds(
+                id,
+                Some(Rect {
+                    x,
+                    y,
+                    width: 100.0,
+                    height: 100.0,
+                }),
+            )
+            .unwrap();
+        }
+
+        let adjacent = nav.adjacent_domains("center").unwrap();
+        assert_eq!(adjacent["up"].as_deref(), Some("above"));
+        assert_eq!(adjacent["right"].as_deref(), Some("right"));
+        assert_eq!(adjacent["down"], None);
+        assert_eq!(adjacent["left"], None);
+
+        nav.set_boundary_lock("center", &["top".to_string()])
+            .unwrap();
+        let adjacent = nav.adjacent_domains("center").unwrap();
+        assert_eq!(adjacent["up"], None);
+        assert_eq!(adjacent["right"].as_deref(), Some("right"));
+
+        // Read-only: the cursor stays where registration put it
+        assert_eq!(nav.get_cursor_position().unwrap().domain_id, "center");
+        assert!(nav.adjacent_domains("missing").is_err());
+    }
+
+    #[test]
+    fn test_explicit_neighbor_overrides_spatial_adjacency() {
+        let mut nav = DomainNavigator::new();
+        let list = LayoutMode::List {
+            direction: ListDirection::Horizontal,
+        };
+        let layout = [
+            ("center", 120.0, 120.0),
+            ("right", 240.0, 120.0),
+            ("sidebar", 0.0, 400.0),
+        ];
+        for (id, x, y) in layout {
+            nav.register_domain(id.to_string(), None, list.clone())
+                .unwrap();
+            nav.register_button(id.to_string(), format!("{}-0", id), None, 0, None)
+                .unwrap();
+            nav.update_domain_bounds(
+                id,
+                Some(Rect {
+                    x,
+                    y,
+                    width: 100.0,
+                    height: 100.0,
+                }),
+            )
+            .unwrap();
+        }
+        assert_eq!(nav.get_cursor_position().unwrap().domain_id, "center");
+
+        nav.set_domain_neighbor("center", "Right", Some("sidebar".to_string()))
+            .unwrap();
+        assert_eq!(
+            nav.adjacent_domains("center").unwrap()["right"].as_deref(),
+            Some("sidebar")
+        );
+        assert!(matches!(
+            nav.handle_wasd_input(WASDKey::D),
+            NavigationResult::DomainBoundaryCrossed { to_domain, .. } if to_domain == "sidebar"
+        ));
+
+        // A neighbor that isn't registered (or is empty) falls back to the bounds
+        nav.set_domain_neighbor("center", "right", Some("later".to_string()))
+            .unwrap();
+        assert_eq!(
+            nav.adjacent_domains("center").unwrap()["right"].as_deref(),
+            Some("right")
+        );
+
+        // Locks still win, and clearing the neighbor restores spatial inference
+        nav.set_domain_neighbor("center", "left", Some("sidebar".to_string()))
+            .unwrap();
+        nav.set_boundary_lock("center", &["left".to_string()])
+            .unwrap();
+        assert_eq!(nav.adjacent_domains("center").unwrap()["left"], None);
+        nav.set_domain_neighbor("center", "right", None).unwrap();
+        assert_eq!(
+            nav.adjacent_domains("center").unwrap()["right"].as_deref(),
+            Some("right")
+        );
+
+        assert!(nav
+            .set_domain_neighbor("center", "up", Some("right".to_string()))
+            .is_err());
+        assert!(nav
+            .set_domain_neighbor("center", "top", Some("center".to_string()))
+            .is_err());
+        assert!(nav
+            .set_domain_neighbor("missing", "top", Some("center".to_string()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_snapshot_captures_saved_cursor() {
+        let mut nav = DomainNavigator::new();
+        nav.register_domain(
+            "menu".to_string(),
+            None,
+            LayoutMode::List {
+                direction: ListDirection::Vertical,
+            },
+        )
+        .unwrap();
+        nav.register_button("menu".to_string(), "menu-0".to_string(), None, 0, None)
+            .unwrap();
+
+        let snapshot = nav.snapshot();
+        assert_eq!(snapshot.active_domain_id.as_deref(), Some("menu"));
+        assert_eq!(snapshot.domains["menu"].buttons.len(), 1);
+        assert_eq!(snapshot.cursor_position.unwrap().element_id, "menu-0");
+
+        // Unregistering the focused button moves the cursor into the saved map
+        nav.unregister_button("menu", "menu-0").unwrap();
+        let snapshot = nav.snapshot();
+        assert!(snapshot.cursor_position.is_none());
+        assert_eq!(snapshot.saved_cursor_positions["menu"].element_id, "menu-0");
+    }
+
+    #[test]
+    fn test_load_snapshot_round_trip_and_repair() {
+        let mut nav = DomainNavigator::new();
+        nav.register_domain(
+            "menu".to_string(),
+            None,
+            LayoutMode::List {
+                direction: ListDirection::Vertical,
+            },
+        )
+        .unwrap();
+        for i in 0..3 {
+            nav.register_button("menu".to_string(), format!("menu-{}", i), None, i, None)
+                .unwrap();
+        }
+        nav.set_cursor_position("menu", "menu-2").unwrap();
+        let snapshot = nav.snapshot();
+
+        // A fresh navigator (e.g. after a hot reload) picks up where we left off
+        let mut restored = DomainNavigator::new();
+        restored.load_snapshot(snapshot.clone());
+        assert_eq!(restored.get_active_domain_id().as_deref(), Some("menu"));
+        assert_eq!(restored.get_cursor_position().unwrap().element_id, "menu-2");
+
+        // A cursor pointing at a vanished button falls back to the first element,
+        // and saved cursors for unknown domains are dropped
+        let mut broken = snapshot;
+        broken.domains.get_mut("menu").unwrap().buttons.pop();
+        broken.saved_cursor_positions.insert(
+            "gone".to_string(),
+            CursorPosition {
+                domain_id: "gone".to_string(),
+                element_id: "gone-0".to_string(),
+                element_type: ElementType::Button,
+            },
+        );
+        broken.spatial_weight = f64::NAN;
+        restored.load_snapshot(broken);
+        assert_eq!(restored.get_cursor_position().unwrap().element_id, "menu-0");
+        let snapshot = restored.snapshot();
+        assert!(snapshot.saved_cursor_positions.is_empty());
+        assert_eq!(snapshot.spatial_weight, DEFAULT_SPATIAL_WEIGHT);
+    }
+
+    #[test]
+    fn test_cycle_domain_skips_empty_and_wraps() {
+        let mut nav = DomainNavigator::new();
+        let list = LayoutMode::List {
+            direction: ListDirection::Horizontal,
+        };
+        for id in ["b-left", "a-osbar", "c-empty", "d-right"] {
+            nav.register_domain(id.to_string(), None, list.clone())
+                .unwrap();
+            if id != "c-empty" {
+                nav.register_button(id.to_string(), format!("{}-0", id), None, 0, None)
+                    .unwrap();
+            }
+        }
+        assert_eq!(nav.get_active_domain_id().as_deref(), Some("b-left"));
+
+        let mut cycle = |forward| match nav.cycle_domain(forward) {
+            NavigationResult::DomainSwitched { to_domain, .. } => to_domain,
+            other => panic!("Expected DomainSwitched, got {:?}", other),
+        };
+        assert_eq!(cycle(true), "d-right");
+        assert_eq!(cycle(true), "a-osbar");
+        assert_eq!(cycle(false), "d-right");
+        assert_eq!(cycle(false), "b-left");
+
+        assert_eq!(nav.get_cursor_position().unwrap().element_id, "b-left-0");
+    }
+
+    #[test]
+    fn test_register_buttons_batch() {
+        let mut nav = DomainNavigator::new();
+        nav.register_domain("grid".to_string(), None, LayoutMode::Grid { columns: 2 })
+            .unwrap();
+
+        let spec = |id: &str, order: usize| ButtonSpec {
+            id: id.to_string(),
+            bounds: None,
+            order,
+            label: None,
+        };
+
+        nav.register_buttons(
+            "grid".to_string(),
+            vec![spec("btn-2", 2), spec("btn-0", 0), spec("btn-1", 1)],
+        )
+        .unwrap();
+
+        // Sorted once by order, cursor lands on the lowest order
+        let ids: Vec<&str> = nav.domains["grid"]
+            .buttons
+            .iter()
+            .map(|b| b.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["btn-0", "btn-1", "btn-2"]);
+        assert_eq!(nav.get_cursor_position().unwrap().element_id, "btn-0");
+
+        // A duplicate anywhere in the batch rejects the whole batch
+        let err = nav
+            .register_buttons("grid".to_string(), vec![spec("btn-3", 3), spec("btn-1", 4)])
+            .unwrap_err();
+        assert_eq!(
+            err,
+            HyphaeicError::ButtonExists {
+                domain_id: "grid".to_string(),
+                button_id: "btn-1".to_string(),
+            }
+        );
+        assert_eq!(nav.domains["grid"].buttons.len(), 3);
+    }
+
+    #[test]
+    fn test_register_buttons_restores_saved_cursor() {
+        let mut nav = DomainNavigator::new();
+        nav.register_domain("grid".to_string(), None, LayoutMode::Grid { columns: 2 })
+            .unwrap();
+        for i in 0..3 {
+            nav.register_button("grid".to_string(), format!("btn-{}", i), None, i, None)
+                .unwrap();
+        }
+        nav.set_cursor_position("grid", "btn-2").unwrap();
+
+        for i in 0..3 {
+            nav.unregister_button("grid", &format!("btn-{}", i))
+                .unwrap();
+        }
+
+        let specs = (0..3)
+            .map(|i| ButtonSpec {
+                id: format!("btn-{}", i),
+                bounds: None,
+                order: i,
+                label: None,
+            })
+            .collect();
+        nav.register_buttons("grid".to_string(), specs).unwrap();
+
+        assert_eq!(nav.get_cursor_position().unwrap().element_id, "btn-2");
+    }
+
+    #[test]
+    fn test_button_unregister_reregister_preserves_cursor() {
+        let mut nav = DomainNavigator::new();
+
+        nav.register_domain(
+            "test-domain".to_string(),
+            None,
+            LayoutMode::List {
+                direction: ListDirection::Horizontal,
+            },
+        )
+        .unwrap();
+
+        // Add 3 buttons (like window header buttons: minimize, maximize, close)
+        nav.register_button(
+            "test-domain".to_string(),
+            "btn-min".to_string(),
+            None,
+            0,
+            None,
+        )
+        .unwrap();
+        nav.register_button(
+            "test-domain".to_string(),
+            "btn-max".to_string(),
+            None,
+            1,
+            None,
+        )
+        .unwrap();
+        nav.register_button(
+            "test-domain".to_string(),
+            "btn-close".to_string(),
+            None,
+            2,
+            None,
+        )
+        .unwrap();
+
+        // Navigate to middle button (maximize)
+        nav.handle_wasd_input(WASDKey::D);
+        let cursor = nav.get_cursor_position().unwrap();
+        assert_eq!(cursor.element_id, "btn-max");
+
+        // Simulate resize: unregister all buttons; only the last one empties the domain
+        assert!(!nav.unregister_button("test-domain", "btn-min").unwrap());
+        assert!(!nav.unregister_button("test-domain", "btn-max").unwrap());
+        assert!(nav.unregister_button("test-domain", "btn-close").unwrap());
+
+        // Cursor should be cleared after unregistering the focused button
+        assert!(nav.get_cursor_position().is_none());
+
+        // Re-register all buttons (simulating re-registration after resize)
+        nav.register_button(
+            "test-domain".to_string(),
+            "btn-min".to_string(),
+            None,
+            0,
+            None,
+        )
+        .unwrap();
+        nav.register_button(
+            "test-domain".to_string(),
+            "btn-max".to_string(),
+            None,
+            1,
+            None,
+        )
+        .unwrap();
+        nav.register_button(
+            "test-domain".to_string(),
+            "btn-close".to_string(),
+            None,
+            2,
+            None,
+        )
+        .unwrap();
 
         // Cursor should be restored to the maximize button
         let cursor = nav.get_cursor_position().unwrap();
@@ -5,7 +5,7 @@ pub mod types;
 pub mod domain_navigator;
 pub mod spatial;
 
-pub use domain_navigator::DomainNavigator;
+pub use domain_navigator::{DomainNavigator, FALLBACK_DOMAIN};
 pub use types::*;
 
 
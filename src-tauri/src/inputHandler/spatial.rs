@@ -1,12 +1,22 @@
 // Spatial navigation algorithms for calculating cursor movement
 
 use super::types::{Rect, WASDKey};
+use std::cmp::Ordering;
 
-/// Calculate the best next element to navigate to based on direction
+/// A navigation target: `(id, bounds, order)`. `order` breaks distance ties
+/// (lower wins, then lower id), so picks don't depend on iteration order.
+pub type SpatialCandidate = (String, Rect, usize);
+
+/// Default penalty multiplier for perpendicular offset in spatial navigation
+pub const DEFAULT_SPATIAL_WEIGHT: f64 = 2.0;
+
+/// Calculate the best next element to navigate to based on direction.
+/// `perpendicular_weight` scales how strongly aligned targets are preferred over closer ones.
 pub fn find_nearest_in_direction(
     current_bounds: &Rect,
-    candidates: &[(String, Rect)],
+    candidates: &[SpatialCandidate],
     direction: WASDKey,
+    perpendicular_weight: f64,
 ) -> Option<String> {
     if candidates.is_empty() {
         return None;
@@ -18,7 +28,7 @@ pub fn find_nearest_in_direction(
     // Filter candidates that are in the desired direction
     let valid_candidates: Vec<_> = candidates
         .iter()
-        .filter(|(_, bounds)| {
+        .filter(|(_, bounds, _)| {
             let (target_x, target_y) = bounds.center();
             is_in_direction(current_x, current_y, target_x, target_y, dx, dy)
         })
@@ -30,8 +40,9 @@ pub fn find_nearest_in_direction(
 
     // Find the closest candidate using weighted distance
     valid_candidates
-        .iter()
-        .min_by(|(_, bounds_a), (_, bounds_b)| {
+        .into_iter()
+        .min_by(|a, b| {
+            let (bounds_a, bounds_b) = (&a.1, &b.1);
             let dist_a = calculate_directional_distance(
                 current_x,
                 current_y,
@@ -39,6 +50,7 @@ pub fn find_nearest_in_direction(
                 bounds_a.center().1,
                 dx,
                 dy,
+                perpendicular_weight,
             );
             let dist_b = calculate_directional_distance(
                 current_x,
@@ -47,12 +59,94 @@ pub fn find_nearest_in_direction(
                 bounds_b.center().1,
                 dx,
                 dy,
+                perpendicular_weight,
             );
             dist_a
                 .partial_cmp(&dist_b)
-                .unwrap_or(std::cmp::Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| tie_break(a, b))
+        })
+        .map(|(id, _, _)| id.clone())
+}
+
+/// Deterministic ordering for equally distant candidates: lower order, then lower id
+fn tie_break(a: &SpatialCandidate, b: &SpatialCandidate) -> Ordering {
+    a.2.cmp(&b.2).then_with(|| a.0.cmp(&b.0))
+}
+
+/// Pixels two domain edges may overlap and still count as facing each other
+const EDGE_TOLERANCE: f64 = 1.0;
+
+/// Find the adjacent domain in a direction by comparing facing edges rather than centers.
+/// Moving right compares the current right edge to candidate left edges and requires
+/// vertical overlap (likewise for the other orthogonal directions). Diagonals, or no
+/// overlapping candidate, fall back to `find_nearest_in_direction`.
+pub fn find_adjacent_by_edge(
+    current_bounds: &Rect,
+    candidates: &[SpatialCandidate],
+    direction: WASDKey,
+    perpendicular_weight: f64,
+) -> Option<String> {
+    candidates
+        .iter()
+        .filter_map(|candidate| {
+            edge_score(current_bounds, &candidate.1, direction).map(|score| (candidate, score))
+        })
+        .min_by(|(a, score_a), (b, score_b)| {
+            score_a
+                .partial_cmp(score_b)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| tie_break(a, b))
         })
-        .map(|(id, _)| id.clone())
+        .map(|(candidate, _)| candidate.0.clone())
+        .or_else(|| {
+            find_nearest_in_direction(current_bounds, candidates, direction, perpendicular_weight)
+        })
+}
+
+/// `(gap, midpoint offset)` between the current domain's facing edge and the
+/// candidate's opposite edge, or `None` if the candidate isn't past that edge
+/// or doesn't overlap on the perpendicular axis
+fn edge_score(current: &Rect, candidate: &Rect, direction: WASDKey) -> Option<(f64, f64)> {
+    let (current_x, current_y) = current.center();
+    let (target_x, target_y) = candidate.center();
+
+    let overlaps_x = ranges_overlap(current.x, current.width, candidate.x, candidate.width);
+    let overlaps_y = ranges_overlap(current.y, current.height, candidate.y, candidate.height);
+
+    let (gap, overlaps, offset) = match direction {
+        WASDKey::D => (
+            candidate.x - (current.x + current.width),
+            overlaps_y,
+            (target_y - current_y).abs(),
+        ),
+        WASDKey::A => (
+            current.x - (candidate.x + candidate.width),
+            overlaps_y,
+            (target_y - current_y).abs(),
+        ),
+        WASDKey::S => (
+            candidate.y - (current.y + current.height),
+            overlaps_x,
+            (target_x - current_x).abs(),
+        ),
+        WASDKey::W => (
+            current.y - (candidate.y + candidate.height),
+            overlaps_x,
+            (target_x - current_x).abs(),
+        ),
+        _ => return None,
+    };
+
+    if !overlaps || gap < -EDGE_TOLERANCE {
+        return None;
+    }
+    Some((gap.max(0.0), offset))
+}
+
+/// Check if two 1D spans `[start, start + len)` overlap
+fn ranges_overlap(a_start: f64, a_len: f64, b_start: f64, b_len: f64) -> bool {
+    a_start < b_start + b_len && b_start < a_start + a_len
 }
 
 /// Check if target point is in the direction from current point
@@ -83,7 +177,8 @@ fn calculate_directional_distance(
     target_x: f64,
     target_y: f64,
     direction_x: f64,
-    _direction_y: f64,
+    direction_y: f64,
+    perpendicular_weight: f64,
 ) -> f64 {
     let to_target_x = target_x - current_x;
     let to_target_y = target_y - current_y;
@@ -92,17 +187,13 @@ fn calculate_directional_distance(
     let direct_distance = (to_target_x.powi(2) + to_target_y.powi(2)).sqrt();
 
     // Calculate perpendicular distance from the directional axis
-    // This penalizes elements that are off to the side
-    let perpendicular_distance = if direction_x != 0.0 {
-        // Horizontal movement - penalize vertical offset
-        to_target_y.abs()
-    } else {
-        // Vertical movement - penalize horizontal offset
-        to_target_x.abs()
-    };
+    // This penalizes elements that are off to the side. The cross product with the
+    // unit direction reduces to |dy| for horizontal and |dx| for vertical movement,
+    // and also works for diagonals.
+    let perpendicular_distance = (to_target_x * direction_y - to_target_y * direction_x).abs();
 
     // Weighted combination: prioritize aligned elements
-    direct_distance + perpendicular_distance * 2.0
+    direct_distance + perpendicular_distance * perpendicular_weight
 }
 
 /// Navigate in grid layout
@@ -112,7 +203,8 @@ pub fn navigate_grid(
     columns: usize,
     direction: WASDKey,
 ) -> Option<usize> {
-    if total_elements == 0 {
+    // A zero-column grid (e.g. from a restored snapshot) can't be navigated
+    if total_elements == 0 || columns == 0 {
         return None;
     }
 
@@ -130,14 +222,9 @@ pub fn navigate_grid(
             }
         }
         WASDKey::S => {
-            // Move down
+            // Move down; a ragged last row snaps to its nearest (last) element
             if current_row < rows - 1 {
-                let candidate = current_index + columns;
-                if candidate < total_elements {
-                    Some(candidate)
-                } else {
-                    None
-                }
+                Some((current_index + columns).min(total_elements - 1))
             } else {
                 None
             }
@@ -158,54 +245,41 @@ pub fn navigate_grid(
                 None
             }
         }
+        // Diagonals don't navigate in grid mode
+        _ => None,
     };
 
     new_index
 }
 
 /// Navigate in list layout
+/// With `wrap`, moving past either end jumps to the other end instead of returning None
 pub fn navigate_list(
     current_index: usize,
     total_elements: usize,
     is_vertical: bool,
+    wrap: bool,
     direction: WASDKey,
 ) -> Option<usize> {
     if total_elements == 0 {
         return None;
     }
 
-    match (is_vertical, direction) {
+    let forward = match (is_vertical, direction) {
         // Vertical list: W/S for navigation
-        (true, WASDKey::W) => {
-            if current_index > 0 {
-                Some(current_index - 1)
-            } else {
-                None
-            }
-        }
-        (true, WASDKey::S) => {
-            if current_index < total_elements - 1 {
-                Some(current_index + 1)
-            } else {
-                None
-            }
-        }
+        (true, WASDKey::W) | (false, WASDKey::A) => false,
         // Horizontal list: A/D for navigation
-        (false, WASDKey::A) => {
-            if current_index > 0 {
-                Some(current_index - 1)
-            } else {
-                None
-            }
-        }
-        (false, WASDKey::D) => {
-            if current_index < total_elements - 1 {
-                Some(current_index + 1)
-            } else {
-                None
-            }
-        }
+        (true, WASDKey::S) | (false, WASDKey::D) => true,
         // Other directions don't navigate in list mode
+        _ => return None,
+    };
+
+    let last_index = total_elements - 1;
+    match (forward, wrap) {
+        (false, _) if current_index > 0 => Some(current_index - 1),
+        (false, true) => Some(last_index),
+        (true, _) if current_index < last_index => Some(current_index + 1),
+        (true, true) => Some(0),
         _ => None,
     }
 }
@@ -220,6 +294,8 @@ mod tests {
         let columns = 3;
         let total = 9;
 
+        assert_eq!(navigate_grid(0, total, 0, WASDKey::D), None); // No columns
+
         // From center (index 4), test all directions
         assert_eq!(navigate_grid(4, total, columns, WASDKey::W), Some(1)); // Up
         assert_eq!(navigate_grid(4, total, columns, WASDKey::S), Some(7)); // Down
@@ -231,21 +307,38 @@ mod tests {
         assert_eq!(navigate_grid(0, total, columns, WASDKey::A), None);
     }
 
+    #[test]
+    fn test_grid_ragged_last_row() {
+        // 7 in 3 columns: the last row only has index 6
+        assert_eq!(navigate_grid(3, 7, 3, WASDKey::S), Some(6));
+        assert_eq!(navigate_grid(4, 7, 3, WASDKey::S), Some(6));
+        assert_eq!(navigate_grid(5, 7, 3, WASDKey::S), Some(6));
+        assert_eq!(navigate_grid(6, 7, 3, WASDKey::S), None);
+        assert_eq!(navigate_grid(6, 7, 3, WASDKey::W), Some(3));
+        assert_eq!(navigate_grid(6, 7, 3, WASDKey::D), None);
+
+        // 5 in 2 columns: the last row only has index 4
+        assert_eq!(navigate_grid(2, 5, 2, WASDKey::S), Some(4));
+        assert_eq!(navigate_grid(3, 5, 2, WASDKey::S), Some(4));
+        assert_eq!(navigate_grid(4, 5, 2, WASDKey::S), None);
+        assert_eq!(navigate_grid(4, 5, 2, WASDKey::W), Some(2));
+    }
+
     #[test]
     fn test_vertical_list_navigation() {
         let total = 5;
 
         // Middle element
-        assert_eq!(navigate_list(2, total, true, WASDKey::W), Some(1));
-        assert_eq!(navigate_list(2, total, true, WASDKey::S), Some(3));
+        assert_eq!(navigate_list(2, total, true, false, WASDKey::W), Some(1));
+        assert_eq!(navigate_list(2, total, true, false, WASDKey::S), Some(3));
 
         // A/D don't work in vertical list
-        assert_eq!(navigate_list(2, total, true, WASDKey::A), None);
-        assert_eq!(navigate_list(2, total, true, WASDKey::D), None);
+        assert_eq!(navigate_list(2, total, true, false, WASDKey::A), None);
+        assert_eq!(navigate_list(2, total, true, false, WASDKey::D), None);
 
         // Boundaries
-        assert_eq!(navigate_list(0, total, true, WASDKey::W), None);
-        assert_eq!(navigate_list(4, total, true, WASDKey::S), None);
+        assert_eq!(navigate_list(0, total, true, false, WASDKey::W), None);
+        assert_eq!(navigate_list(4, total, true, false, WASDKey::S), None);
     }
 
     #[test]
@@ -255,4 +348,210 @@ mod tests {
         assert!(is_in_direction(0.0, 0.0, 5.0, 1.0, 1.0, 0.0)); // Slightly up-right
         assert!(!is_in_direction(0.0, 0.0, -5.0, 0.0, 1.0, 0.0)); // Left (wrong direction)
     }
+
+    #[test]
+    fn test_diagonal_spatial_navigation() {
+        let current = Rect {
+            x: 100.0,
+            y: 100.0,
+            width: 10.0,
+            height: 10.0,
+        };
+        let rect = |x: f64, y: f64| Rect {
+            x,
+            y,
+            width: 10.0,
+            height: 10.0,
+        };
+        let candidates = vec![
+            ("up-left".to_string(), rect(50.0, 50.0), 0),
+            ("up-right".to_string(), rect(150.0, 50.0), 0),
+            ("down-left".to_string(), rect(50.0, 150.0), 0),
+            ("down-right".to_string(), rect(150.0, 150.0), 0),
+            ("right".to_string(), rect(160.0, 100.0), 0),
+        ];
+
+        let nearest =
+            |key| find_nearest_in_direction(&current, &candidates, key, DEFAULT_SPATIAL_WEIGHT);
+        assert_eq!(nearest(WASDKey::Q).as_deref(), Some("up-left"));
+        assert_eq!(nearest(WASDKey::E).as_deref(), Some("up-right"));
+        assert_eq!(nearest(WASDKey::Z).as_deref(), Some("down-left"));
+        assert_eq!(nearest(WASDKey::C).as_deref(), Some("down-right"));
+        // Orthogonal movement still prefers the aligned element
+        assert_eq!(nearest(WASDKey::D).as_deref(), Some("right"));
+    }
+
+    #[test]
+    fn test_edge_adjacency_prefers_facing_domain() {
+        // Short domain on the left, a tall main area right next to it,
+        // and a short widget further away but level with its center
+        let short = Rect {
+            x: 0.0,
+            y: 0.0,
+            width: 100.0,
+            height: 100.0,
+        };
+        let candidates = vec![
+            (
+                "tall-main".to_string(),
+                Rect {
+                    x: 120.0,
+                    y: 0.0,
+                    width: 500.0,
+                    height: 1000.0,
+                },
+                0,
+            ),
+            (
+                "far-widget".to_string(),
+                Rect {
+                    x: 700.0,
+                    y: 0.0,
+                    width: 100.0,
+                    height: 100.0,
+                },
+                0,
+            ),
+        ];
+
+        // Center math is pulled toward the level-but-distant widget
+        assert_eq!(
+            find_nearest_in_direction(&short, &candidates, WASDKey::D, DEFAULT_SPATIAL_WEIGHT)
+                .as_deref(),
+            Some("far-widget")
+        );
+        assert_eq!(
+            find_adjacent_by_edge(&short, &candidates, WASDKey::D, DEFAULT_SPATIAL_WEIGHT)
+                .as_deref(),
+            Some("tall-main")
+        );
+
+        // Candidates without perpendicular overlap are skipped by the edge pass
+        let below = Rect {
+            x: 0.0,
+            y: 200.0,
+            width: 100.0,
+            height: 100.0,
+        };
+        assert_eq!(
+            find_adjacent_by_edge(&below, &candidates[1..], WASDKey::D, DEFAULT_SPATIAL_WEIGHT)
+                .as_deref(),
+            Some("far-widget") // via the center fallback
+        );
+        assert_eq!(
+            find_adjacent_by_edge(&below, &candidates, WASDKey::D, DEFAULT_SPATIAL_WEIGHT)
+                .as_deref(),
+            Some("tall-main")
+        );
+    }
+
+    #[test]
+    fn test_perpendicular_weight_trades_alignment_for_proximity() {
+        let current = Rect {
+            x: 0.0,
+            y: 0.0,
+            width: 10.0,
+            height: 10.0,
+        };
+        let candidates = vec![
+            (
+                "aligned-far".to_string(),
+                Rect {
+                    x: 200.0,
+                    y: 0.0,
+                    width: 10.0,
+                    height: 10.0,
+                },
+                0,
+            ),
+            (
+                "offset-near".to_string(),
+                Rect {
+                    x: 60.0,
+                    y: 60.0,
+                    width: 10.0,
+                    height: 10.0,
+                },
+                0,
+            ),
+        ];
+
+        let nearest = |weight| find_nearest_in_direction(&current, &candidates, WASDKey::D, weight);
+        assert_eq!(
+            nearest(DEFAULT_SPATIAL_WEIGHT).as_deref(),
+            Some("aligned-far")
+        );
+        assert_eq!(nearest(0.0).as_deref(), Some("offset-near"));
+    }
+
+    #[test]
+    fn test_equidistant_targets_break_ties_by_order_then_id() {
+        let current = Rect {
+            x: 100.0,
+            y: 100.0,
+            width: 10.0,
+            height: 10.0,
+        };
+        let rect = |x: f64, y: f64| Rect {
+            x,
+            y,
+            width: 10.0,
+            height: 10.0,
+        };
+        // Mirror images above and below the axis of movement
+        let upper = ("upper".to_string(), rect(150.0, 80.0), 1);
+        let lower = ("lower".to_string(), rect(150.0, 120.0), 0);
+
+        for candidates in [
+            vec![upper.clone(), lower.clone()],
+            vec![lower.clone(), upper.clone()],
+        ] {
+            let pick = find_nearest_in_direction(
+                &current,
+                &candidates,
+                WASDKey::D,
+                DEFAULT_SPATIAL_WEIGHT,
+            );
+            assert_eq!(pick.as_deref(), Some("lower"));
+        }
+
+        // Equal order falls back to the lower id
+        let upper = ("a-upper".to_string(), rect(150.0, 80.0), 0);
+        let lower = ("b-lower".to_string(), rect(150.0, 120.0), 0);
+        for candidates in [vec![upper.clone(), lower.clone()], vec![lower, upper]] {
+            let pick = find_nearest_in_direction(
+                &current,
+                &candidates,
+                WASDKey::D,
+                DEFAULT_SPATIAL_WEIGHT,
+            );
+            assert_eq!(pick.as_deref(), Some("a-upper"));
+        }
+    }
+
+    #[test]
+    fn test_diagonals_are_noops_in_grid_and_list() {
+        assert_eq!(navigate_grid(4, 9, 3, WASDKey::C), None);
+        assert_eq!(navigate_list(2, 5, true, false, WASDKey::Z), None);
+        assert_eq!(navigate_list(2, 5, false, false, WASDKey::E), None);
+    }
+
+    #[test]
+    fn test_list_wrap_around() {
+        let total = 5;
+
+        // Without wrap the ends are boundaries
+        assert_eq!(navigate_list(4, total, true, false, WASDKey::S), None);
+        assert_eq!(navigate_list(0, total, false, false, WASDKey::A), None);
+
+        // With wrap they jump to the other end
+        assert_eq!(navigate_list(4, total, true, true, WASDKey::S), Some(0));
+        assert_eq!(navigate_list(0, total, true, true, WASDKey::W), Some(4));
+        assert_eq!(navigate_list(4, total, false, true, WASDKey::D), Some(0));
+        assert_eq!(navigate_list(0, total, false, true, WASDKey::A), Some(4));
+
+        // Middle movement and off-axis keys are unaffected
+        assert_eq!(navigate_list(2, total, true, true, WASDKey::S), Some(3));
+        assert_eq!(navigate_list(2, total, true, true, WASDKey::A), None);
+    }
 }
@@ -1,6 +1,8 @@
 // Core data structures for domain navigation system
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f64::consts::FRAC_1_SQRT_2;
 
 /// Represents a spatial rectangle for positioning elements
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -24,23 +26,31 @@ impl Rect {
     }
 }
 
-/// WASD input keys
+/// WASD input keys, plus Q/E/Z/C diagonals
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WASDKey {
     W, // Up
     A, // Left
     S, // Down
     D, // Right
+    Q, // Up-left
+    E, // Up-right
+    Z, // Down-left
+    C, // Down-right
 }
 
 impl WASDKey {
-    /// Parse from string
+    /// Parse from string. Vim-style H/J/K/L are accepted as aliases for A/S/W/D.
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_uppercase().as_str() {
-            "W" => Some(WASDKey::W),
-            "A" => Some(WASDKey::A),
-            "S" => Some(WASDKey::S),
-            "D" => Some(WASDKey::D),
+            "W" | "K" => Some(WASDKey::W),
+            "A" | "H" => Some(WASDKey::A),
+            "S" | "J" => Some(WASDKey::S),
+            "D" | "L" => Some(WASDKey::D),
+            "Q" => Some(WASDKey::Q),
+            "E" => Some(WASDKey::E),
+            "Z" => Some(WASDKey::Z),
+            "C" => Some(WASDKey::C),
             _ => None,
         }
     }
@@ -48,14 +58,29 @@ impl WASDKey {
     /// Get directional unit vector
     pub fn direction_vector(&self) -> (f64, f64) {
         match self {
-            WASDKey::W => (0.0, -1.0), // Up
-            WASDKey::A => (-1.0, 0.0), // Left
-            WASDKey::S => (0.0, 1.0),  // Down
-            WASDKey::D => (1.0, 0.0),  // Right
+            WASDKey::W => (0.0, -1.0),                      // Up
+            WASDKey::A => (-1.0, 0.0),                      // Left
+            WASDKey::S => (0.0, 1.0),                       // Down
+            WASDKey::D => (1.0, 0.0),                       // Right
+            WASDKey::Q => (-FRAC_1_SQRT_2, -FRAC_1_SQRT_2), // Up-left
+            WASDKey::E => (FRAC_1_SQRT_2, -FRAC_1_SQRT_2),  // Up-right
+            WASDKey::Z => (-FRAC_1_SQRT_2, FRAC_1_SQRT_2),  // Down-left
+            WASDKey::C => (FRAC_1_SQRT_2, FRAC_1_SQRT_2),   // Down-right
         }
     }
 }
 
+/// What button registration does when the requested `order` is already taken in the domain
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateOrderPolicy {
+    /// Log a warning and give the button the next free order after the requested one
+    #[default]
+    Reassign,
+    /// Reject the registration with an error
+    Reject,
+}
+
 /// Layout mode for domain navigation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum LayoutMode {
@@ -75,7 +100,7 @@ pub enum ListDirection {
 
 /// Direction of a gate (which edge of the domain)
 /// Now used for boundary_lock in spatial navigation
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum GateDirection {
     Top,
     Bottom,
@@ -109,6 +134,19 @@ pub struct ButtonElement {
     pub bounds: Option<Rect>,
     pub enabled: bool,
     pub order: usize, // Sequential order for list/grid layouts
+    /// Human-readable name (e.g. for type-to-search)
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// A button description used for batch registration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ButtonSpec {
+    pub id: String,
+    pub bounds: Option<Rect>,
+    pub order: usize,
+    #[serde(default)]
+    pub label: Option<String>,
 }
 
 // DEPRECATED: Gate system replaced by spatial boundary navigation
@@ -137,6 +175,17 @@ pub struct Domain {
     pub bounds: Option<Rect>,
     /// Directions where cursor cannot exit this domain (even if adjacent domain exists)
     pub boundary_lock: Vec<GateDirection>,
+    /// List layouts only: moving past either end wraps to the other end
+    /// instead of reaching the boundary
+    #[serde(default)]
+    pub wrap: bool,
+    /// Re-entering this domain returns to the last focused element instead of the first
+    #[serde(default)]
+    pub restore_focus: bool,
+    /// Explicit domain to enter when leaving through an edge; takes precedence over
+    /// adjacency inferred from bounds
+    #[serde(default)]
+    pub neighbors: HashMap<GateDirection, String>,
 }
 
 impl Domain {
@@ -150,6 +199,9 @@ impl Domain {
             layout_mode,
             bounds: None,
             boundary_lock: Vec::new(),
+            wrap: false,
+            restore_focus: false,
+            neighbors: HashMap::new(),
         }
     }
 
@@ -167,6 +219,19 @@ impl Domain {
         }
     }
 
+    /// Whether the element at `index` can receive the cursor
+    pub fn is_element_enabled(&self, index: usize) -> bool {
+        self.buttons.get(index).is_some_and(|b| b.enabled)
+    }
+
+    /// Find the first enabled button whose label matches (case-insensitive)
+    pub fn find_button_by_label(&self, label: &str) -> Option<&ButtonElement> {
+        let label = label.to_lowercase();
+        self.buttons
+            .iter()
+            .find(|b| b.enabled && b.label.as_ref().is_some_and(|l| l.to_lowercase() == label))
+    }
+
     /// Find index of element by ID (buttons only, gates deprecated)
     pub fn find_element_index(&self, element_id: &str) -> Option<usize> {
         self.buttons.iter().position(|b| b.id == element_id)
@@ -186,6 +251,18 @@ pub struct CursorPosition {
     pub element_type: ElementType,
 }
 
+/// Complete navigator state, captured for debugging the cursor save/restore flow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NavigationSnapshot {
+    pub domains: HashMap<String, Domain>,
+    pub active_domain_id: Option<String>,
+    pub cursor_position: Option<CursorPosition>,
+    pub saved_cursor_positions: HashMap<String, CursorPosition>,
+    pub saved_active_domain: Option<String>,
+    pub last_focused: HashMap<String, String>,
+    pub spatial_weight: f64,
+}
+
 /// Target of a navigation action
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum NavigationTarget {
@@ -197,6 +274,20 @@ pub enum NavigationTarget {
     Boundary,
 }
 
+/// Why a move was blocked, reported with `NavigationResult::BoundaryReached`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockReason {
+    /// The edge in that direction is locked with `set_boundary_lock`
+    BoundaryLock,
+    /// The edge is open but no domain lies that way
+    NoAdjacentDomain,
+    /// The domain has no elements to move between
+    EmptyDomain,
+    /// A diagonal move ran out of elements; diagonals never cross into other domains
+    Edge,
+}
+
 /// Result of a navigation action
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -220,7 +311,7 @@ pub enum NavigationResult {
     //     target_domain: String,
     // },
     /// Hit boundary of domain (no adjacent domain to switch to)
-    BoundaryReached,
+    BoundaryReached { reason: BlockReason },
     /// No active domain to navigate
     NoActiveDomain,
     /// Domain switched successfully
@@ -232,3 +323,20 @@ pub enum NavigationResult {
     /// Error occurred
     Error { message: String },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hjkl_aliases() {
+        assert_eq!(WASDKey::from_str("h"), Some(WASDKey::A));
+        assert_eq!(WASDKey::from_str("j"), Some(WASDKey::S));
+        assert_eq!(WASDKey::from_str("k"), Some(WASDKey::W));
+        assert_eq!(WASDKey::from_str("L"), Some(WASDKey::D));
+
+        // WASD itself is unchanged
+        assert_eq!(WASDKey::from_str("w"), Some(WASDKey::W));
+        assert_eq!(WASDKey::from_str("x"), None);
+    }
+}
@@ -9,6 +9,12 @@ mod input_handler;
 // State management module
 mod state;
 
+// Structured error type returned by commands
+mod error;
+
+// Startup configuration (hyphaeic.toml)
+mod config;
+
 // PTY terminal module
 // PTY terminal module
 mod pty;
@@ -16,17 +22,32 @@ mod pty;
 // Audio module
 mod audio;
 
-use asset_loader::{clear_asset_cache, get_asset_cache_path, is_asset_cached, load_asset};
-use audio::{AudioState, AudioSystem};
+use asset_loader::{
+    cancel_asset_download, clear_asset_cache, enforce_cache_limit, get_asset_cache_path,
+    get_cache_stats, import_local_asset, is_asset_cached, load_asset, prefetch_assets,
+    set_offline_mode, AssetDownloads, OfflineMode,
+};
+use audio::ambience::{AmbiencePosition, FadeCurve};
+use audio::device::DeviceSelection;
+use audio::{AudioLoadReport, AudioSettings, AudioState, AudioSystem};
+use config::Config;
+use error::HyphaeicError;
 use input_handler::{
-    DomainNavigator, ElementType, LayoutMode, ListDirection, NavigationResult, Rect, WASDKey,
+    BlockReason, ButtonElement, ButtonSpec, CursorPosition, DomainNavigator, ElementType,
+    LayoutMode, ListDirection, NavigationResult, NavigationSnapshot, Rect, WASDKey,
+    FALLBACK_DOMAIN,
 };
-use pty::PtyManager;
+use log::{debug, error, info, trace, warn, LevelFilter};
+use pty::{PtyDimensions, PtyManager, RunOnceOutput, SearchMatch};
 use serde::Serialize;
 
-use state::window::{WindowInstance, WindowState};
+use state::window::{CompositorSlot, WindowInstance, WindowState};
 use state::StateManager;
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 
@@ -36,6 +57,11 @@ struct CursorMovedPayload {
     domain_id: String,
     element_id: String,
     element_type: String,
+    /// Geometry of the focused element; None until the frontend has measured it
+    bounds: Option<Rect>,
+    /// Focus before this move, so the frontend can clear the old highlight
+    previous_element_id: Option<String>,
+    previous_domain_id: Option<String>,
 }
 
 #[derive(Clone, Serialize)]
@@ -54,6 +80,8 @@ struct DomainSwitchedPayload {
 #[derive(Clone, Serialize)]
 struct BoundaryReachedPayload {
     direction: String,
+    /// "boundary_lock", "no_adjacent_domain", "empty_domain" or "edge"
+    reason: BlockReason,
 }
 
 #[derive(Clone, Serialize)]
@@ -63,9 +91,132 @@ struct DomainBoundaryCrossedPayload {
     direction: String,
 }
 
+#[derive(Clone, Serialize)]
+struct DomainEmptiedPayload {
+    domain_id: String,
+    /// Domain the frontend can move focus to (osbar-nav when it has buttons)
+    fallback_domain: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct CompositorFullPayload {
+    content_key: String,
+}
+
+#[derive(Clone, Serialize)]
+struct NavigationPeekPayload {
+    domain_id: String,
+    element_id: String,
+}
+
+#[derive(Clone, Serialize)]
+struct AudioDeviceFallbackPayload {
+    requested: String,
+    device: Option<String>,
+}
+
 // Global state for domain navigator (Arc for sharing with shortcut handlers)
 struct AppState {
     domain_navigator: Arc<Mutex<DomainNavigator>>,
+    cursor_coalescer: Arc<Mutex<CursorCoalescer>>,
+    nav_cooldown: Arc<Mutex<NavCooldown>>,
+}
+
+/// Key-driven cursor moves closer together than this are coalesced (about one frame)
+const CURSOR_COALESCE_WINDOW: Duration = Duration::from_millis(16);
+
+/// Same-direction navigation shortcuts closer together than this are dropped
+const DEFAULT_NAV_COOLDOWN: Duration = Duration::from_millis(config::DEFAULT_REPEAT_COOLDOWN_MS);
+
+/// What to do with a key-driven cursor move
+enum CoalescedMove {
+    /// Nothing was emitted within the window: emit right away
+    Emit(CursorMovedPayload),
+    /// Buffered; flush once the delay has passed
+    Schedule(Duration),
+    /// Replaced a buffered move whose flush is already scheduled
+    Merged,
+}
+
+/// Throttles cursor-moved while a key autorepeats. The first move after a quiet period
+/// is emitted immediately; moves within CURSOR_COALESCE_WINDOW of the last emit are
+/// buffered, and only the latest one is emitted when the window closes.
+#[derive(Default)]
+struct CursorCoalescer {
+    last_emit: Option<Instant>,
+    pending: Option<CursorMovedPayload>,
+    /// Flush deadlines for the worker started by `spawn_cursor_flush_worker`
+    flush_tx: Option<mpsc::Sender<Instant>>,
+}
+
+impl CursorCoalescer {
+    fn push(&mut self, mut payload: CursorMovedPayload, now: Instant) -> CoalescedMove {
+        if let Some(pending) = self.pending.take() {
+            // Keep the focus the frontend last saw, so it clears the right highlight
+            payload.previous_element_id = pending.previous_element_id;
+            payload.previous_domain_id = pending.previous_domain_id;
+            self.pending = Some(payload);
+            return CoalescedMove::Merged;
+        }
+
+        match self.last_emit {
+            Some(last) if now.duration_since(last) < CURSOR_COALESCE_WINDOW => {
+                self.pending = Some(payload);
+                CoalescedMove::Schedule(CURSOR_COALESCE_WINDOW - now.duration_since(last))
+            }
+            _ => {
+                self.last_emit = Some(now);
+                CoalescedMove::Emit(payload)
+            }
+        }
+    }
+
+    /// Take the buffered move, if any, counting it as emitted at `now`
+    fn flush(&mut self, now: Instant) -> Option<CursorMovedPayload> {
+        let payload = self.pending.take()?;
+        self.last_emit = Some(now);
+        Some(payload)
+    }
+
+    /// Prepare a move that bypasses the coalescer. Any buffered move is discarded and
+    /// its previous focus carried over, since the frontend never saw it highlighted.
+    fn supersede(&mut self, mut payload: CursorMovedPayload, now: Instant) -> CursorMovedPayload {
+        if let Some(pending) = self.pending.take() {
+            payload.previous_element_id = pending.previous_element_id;
+            payload.previous_domain_id = pending.previous_domain_id;
+        }
+        self.last_emit = Some(now);
+        payload
+    }
+}
+
+/// Ignores a shortcut move in the same direction as the last accepted one when it
+/// arrives within the cooldown, so key repeat on top of a quick tap doesn't skip two
+/// elements. A new direction is always accepted and restarts the window.
+struct NavCooldown {
+    window: Duration,
+    last: Option<(WASDKey, Instant)>,
+}
+
+impl NavCooldown {
+    fn new(window: Duration) -> Self {
+        Self { window, last: None }
+    }
+
+    fn set_window(&mut self, window: Duration) {
+        self.window = window;
+    }
+
+    /// Whether a press of `key` at `now` should navigate (recording it if so)
+    fn accept(&mut self, key: WASDKey, now: Instant) -> bool {
+        if let Some((last_key, at)) = self.last {
+            if last_key == key && now.duration_since(at) < self.window {
+                return false;
+            }
+        }
+        self.last = Some((key, now));
+        true
+    }
 }
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
@@ -74,6 +225,81 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+/// Adjust the log filter at runtime ("off", "error", "warn", "info", "debug", "trace").
+/// When RUST_LOG was set at startup this can't raise a module past its RUST_LOG directive.
+#[tauri::command]
+fn set_log_level(level: String) -> Result<(), HyphaeicError> {
+    let filter: LevelFilter = level
+        .parse()
+        .map_err(|_| HyphaeicError::InvalidInput(format!("Invalid log level: {}", level)))?;
+    log::set_max_level(filter);
+    info!("Log level set to {}", filter);
+    Ok(())
+}
+
+/// Re-apply the runtime-adjustable parts of a config (volumes, fade, spatial weight, terminal).
+/// Audio settings the user has saved take precedence over the config's `[audio]` values.
+fn apply_config(
+    config: &Config,
+    navigator: &mut DomainNavigator,
+    cooldown: &mut NavCooldown,
+    audio: &mut AudioSystem,
+    pty: &mut PtyManager,
+) {
+    if let Err(e) = navigator.set_spatial_weight(config.navigation.spatial_weight) {
+        warn!("Ignoring configured spatial_weight: {}", e);
+    }
+    navigator.set_duplicate_order_policy(config.navigation.duplicate_order);
+    cooldown.set_window(Duration::from_millis(config.navigation.repeat_cooldown_ms));
+    if !audio.has_saved_settings() {
+        audio.set_master_volume(config.audio.master_volume);
+        audio.set_sfx_volume(config.audio.sfx_volume);
+        audio.set_ambience_volume(config.audio.ambience_volume);
+        audio.set_fade_duration(config.audio.fade_duration_secs);
+        audio.set_enabled(config.audio.enabled);
+    }
+    pty.set_max_sessions(config.terminal.max_sessions);
+    pty.set_shell(config.terminal.shell.clone());
+}
+
+/// Re-read hyphaeic.toml and apply it. Slot count and startup domain only take
+/// effect on the next launch.
+#[tauri::command]
+fn reload_config(
+    app: AppHandle,
+    state: State<AppState>,
+    audio_state: State<AudioState>,
+    pty_state: State<Mutex<PtyManager>>,
+    config_state: State<Mutex<Config>>,
+) -> Result<Config, HyphaeicError> {
+    let config = Config::load(&app.path().app_config_dir()?)?;
+
+    {
+        let mut navigator = state
+            .domain_navigator
+            .lock()
+            .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+        let mut cooldown = state
+            .nav_cooldown
+            .lock()
+            .map_err(|_| HyphaeicError::LockPoisoned("navigation cooldown"))?;
+        let mut audio = audio_state
+            .0
+            .lock()
+            .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+        let mut pty = pty_state
+            .lock()
+            .map_err(|_| HyphaeicError::LockPoisoned("PTY manager"))?;
+        apply_config(&config, &mut navigator, &mut cooldown, &mut audio, &mut pty);
+    }
+
+    *config_state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("config"))? = config.clone();
+    info!("Reloaded {}", config::CONFIG_FILE);
+    Ok(config)
+}
+
 // ===== Window Management Commands =====
 
 #[tauri::command]
@@ -81,37 +307,82 @@ fn spawn_window(
     content_key: String,
     source_element_id: Option<String>,
     source_domain_id: Option<String>,
+    preferred_slot: Option<CompositorSlot>,
     app: AppHandle,
     state: State<Mutex<StateManager>>,
-) -> Result<WindowInstance, String> {
-    let mut manager = state.lock().map_err(|e| e.to_string())?;
-
-    match manager.spawn_window(content_key, source_element_id, source_domain_id) {
+) -> Result<WindowInstance, HyphaeicError> {
+    let mut manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("window state"))?;
+
+    match manager.spawn_window(
+        content_key.clone(),
+        source_element_id,
+        source_domain_id,
+        preferred_slot,
+    ) {
         Some(window) => {
             // Emit event
-            app.emit("window-created", window.clone())
-                .map_err(|e| e.to_string())?;
+            app.emit("window-created", window.clone())?;
             Ok(window)
         }
-        None => Err("No available slots - both compositor slots are occupied".to_string()),
+        None => {
+            // Global signal so a central handler can prompt to close a window first
+            app.emit("compositor-full", CompositorFullPayload { content_key })?;
+            Err(HyphaeicError::SlotFull)
+        }
     }
 }
 
+/// Spawn a window, closing the least-recently-focused one if every slot is occupied
+#[tauri::command]
+fn spawn_window_lru(
+    content_key: String,
+    source_element_id: Option<String>,
+    source_domain_id: Option<String>,
+    preferred_slot: Option<CompositorSlot>,
+    app: AppHandle,
+    state: State<Mutex<StateManager>>,
+) -> Result<WindowInstance, HyphaeicError> {
+    let mut manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("window state"))?;
+
+    let (window, evicted) = manager
+        .spawn_window_lru(
+            content_key,
+            source_element_id,
+            source_domain_id,
+            preferred_slot,
+        )
+        .ok_or(HyphaeicError::SlotFull)?;
+
+    if let Some(evicted) = evicted {
+        app.emit("window-closed", evicted.id)?;
+    }
+    app.emit("window-created", window.clone())?;
+    Ok(window)
+}
+
 #[tauri::command]
 fn close_window(
     id: String,
     app: AppHandle,
     state: State<Mutex<StateManager>>,
-) -> Result<(), String> {
-    let mut manager = state.lock().map_err(|e| e.to_string())?;
+) -> Result<(), HyphaeicError> {
+    let mut manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("window state"))?;
 
     // First, set window state to Closing (triggers animation)
-    if let Some(window) = manager.set_window_state(&id, WindowState::Closing) {
-        // Emit state change event so frontend updates
-        app.emit("window-state-changed", window)
-            .map_err(|e| e.to_string())?;
-    } else {
-        return Err(format!("Window {} not found", id));
+    let changed = manager.set_window_state(&id, WindowState::Closing);
+    if changed.is_empty() {
+        return Err(HyphaeicError::WindowNotFound(id));
+    }
+
+    // Emit state change event so frontend updates
+    for window in changed {
+        app.emit("window-state-changed", window)?;
     }
 
     Ok(())
@@ -122,12 +393,14 @@ fn remove_window(
     id: String,
     app: AppHandle,
     state: State<Mutex<StateManager>>,
-) -> Result<(), String> {
-    let mut manager = state.lock().map_err(|e| e.to_string())?;
+) -> Result<(), HyphaeicError> {
+    let mut manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("window state"))?;
     let closed_window = manager.close_window(&id);
 
     // Emit event
-    app.emit("window-closed", id).map_err(|e| e.to_string())?;
+    app.emit("window-closed", id)?;
 
     // If window had a source element, try to return focus to it
     if let Some(win) = closed_window {
@@ -142,9 +415,11 @@ fn remove_window(
                     domain_id: source_domain,
                     element_id: source_element,
                     element_type: "Button".to_string(), // Assuming button triggered it
+                    bounds: None,
+                    previous_element_id: None,
+                    previous_domain_id: None,
                 },
-            )
-            .map_err(|e| e.to_string())?;
+            )?;
         }
     }
 
@@ -157,69 +432,260 @@ fn set_window_state(
     window_state: String,
     app: AppHandle,
     state: State<Mutex<StateManager>>,
-) -> Result<(), String> {
+) -> Result<(), HyphaeicError> {
     let new_state = match window_state.as_str() {
         "Minimized" => WindowState::Minimized,
         "Maximized" => WindowState::Maximized,
         "Hidden" => WindowState::Hidden,
         "Closing" => WindowState::Closing,
-        _ => return Err(format!("Invalid window state: {}", window_state)),
+        _ => {
+            return Err(HyphaeicError::InvalidInput(format!(
+                "Invalid window state: {}",
+                window_state
+            )))
+        }
     };
 
-    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    let mut manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("window state"))?;
 
-    if let Some(window) = manager.set_window_state(&id, new_state) {
-        app.emit("window-state-changed", window)
-            .map_err(|e| e.to_string())?;
-        Ok(())
-    } else {
-        Err(format!("Window not found: {}", id))
+    // Maximizing may also demote another window; emit for every change
+    let changed = manager.set_window_state(&id, new_state);
+    if changed.is_empty() {
+        return Err(HyphaeicError::WindowNotFound(id));
+    }
+    for window in changed {
+        app.emit("window-state-changed", window)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn cycle_window_state(
+    id: String,
+    app: AppHandle,
+    state: State<Mutex<StateManager>>,
+) -> Result<(), HyphaeicError> {
+    let mut manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("window state"))?;
+
+    for window in manager.cycle_window_state(&id)? {
+        app.emit("window-state-changed", window)?;
     }
+    Ok(())
+}
+
+/// File in the app data dir holding the saved window layout
+const LAYOUT_FILE: &str = "window_layout.json";
+
+fn layout_path(app: &AppHandle) -> Result<PathBuf, HyphaeicError> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| HyphaeicError::Io(format!("Failed to get app data dir: {}", e)))?;
+    Ok(app_data_dir.join(LAYOUT_FILE))
+}
+
+/// Save the current window layout to the app data dir
+#[tauri::command]
+fn save_layout(app: AppHandle, state: State<Mutex<StateManager>>) -> Result<(), HyphaeicError> {
+    let json = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("window state"))?
+        .serialize()?;
+
+    let path = layout_path(&app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| HyphaeicError::Io(format!("Failed to create app data dir: {}", e)))?;
+    }
+    std::fs::write(&path, json)
+        .map_err(|e| HyphaeicError::Io(format!("Failed to write layout: {}", e)))
+}
+
+/// Replace the current windows with the saved layout, emitting window-created for each.
+/// Returns no windows if nothing has been saved yet.
+#[tauri::command]
+fn load_layout(
+    app: AppHandle,
+    state: State<Mutex<StateManager>>,
+) -> Result<Vec<WindowInstance>, HyphaeicError> {
+    let path = layout_path(&app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| HyphaeicError::Io(format!("Failed to read layout: {}", e)))?;
+
+    let mut manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("window state"))?;
+    let windows = manager.restore(&json)?;
+    for window in &windows {
+        app.emit("window-created", window.clone())?;
+    }
+    Ok(windows)
+}
+
+/// Re-open the most recently closed window in an available slot
+#[tauri::command]
+fn restore_last_closed(
+    app: AppHandle,
+    state: State<Mutex<StateManager>>,
+) -> Result<WindowInstance, HyphaeicError> {
+    let mut manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("window state"))?;
+
+    let window = manager.restore_last_closed()?;
+    app.emit("window-created", window.clone())?;
+    Ok(window)
+}
+
+/// List hidden windows (e.g. for a minimized-windows tray)
+#[tauri::command]
+fn get_hidden_windows(
+    state: State<Mutex<StateManager>>,
+) -> Result<Vec<WindowInstance>, HyphaeicError> {
+    let manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("window state"))?;
+    Ok(manager.get_hidden_windows())
+}
+
+/// Restore a hidden window to Minimized
+#[tauri::command]
+fn unhide_window(
+    id: String,
+    app: AppHandle,
+    state: State<Mutex<StateManager>>,
+) -> Result<(), HyphaeicError> {
+    let mut manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("window state"))?;
+
+    let window = manager.unhide_window(&id)?;
+    app.emit("window-state-changed", window)?;
+    Ok(())
+}
+
+/// Change a window's title after creation
+#[tauri::command]
+fn set_window_title(
+    id: String,
+    title: String,
+    app: AppHandle,
+    state: State<Mutex<StateManager>>,
+) -> Result<(), HyphaeicError> {
+    let mut manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("window state"))?;
+
+    let window = manager.set_title(&id, title)?;
+    app.emit("window-state-changed", window)?;
+    Ok(())
+}
+
+/// Move a window to a specific (free) compositor slot
+#[tauri::command]
+fn move_window_to_slot(
+    id: String,
+    slot: CompositorSlot,
+    app: AppHandle,
+    state: State<Mutex<StateManager>>,
+) -> Result<(), HyphaeicError> {
+    let mut manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("window state"))?;
+
+    let window = manager.move_window(&id, slot)?;
+    app.emit("window-state-changed", window)?;
+    Ok(())
+}
+
+/// Exchange the windows in the left and right slots
+#[tauri::command]
+fn swap_windows(app: AppHandle, state: State<Mutex<StateManager>>) -> Result<(), HyphaeicError> {
+    let mut manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("window state"))?;
+
+    for window in manager.swap_slots() {
+        app.emit("window-state-changed", window)?;
+    }
+    Ok(())
 }
 
 // ===== PTY Terminal Commands =====
 
-/// Spawn a new PTY session for a terminal
+/// Spawn a new PTY session for a terminal, opened at `rows` x `cols` (default 24x80)
 #[tauri::command]
-fn pty_spawn(session_id: String, state: State<Mutex<PtyManager>>) -> Result<String, String> {
-    println!(
+fn pty_spawn(
+    session_id: String,
+    rows: Option<u16>,
+    cols: Option<u16>,
+    state: State<Mutex<PtyManager>>,
+) -> Result<String, HyphaeicError> {
+    debug!(
         "[TAURI CMD] pty_spawn called with session_id: {}",
         session_id
     );
     let mut manager = state.lock().map_err(|e| {
-        println!("[TAURI CMD] ERROR: Failed to lock PtyManager: {}", e);
-        e.to_string()
+        error!("[TAURI CMD] Failed to lock PtyManager: {}", e);
+        HyphaeicError::LockPoisoned("PTY manager")
     })?;
-    println!("[TAURI CMD] Got PtyManager lock, calling spawn...");
-    let result = manager.spawn(session_id);
-    println!("[TAURI CMD] pty_spawn result: {:?}", result.is_ok());
+    debug!("[TAURI CMD] Got PtyManager lock, calling spawn...");
+    let result = manager.spawn(
+        session_id,
+        rows.unwrap_or(pty::DEFAULT_ROWS),
+        cols.unwrap_or(pty::DEFAULT_COLS),
+    );
+    debug!("[TAURI CMD] pty_spawn result: {:?}", result.is_ok());
     result
 }
 
-/// Write data to a PTY session
+/// Write data to a PTY session; returns the number of bytes written
 #[tauri::command]
 fn pty_write(
     session_id: String,
     data: String,
     state: State<Mutex<PtyManager>>,
-) -> Result<(), String> {
-    println!("[TAURI CMD] pty_write called for session: {}", session_id);
+) -> Result<usize, HyphaeicError> {
+    trace!("[TAURI CMD] pty_write called for session: {}", session_id);
     let manager = state.lock().map_err(|e| {
-        println!("[TAURI CMD] ERROR: Failed to lock PtyManager: {}", e);
-        e.to_string()
+        error!("[TAURI CMD] Failed to lock PtyManager: {}", e);
+        HyphaeicError::LockPoisoned("PTY manager")
     })?;
     manager.write(&session_id, data.as_bytes())
 }
 
+/// Paste text into a PTY session; shells that support it get bracketed paste, so
+/// multi-line text isn't run line by line. Returns the number of bytes written.
+#[tauri::command]
+fn pty_paste(
+    session_id: String,
+    text: String,
+    state: State<Mutex<PtyManager>>,
+) -> Result<usize, HyphaeicError> {
+    let manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("PTY manager"))?;
+    manager.paste(&session_id, &text)
+}
+
 /// Read available data from a PTY session
 #[tauri::command]
-fn pty_read(session_id: String, state: State<Mutex<PtyManager>>) -> Result<String, String> {
+fn pty_read(session_id: String, state: State<Mutex<PtyManager>>) -> Result<String, HyphaeicError> {
     // Don't log every read since it polls frequently
-    let manager = state.lock().map_err(|e| e.to_string())?;
+    let manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("PTY manager"))?;
     let bytes = manager.read(&session_id)?;
 
     // Convert bytes to string, handling potential encoding issues
-    String::from_utf8(bytes).map_err(|e| format!("UTF-8 decode error: {}", e))
+    String::from_utf8(bytes).map_err(|e| HyphaeicError::Pty(format!("UTF-8 decode error: {}", e)))
 }
 
 /// Resize a PTY session
@@ -229,84 +695,563 @@ fn pty_resize(
     rows: u16,
     cols: u16,
     state: State<Mutex<PtyManager>>,
-) -> Result<(), String> {
-    println!(
+) -> Result<(), HyphaeicError> {
+    debug!(
         "[TAURI CMD] pty_resize called for session: {}, {}x{}",
         session_id, cols, rows
     );
-    let manager = state.lock().map_err(|e| {
-        println!("[TAURI CMD] ERROR: Failed to lock PtyManager: {}", e);
-        e.to_string()
+    let mut manager = state.lock().map_err(|e| {
+        error!("[TAURI CMD] Failed to lock PtyManager: {}", e);
+        HyphaeicError::LockPoisoned("PTY manager")
     })?;
     manager.resize(&session_id, rows, cols)
 }
 
+/// Last known size of a PTY session, for restoring a view after reconnecting
+#[tauri::command]
+fn pty_get_size(
+    session_id: String,
+    state: State<Mutex<PtyManager>>,
+) -> Result<PtyDimensions, HyphaeicError> {
+    let manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("PTY manager"))?;
+    manager.size(&session_id)
+}
+
 /// Close a PTY session
 #[tauri::command]
-fn pty_close(session_id: String, state: State<Mutex<PtyManager>>) -> Result<(), String> {
-    println!("[TAURI CMD] pty_close called for session: {}", session_id);
+fn pty_close(session_id: String, state: State<Mutex<PtyManager>>) -> Result<(), HyphaeicError> {
+    debug!("[TAURI CMD] pty_close called for session: {}", session_id);
     let mut manager = state.lock().map_err(|e| {
-        println!("[TAURI CMD] ERROR: Failed to lock PtyManager: {}", e);
-        e.to_string()
+        error!("[TAURI CMD] Failed to lock PtyManager: {}", e);
+        HyphaeicError::LockPoisoned("PTY manager")
     })?;
     manager.close(&session_id)
 }
 
+/// Send a signal (e.g. SIGINT, SIGTERM) to a PTY session's child process
+#[tauri::command]
+fn pty_signal(
+    session_id: String,
+    signal: String,
+    state: State<Mutex<PtyManager>>,
+) -> Result<(), HyphaeicError> {
+    debug!(
+        "[TAURI CMD] pty_signal called for session: {}, signal: {}",
+        session_id, signal
+    );
+    let mut manager = state.lock().map_err(|e| {
+        error!("[TAURI CMD] Failed to lock PtyManager: {}", e);
+        HyphaeicError::LockPoisoned("PTY manager")
+    })?;
+    manager.signal(&session_id, &signal)
+}
+
+/// Check whether a PTY session's shell is still running
+#[tauri::command]
+fn pty_is_alive(
+    session_id: String,
+    state: State<Mutex<PtyManager>>,
+) -> Result<bool, HyphaeicError> {
+    let manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("PTY manager"))?;
+    manager.is_alive(&session_id)
+}
+
+/// Start recording a PTY session's output to a file (input too if `include_input`)
+#[tauri::command]
+fn pty_start_recording(
+    session_id: String,
+    path: String,
+    include_input: Option<bool>,
+    state: State<Mutex<PtyManager>>,
+) -> Result<(), HyphaeicError> {
+    debug!(
+        "[TAURI CMD] pty_start_recording called for session: {}, path: {}",
+        session_id, path
+    );
+    let manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("PTY manager"))?;
+    manager.start_recording(&session_id, &path, include_input.unwrap_or(false))
+}
+
+/// Stop recording a PTY session and close the transcript file
+#[tauri::command]
+fn pty_stop_recording(
+    session_id: String,
+    state: State<Mutex<PtyManager>>,
+) -> Result<(), HyphaeicError> {
+    debug!(
+        "[TAURI CMD] pty_stop_recording called for session: {}",
+        session_id
+    );
+    let manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("PTY manager"))?;
+    manager.stop_recording(&session_id)
+}
+
+/// Search a PTY session's scrollback for a regex; returns matching lines
+#[tauri::command]
+fn pty_search(
+    session_id: String,
+    pattern: String,
+    state: State<Mutex<PtyManager>>,
+) -> Result<Vec<SearchMatch>, HyphaeicError> {
+    debug!(
+        "[TAURI CMD] pty_search called for session: {}, pattern: {}",
+        session_id, pattern
+    );
+    let manager = state
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("PTY manager"))?;
+    manager.search(&session_id, &pattern)
+}
+
+/// Run one command in a throwaway PTY and return its output and exit code
+///
+/// Runs on a blocking worker so a slow command doesn't stall the main thread.
+#[tauri::command]
+async fn pty_run_once(
+    cmd: String,
+    args: Option<Vec<String>>,
+    cwd: Option<String>,
+    timeout_secs: u64,
+) -> Result<RunOnceOutput, HyphaeicError> {
+    debug!("[TAURI CMD] pty_run_once called: {} {:?}", cmd, args);
+    if timeout_secs == 0 {
+        return Err(HyphaeicError::InvalidInput(
+            "timeout_secs must be at least 1".to_string(),
+        ));
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        pty::run_once(
+            &cmd,
+            &args.unwrap_or_default(),
+            cwd.as_deref(),
+            Duration::from_secs(timeout_secs),
+        )
+    })
+    .await
+    .map_err(|e| HyphaeicError::Pty(format!("pty_run_once worker failed: {}", e)))?
+}
+
 /// Get the system status banner for display on terminal startup
+///
+/// `banner_width` defaults to 64 columns, widened so the session id fits. The full
+/// session id is shown unless `session_id_length` asks for a shorter one (0 also means
+/// the full id) or an explicit `banner_width` is too narrow for it.
 #[tauri::command]
-fn get_system_banner(session_id: String) -> String {
-    println!(
+fn get_system_banner(
+    session_id: String,
+    banner_width: Option<usize>,
+    session_id_length: Option<usize>,
+) -> Result<String, HyphaeicError> {
+    debug!(
         "[TAURI CMD] get_system_banner called for session: {}",
         session_id
     );
-    pty::generate_system_banner(&session_id)
+    let session_id_len = session_id_length.filter(|&len| len > 0);
+    let width = banner_width.unwrap_or_else(|| pty::fit_banner_width(&session_id, session_id_len));
+    pty::generate_system_banner(&session_id, width, session_id_len)
+}
+
+// ===== Audio Commands =====
+
+#[tauri::command]
+fn play_sound(id: String, state: State<AudioState>) -> Result<(), HyphaeicError> {
+    let system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    if !system.has_sfx(&id) {
+        return Err(HyphaeicError::SoundNotFound(id));
+    }
+    system.play_sfx(&id);
+    Ok(())
+}
+
+/// Play a sound with a per-play volume (0.0 - 1.0, default 1.0) and pan
+/// (-1.0 left to 1.0 right, default 0.0); both leave the global volumes alone
+#[tauri::command]
+fn play_sound_ex(
+    id: String,
+    volume: Option<f32>,
+    pan: Option<f32>,
+    state: State<AudioState>,
+) -> Result<(), HyphaeicError> {
+    let system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    if !system.has_sfx(&id) {
+        return Err(HyphaeicError::SoundNotFound(id));
+    }
+    system.play_sfx_ex(&id, volume.unwrap_or(1.0), pan.unwrap_or(0.0));
+    Ok(())
+}
+
+/// Choose which SFX ids briefly dim the ambience when played (default `["domain_switch"]`)
+/// and the level it dips to (default 0.3); an empty list disables ducking
+#[tauri::command]
+fn set_audio_ducking(
+    ids: Vec<String>,
+    level: Option<f32>,
+    state: State<AudioState>,
+) -> Result<(), HyphaeicError> {
+    let mut system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    system.set_ducking(ids, level.unwrap_or(audio::DEFAULT_DUCK_LEVEL));
+    Ok(())
+}
+
+/// Ids of the loaded SFX, i.e. the ids `play_sound` accepts
+#[tauri::command]
+fn list_sfx_ids(state: State<AudioState>) -> Result<Vec<String>, HyphaeicError> {
+    let system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    Ok(system.sfx_ids())
+}
+
+/// Play a sound at a different speed (pitch-shifts by resampling, so duration changes too)
+#[tauri::command]
+fn play_sound_pitched(
+    id: String,
+    speed: f32,
+    state: State<AudioState>,
+) -> Result<(), HyphaeicError> {
+    let system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    if !system.has_sfx(&id) {
+        return Err(HyphaeicError::SoundNotFound(id));
+    }
+    system.play_sfx_with_speed(&id, speed);
+    Ok(())
+}
+
+/// Set the master volume for all audio (0.0 - 1.0)
+#[tauri::command]
+fn set_master_volume(level: f32, state: State<AudioState>) -> Result<(), HyphaeicError> {
+    let system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    system.set_master_volume(level);
+    Ok(())
+}
+
+/// Set the SFX channel volume (0.0 - 1.0)
+#[tauri::command]
+fn set_sfx_volume(level: f32, state: State<AudioState>) -> Result<(), HyphaeicError> {
+    let mut system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    system.set_sfx_volume(level);
+    Ok(())
+}
+
+/// Set the minimum interval (ms) between repeat plays of an SFX id
+#[tauri::command]
+fn set_sfx_min_interval(
+    id: String,
+    ms: u64,
+    state: State<AudioState>,
+) -> Result<(), HyphaeicError> {
+    let mut system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    system.set_sfx_min_interval(&id, ms);
+    Ok(())
+}
+
+/// Set the ambience channel volume (0.0 - 1.0)
+#[tauri::command]
+fn set_ambience_volume(level: f32, state: State<AudioState>) -> Result<(), HyphaeicError> {
+    let system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    system.set_ambience_volume(level);
+    Ok(())
+}
+
+/// Set the ambience crossfade duration in seconds
+#[tauri::command]
+fn set_ambience_fade_duration(secs: f32, state: State<AudioState>) -> Result<(), HyphaeicError> {
+    let mut system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    system.set_fade_duration(secs);
+    Ok(())
+}
+
+/// Set the ambience crossfade curve ("Linear" or "EqualPower")
+#[tauri::command]
+fn set_ambience_fade_curve(
+    curve: FadeCurve,
+    state: State<AudioState>,
+) -> Result<(), HyphaeicError> {
+    let mut system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    system.set_fade_curve(curve);
+    Ok(())
+}
+
+/// Register an additional ambient loop (raw audio bytes) for domains matching a pattern
+#[tauri::command]
+fn register_ambient_track(
+    track_id: String,
+    domain_pattern: String,
+    data: Vec<u8>,
+    state: State<AudioState>,
+) -> Result<(), HyphaeicError> {
+    let mut system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    system
+        .register_ambient_track(&track_id, &domain_pattern, data)
+        .map_err(HyphaeicError::InvalidInput)
+}
+
+/// Pause background ambience without losing the current track
+#[tauri::command]
+fn pause_ambience(state: State<AudioState>) -> Result<(), HyphaeicError> {
+    let mut system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    system.pause_ambience();
+    Ok(())
+}
+
+/// Resume background ambience after `pause_ambience`
+#[tauri::command]
+fn resume_ambience(state: State<AudioState>) -> Result<(), HyphaeicError> {
+    let mut system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    system.resume_ambience();
+    Ok(())
+}
+
+/// Report which audio assets loaded, so the frontend can warn when audio is degraded
+#[tauri::command]
+fn audio_status(state: State<AudioState>) -> Result<AudioLoadReport, HyphaeicError> {
+    let system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    Ok(system.get_load_report())
+}
+
+/// Current ambient track and its playback position; None while ambience is silent
+#[tauri::command]
+fn get_ambience_position(
+    state: State<AudioState>,
+) -> Result<Option<AmbiencePosition>, HyphaeicError> {
+    let system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    Ok(system.ambience_position())
+}
+
+/// Map domains whose id matches `domain_pattern` to an ambient track
+/// ("home", "window_header", "terminal", "none", or a registered custom track id)
+#[tauri::command]
+fn map_domain_ambience(
+    domain_pattern: String,
+    track_id: String,
+    state: State<AudioState>,
+) -> Result<(), HyphaeicError> {
+    let mut system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    system
+        .map_domain_ambience(&domain_pattern, &track_id)
+        .map_err(HyphaeicError::InvalidInput)
+}
+
+/// Mute or unmute all audio without stopping playback
+#[tauri::command]
+fn set_audio_muted(muted: bool, state: State<AudioState>) -> Result<(), HyphaeicError> {
+    let system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    system.set_muted(muted);
+    Ok(())
+}
+
+/// Reload SFX and ambience files from disk; returns how many loaded successfully
+/// (failures are listed by `audio_status`)
+#[tauri::command]
+fn reload_audio_assets(state: State<AudioState>) -> Result<usize, HyphaeicError> {
+    let mut system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    Ok(system.reload_assets())
+}
+
+/// Names of the available audio output devices
+#[tauri::command]
+fn list_audio_devices() -> Result<Vec<String>, HyphaeicError> {
+    audio::device::list_output_devices().map_err(HyphaeicError::Other)
+}
+
+/// Play audio through the named output device (null for the system default).
+/// If the device is gone, the default is used and `audio-device-fallback` is emitted.
+#[tauri::command]
+fn set_audio_device(
+    name: Option<String>,
+    app: AppHandle,
+    state: State<AudioState>,
+) -> Result<DeviceSelection, HyphaeicError> {
+    let mut system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    let selection = system
+        .set_output_device(name.as_deref())
+        .map_err(HyphaeicError::Other)?;
+
+    if selection.fell_back {
+        if let Some(requested) = name {
+            warn!(
+                "Audio device '{}' unavailable, using {:?}",
+                requested, selection.device
+            );
+            let payload = AudioDeviceFallbackPayload {
+                requested,
+                device: selection.device.clone(),
+            };
+            app.emit("audio-device-fallback", payload)?;
+        }
+    }
+    Ok(selection)
+}
+
+/// Save the current audio preferences (volumes, fade, mute, enabled) so they survive restarts
+#[tauri::command]
+fn save_audio_settings(state: State<AudioState>) -> Result<AudioSettings, HyphaeicError> {
+    let system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    system.save_settings().map_err(HyphaeicError::Io)?;
+    Ok(system.settings())
 }
 
-// ===== Audio Commands =====
+/// Stop all SFX that are still playing; returns how many were stopped
+#[tauri::command]
+fn stop_all_sfx(state: State<AudioState>) -> Result<usize, HyphaeicError> {
+    let system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    Ok(system.stop_all_sfx())
+}
 
+/// Turn all audio on or off; unlike mute, this also stops domain-switch SFX
 #[tauri::command]
-fn play_sound(id: String, state: State<AudioState>) -> Result<(), String> {
-    let system = state.0.lock().map_err(|e| e.to_string())?;
-    system.play_sfx(&id);
+fn set_audio_enabled(enabled: bool, state: State<AudioState>) -> Result<(), HyphaeicError> {
+    let mut system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    system.set_enabled(enabled);
     Ok(())
 }
 
+/// Whether audio is switched on
+#[tauri::command]
+fn get_audio_enabled(state: State<AudioState>) -> Result<bool, HyphaeicError> {
+    let system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
+    Ok(system.is_enabled())
+}
+
 #[tauri::command]
-fn update_audio_context(domain_id: String, state: State<AudioState>) -> Result<(), String> {
-    let mut system = state.0.lock().map_err(|e| e.to_string())?;
+fn update_audio_context(domain_id: String, state: State<AudioState>) -> Result<(), HyphaeicError> {
+    let mut system = state
+        .0
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("audio system"))?;
     system.on_domain_change(&domain_id);
     Ok(())
 }
 
 // ===== Domain Navigation Commands =====
 
-/// Register a new domain
+/// Parse a layout mode string ("grid", "list-vertical", "list-horizontal", "spatial")
+fn parse_layout_mode(layout_mode: &str, grid_columns: usize) -> Result<LayoutMode, HyphaeicError> {
+    match layout_mode {
+        // Grid navigation divides by the column count
+        "grid" if grid_columns == 0 => Err(HyphaeicError::InvalidInput(
+            "Grid layout needs at least 1 column".to_string(),
+        )),
+        "grid" => Ok(LayoutMode::Grid {
+            columns: grid_columns,
+        }),
+        "list-vertical" => Ok(LayoutMode::List {
+            direction: ListDirection::Vertical,
+        }),
+        "list-horizontal" => Ok(LayoutMode::List {
+            direction: ListDirection::Horizontal,
+        }),
+        "spatial" => Ok(LayoutMode::Spatial),
+        _ => Err(HyphaeicError::InvalidInput(format!(
+            "Unknown layout mode: {}",
+            layout_mode
+        ))),
+    }
+}
+
+/// Register a new domain (layout defaults to the configured default_layout_mode)
 #[tauri::command]
 fn register_domain(
     domain_id: String,
     parent_domain: Option<String>,
-    layout_mode: String,
+    layout_mode: Option<String>,
     grid_columns: Option<usize>,
     state: State<AppState>,
-) -> Result<(), String> {
-    let layout = match layout_mode.as_str() {
-        "grid" => LayoutMode::Grid {
-            columns: grid_columns.unwrap_or(3),
-        },
-        "list-vertical" => LayoutMode::List {
-            direction: ListDirection::Vertical,
-        },
-        "list-horizontal" => LayoutMode::List {
-            direction: ListDirection::Horizontal,
-        },
-        "spatial" => LayoutMode::Spatial,
-        _ => return Err(format!("Unknown layout mode: {}", layout_mode)),
+    config: State<Mutex<Config>>,
+) -> Result<(), HyphaeicError> {
+    let layout = {
+        let config = config
+            .lock()
+            .map_err(|_| HyphaeicError::LockPoisoned("config"))?;
+        let mode = layout_mode.unwrap_or_else(|| config.navigation.default_layout_mode.clone());
+        parse_layout_mode(
+            &mode,
+            grid_columns.unwrap_or(config.navigation.grid_columns),
+        )?
     };
 
     let mut navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
     navigator.register_domain(domain_id, parent_domain, layout)
 }
@@ -318,24 +1263,29 @@ fn unregister_domain(
     domain_id: String,
     app: AppHandle,
     state: State<AppState>,
-) -> Result<(), String> {
+) -> Result<(), HyphaeicError> {
     let mut navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
+    let previous = navigator.get_cursor_position();
     if let Some(new_cursor) = navigator.unregister_domain(&domain_id)? {
         let type_str = match new_cursor.element_type {
             ElementType::Button => "Button",
             ElementType::Gate => "Gate",
         };
 
-        let _ = app.emit(
-            "cursor-moved",
+        let bounds = navigator.element_bounds(&new_cursor.domain_id, &new_cursor.element_id);
+        emit_cursor_moved(
+            &app,
             CursorMovedPayload {
                 domain_id: new_cursor.domain_id,
                 element_id: new_cursor.element_id,
                 element_type: type_str.to_string(),
+                bounds,
+                previous_element_id: previous.as_ref().map(|p| p.element_id.clone()),
+                previous_domain_id: previous.as_ref().map(|p| p.domain_id.clone()),
             },
         );
     }
@@ -349,29 +1299,32 @@ fn register_button(
     button_id: String,
     bounds: Option<Rect>,
     order: usize,
+    label: Option<String>,
     app: AppHandle,
     state: State<AppState>,
-) -> Result<(), String> {
-    println!(
+) -> Result<(), HyphaeicError> {
+    trace!(
         "[TAURI CMD] register_button called: domain={}, button={}, order={}",
-        domain_id, button_id, order
+        domain_id,
+        button_id,
+        order
     );
 
     let mut navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
     // Get cursor position before registration
     let cursor_before = navigator.get_cursor_position();
-    println!("[TAURI CMD] Cursor before: {:?}", cursor_before);
+    trace!("[TAURI CMD] Cursor before: {:?}", cursor_before);
 
     // Register the button
-    navigator.register_button(domain_id.clone(), button_id.clone(), bounds, order)?;
+    navigator.register_button(domain_id.clone(), button_id.clone(), bounds, order, label)?;
 
     // Check if cursor was restored (position changed to this button)
     let cursor_after = navigator.get_cursor_position();
-    println!("[TAURI CMD] Cursor after: {:?}", cursor_after);
+    trace!("[TAURI CMD] Cursor after: {:?}", cursor_after);
 
     if let Some(cursor) = &cursor_after {
         // If cursor changed and is now on this button, emit event
@@ -382,14 +1335,14 @@ fn register_button(
             None => true,
         };
 
-        println!(
+        trace!(
             "[TAURI CMD] Cursor changed: {}, matches button: {}",
             cursor_changed,
             cursor.element_id == button_id
         );
 
         if cursor_changed && cursor.element_id == button_id && cursor.domain_id == domain_id {
-            println!(
+            trace!(
                 "[TAURI CMD] ✓ EMITTING cursor-moved event for {}",
                 button_id
             );
@@ -397,12 +1350,15 @@ fn register_button(
                 ElementType::Button => "Button",
                 ElementType::Gate => "Gate",
             };
-            let _ = app.emit(
-                "cursor-moved",
+            emit_cursor_moved(
+                &app,
                 CursorMovedPayload {
                     domain_id: cursor.domain_id.clone(),
                     element_id: cursor.element_id.clone(),
                     element_type: type_str.to_string(),
+                    bounds: navigator.element_bounds(&cursor.domain_id, &cursor.element_id),
+                    previous_element_id: cursor_before.as_ref().map(|p| p.element_id.clone()),
+                    previous_domain_id: cursor_before.as_ref().map(|p| p.domain_id.clone()),
                 },
             );
         }
@@ -411,24 +1367,78 @@ fn register_button(
     Ok(())
 }
 
+/// Register several buttons within a domain under a single navigator lock
+#[tauri::command]
+fn register_buttons(
+    domain_id: String,
+    buttons: Vec<ButtonSpec>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<(), HyphaeicError> {
+    trace!(
+        "[TAURI CMD] register_buttons called: domain={}, count={}",
+        domain_id,
+        buttons.len()
+    );
+
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    let cursor_before = navigator.get_cursor_position();
+    navigator.register_buttons(domain_id.clone(), buttons)?;
+    let cursor_after = navigator.get_cursor_position();
+
+    if let Some(cursor) = cursor_after {
+        let cursor_changed = match &cursor_before {
+            Some(before) => {
+                before.element_id != cursor.element_id || before.domain_id != cursor.domain_id
+            }
+            None => true,
+        };
+
+        if cursor_changed && cursor.domain_id == domain_id {
+            emit_focus_change(&app, &navigator, cursor, cursor_before);
+        }
+    }
+
+    Ok(())
+}
+
 /// Unregister a button
 #[tauri::command]
 fn unregister_button(
     domain_id: String,
     button_id: String,
+    app: AppHandle,
     state: State<AppState>,
-) -> Result<(), String> {
-    println!(
+) -> Result<(), HyphaeicError> {
+    trace!(
         "[TAURI CMD] unregister_button called: domain={}, button={}",
-        domain_id, button_id
+        domain_id,
+        button_id
     );
 
     let mut navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
-    navigator.unregister_button(&domain_id, &button_id)
+    if navigator.unregister_button(&domain_id, &button_id)? {
+        // The active domain has nothing left to focus; let the frontend pick where to go
+        let fallback_domain = navigator
+            .fallback_domain()
+            .map(|_| FALLBACK_DOMAIN.to_string());
+        let _ = app.emit(
+            "domain-emptied",
+            DomainEmptiedPayload {
+                domain_id,
+                fallback_domain,
+            },
+        );
+    }
+    Ok(())
 }
 
 /// Update button bounds without unregistering (used during resize)
@@ -438,15 +1448,31 @@ fn update_button_bounds(
     button_id: String,
     bounds: Option<Rect>,
     state: State<AppState>,
-) -> Result<(), String> {
+) -> Result<(), HyphaeicError> {
     let mut navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
     navigator.update_button_bounds(&domain_id, &button_id, bounds)
 }
 
+/// Enable or disable a button; disabled buttons are skipped by navigation
+#[tauri::command]
+fn set_button_enabled(
+    domain_id: String,
+    button_id: String,
+    enabled: bool,
+    state: State<AppState>,
+) -> Result<(), HyphaeicError> {
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    navigator.set_button_enabled(&domain_id, &button_id, enabled)
+}
+
 // DEPRECATED: Gate system replaced by spatial boundary navigation
 // /// Register a gate within a domain
 // #[tauri::command]
@@ -491,11 +1517,11 @@ fn set_active_domain(
     domain_id: String,
     state: State<AppState>,
     audio_state: State<AudioState>,
-) -> Result<(), String> {
+) -> Result<(), HyphaeicError> {
     let mut navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
     navigator.set_active_domain(domain_id.clone())?;
 
@@ -509,11 +1535,11 @@ fn set_active_domain(
 
 /// Get the current active domain ID
 #[tauri::command]
-fn get_active_domain(state: State<AppState>) -> Result<Option<String>, String> {
+fn get_active_domain(state: State<AppState>) -> Result<Option<String>, HyphaeicError> {
     let navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
     Ok(navigator.get_active_domain_id())
 }
@@ -525,14 +1551,16 @@ fn handle_wasd_input(
     app: AppHandle,
     state: State<AppState>,
     audio_state: State<AudioState>,
-) -> Result<NavigationResult, String> {
-    let wasd_key = WASDKey::from_str(&key).ok_or_else(|| format!("Invalid WASD key: {}", key))?;
+) -> Result<NavigationResult, HyphaeicError> {
+    let wasd_key = WASDKey::from_str(&key)
+        .ok_or_else(|| HyphaeicError::InvalidInput(format!("Invalid WASD key: {}", key)))?;
 
     let mut navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
+    let previous = navigator.get_cursor_position();
     let result = navigator.handle_wasd_input(wasd_key.clone());
 
     // Emit appropriate event based on navigation result
@@ -546,28 +1574,43 @@ fn handle_wasd_input(
                 ElementType::Button => "Button",
                 ElementType::Gate => "Gate",
             };
-            let _ = app.emit(
-                "cursor-moved",
+            let bounds = navigator.element_bounds(domain_id, element_id);
+            emit_cursor_moved_coalesced(
+                &app,
+                &state.cursor_coalescer,
                 CursorMovedPayload {
                     domain_id: domain_id.clone(),
                     element_id: element_id.clone(),
                     element_type: type_str.to_string(),
+                    bounds,
+                    previous_element_id: previous.as_ref().map(|p| p.element_id.clone()),
+                    previous_domain_id: previous.as_ref().map(|p| p.domain_id.clone()),
                 },
             );
         }
         // DEPRECATED: AtGate removed - gates replaced by spatial boundary navigation
         // NavigationResult::AtGate { ... } => { ... }
-        NavigationResult::BoundaryReached => {
+        NavigationResult::BoundaryReached { reason } => {
+            // Audio Feedback - the cursor didn't move, so signal the blocked input
+            if let Ok(sys) = audio_state.0.lock() {
+                sys.play_sfx("boundary");
+            }
+
             let direction = match wasd_key {
                 WASDKey::W => "up",
                 WASDKey::A => "left",
                 WASDKey::S => "down",
                 WASDKey::D => "right",
+                WASDKey::Q => "up-left",
+                WASDKey::E => "up-right",
+                WASDKey::Z => "down-left",
+                WASDKey::C => "down-right",
             };
             let _ = app.emit(
                 "boundary-reached",
                 BoundaryReachedPayload {
                     direction: direction.to_string(),
+                    reason: *reason,
                 },
             );
         }
@@ -601,7 +1644,7 @@ fn handle_wasd_input(
             let mut navigator = state
                 .domain_navigator
                 .lock()
-                .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+                .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
             let switch_result = navigator.switch_to_domain(&to_domain);
 
@@ -620,12 +1663,16 @@ fn handle_wasd_input(
                         new_element_id: new_element_id.clone(),
                     },
                 );
-                let _ = app.emit(
-                    "cursor-moved",
+                let bounds = navigator.element_bounds(t, new_element_id);
+                emit_cursor_moved(
+                    &app,
                     CursorMovedPayload {
                         domain_id: t.clone(),
                         element_id: new_element_id.clone(),
                         element_type: "Button".to_string(),
+                        bounds,
+                        previous_element_id: previous.as_ref().map(|p| p.element_id.clone()),
+                        previous_domain_id: previous.as_ref().map(|p| p.domain_id.clone()),
                     },
                 );
             }
@@ -636,26 +1683,169 @@ fn handle_wasd_input(
     Ok(result)
 }
 
+/// Cycle focus to the next (or previous) domain, regardless of bounds (Tab/Shift-Tab)
+#[tauri::command]
+fn cycle_domain(
+    forward: bool,
+    app: AppHandle,
+    state: State<AppState>,
+    audio_state: State<AudioState>,
+) -> NavigationResult {
+    process_cycle_domain(&app, &state.domain_navigator, &audio_state.0, forward)
+}
+
+/// Move focus up to the active domain's parent domain
+#[tauri::command]
+fn navigate_to_parent(
+    app: AppHandle,
+    state: State<AppState>,
+    audio_state: State<AudioState>,
+) -> Result<NavigationResult, HyphaeicError> {
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    let previous = navigator.get_cursor_position();
+    let result = navigator.go_to_parent();
+
+    if let NavigationResult::DomainSwitched {
+        from_domain,
+        to_domain,
+        new_element_id,
+    } = &result
+    {
+        // Audio Feedback
+        if let Ok(mut sys) = audio_state.0.lock() {
+            sys.on_domain_change(to_domain);
+        }
+
+        let _ = app.emit(
+            "domain-switched",
+            DomainSwitchedPayload {
+                from_domain: from_domain.clone(),
+                to_domain: to_domain.clone(),
+                new_element_id: new_element_id.clone(),
+            },
+        );
+        let bounds = navigator.element_bounds(to_domain, new_element_id);
+        emit_cursor_moved(
+            &app,
+            CursorMovedPayload {
+                domain_id: to_domain.clone(),
+                element_id: new_element_id.clone(),
+                element_type: "Button".to_string(),
+                bounds,
+                previous_element_id: previous.as_ref().map(|p| p.element_id.clone()),
+                previous_domain_id: previous.as_ref().map(|p| p.domain_id.clone()),
+            },
+        );
+    }
+
+    Ok(result)
+}
+
+/// Activate the focused button in `domain_id` and move focus to that domain's parent
+/// (or osbar-nav) in one step, so the frontend can tear the domain down afterwards
+/// without racing the focus change
+#[tauri::command]
+fn activate_and_dismiss(
+    domain_id: String,
+    app: AppHandle,
+    state: State<AppState>,
+    audio_state: State<AudioState>,
+) -> Result<NavigationResult, HyphaeicError> {
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    let cursor = navigator
+        .get_cursor_position()
+        .filter(|cursor| cursor.domain_id == domain_id)
+        .ok_or_else(|| {
+            HyphaeicError::InvalidInput(format!(
+                "Cursor is not in domain '{}', nothing to activate",
+                domain_id
+            ))
+        })?;
+    // Resolve where focus goes before activating, so a domain with nowhere to return
+    // to fails without having launched anything
+    let target = navigator.dismiss_target(&domain_id)?;
+
+    if let Ok(sys) = audio_state.0.lock() {
+        sys.play_sfx("click");
+    }
+    let bounds = navigator.element_bounds(&cursor.domain_id, &cursor.element_id);
+    let _ = app.emit(
+        "button-activate",
+        CursorMovedPayload {
+            domain_id: cursor.domain_id.clone(),
+            element_id: cursor.element_id.clone(),
+            element_type: "Button".to_string(),
+            bounds,
+            previous_element_id: None,
+            previous_domain_id: None,
+        },
+    );
+
+    let result = navigator.switch_to_domain(&target);
+
+    if let NavigationResult::DomainSwitched {
+        from_domain,
+        to_domain,
+        new_element_id,
+    } = &result
+    {
+        if let Ok(mut sys) = audio_state.0.lock() {
+            sys.on_domain_change(to_domain);
+        }
+
+        let _ = app.emit(
+            "domain-switched",
+            DomainSwitchedPayload {
+                from_domain: from_domain.clone(),
+                to_domain: to_domain.clone(),
+                new_element_id: new_element_id.clone(),
+            },
+        );
+        let bounds = navigator.element_bounds(to_domain, new_element_id);
+        emit_cursor_moved(
+            &app,
+            CursorMovedPayload {
+                domain_id: to_domain.clone(),
+                element_id: new_element_id.clone(),
+                element_type: "Button".to_string(),
+                bounds,
+                previous_element_id: Some(cursor.element_id),
+                previous_domain_id: Some(cursor.domain_id),
+            },
+        );
+    }
+
+    Ok(result)
+}
+
 /// Toggle fullscreen mode (F11)
 #[tauri::command]
-fn toggle_fullscreen(app: tauri::AppHandle) -> Result<bool, String> {
+fn toggle_fullscreen(app: tauri::AppHandle) -> Result<bool, HyphaeicError> {
     let window = app
         .get_webview_window("main")
-        .ok_or_else(|| "Main window not found".to_string())?;
+        .ok_or_else(|| HyphaeicError::Other("Main window not found".to_string()))?;
 
     let is_fullscreen = window
         .is_fullscreen()
-        .map_err(|e| format!("Failed to check fullscreen state: {}", e))?;
+        .map_err(|e| HyphaeicError::Other(format!("Failed to check fullscreen state: {}", e)))?;
 
     if is_fullscreen {
         window
             .set_fullscreen(false)
-            .map_err(|e| format!("Failed to exit fullscreen: {}", e))?;
+            .map_err(|e| HyphaeicError::Other(format!("Failed to exit fullscreen: {}", e)))?;
         Ok(false)
     } else {
         window
             .set_fullscreen(true)
-            .map_err(|e| format!("Failed to enter fullscreen: {}", e))?;
+            .map_err(|e| HyphaeicError::Other(format!("Failed to enter fullscreen: {}", e)))?;
         Ok(true)
     }
 }
@@ -669,23 +1859,28 @@ fn toggle_fullscreen(app: tauri::AppHandle) -> Result<bool, String> {
 
 /// Emit the current cursor position - useful for initial setup
 #[tauri::command]
-fn emit_cursor_position(app: AppHandle, state: State<AppState>) -> Result<bool, String> {
+fn emit_cursor_position(app: AppHandle, state: State<AppState>) -> Result<bool, HyphaeicError> {
     let navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
     if let Some(cursor) = navigator.get_cursor_position() {
         let type_str = match cursor.element_type {
             ElementType::Button => "Button",
             ElementType::Gate => "Gate",
         };
-        let _ = app.emit(
-            "cursor-moved",
+        let bounds = navigator.element_bounds(&cursor.domain_id, &cursor.element_id);
+        emit_cursor_moved(
+            &app,
             CursorMovedPayload {
                 domain_id: cursor.domain_id,
                 element_id: cursor.element_id,
                 element_type: type_str.to_string(),
+                bounds,
+                // Re-announces the current focus; there is no move to clear
+                previous_element_id: None,
+                previous_domain_id: None,
             },
         );
         Ok(true)
@@ -696,79 +1891,243 @@ fn emit_cursor_position(app: AppHandle, state: State<AppState>) -> Result<bool,
 
 /// Get current cursor position
 #[tauri::command]
-fn get_cursor_position(state: State<AppState>) -> Result<serde_json::Value, String> {
+fn get_cursor_position(state: State<AppState>) -> Result<serde_json::Value, HyphaeicError> {
+    let navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    match navigator.get_cursor_position() {
+        Some(pos) => Ok(serde_json::to_value(pos)?),
+        None => Ok(serde_json::Value::Null),
+    }
+}
+
+/// Preview where a navigation key would move the cursor, without moving it
+#[tauri::command]
+fn peek_navigation(
+    key: String,
+    state: State<AppState>,
+) -> Result<Option<NavigationPeekPayload>, HyphaeicError> {
+    let wasd_key = WASDKey::from_str(&key)
+        .ok_or_else(|| HyphaeicError::InvalidInput(format!("Invalid WASD key: {}", key)))?;
+
     let navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    Ok(navigator
+        .peek_neighbor(wasd_key)
+        .map(|(domain_id, element_id)| NavigationPeekPayload {
+            domain_id,
+            element_id,
+        }))
+}
+
+/// Set cursor position explicitly (e.g. from mouse hover)
+#[tauri::command]
+fn set_cursor_position(
+    domain_id: String,
+    element_id: String,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<(), HyphaeicError> {
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    let previous = navigator.get_cursor_position();
+    let element_type = navigator.set_cursor_position(&domain_id, &element_id)?;
+
+    // Emit event so frontend updates (clearing previous focus)
+    let type_str = match element_type {
+        ElementType::Button => "Button",
+        ElementType::Gate => "Gate",
+    };
+
+    let bounds = navigator.element_bounds(&domain_id, &element_id);
+    emit_cursor_moved(
+        &app,
+        CursorMovedPayload {
+            domain_id,
+            element_id,
+            element_type: type_str.to_string(),
+            bounds,
+            previous_element_id: previous.as_ref().map(|p| p.element_id.clone()),
+            previous_domain_id: previous.as_ref().map(|p| p.domain_id.clone()),
+        },
+    );
+
+    Ok(())
+}
+
+/// Focus the first enabled element of the active domain (Home)
+#[tauri::command]
+fn focus_first(app: AppHandle, state: State<AppState>) -> Result<(), HyphaeicError> {
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    let previous = navigator.get_cursor_position();
+    if let Some(position) = navigator.focus_first() {
+        emit_focus_change(&app, &navigator, position, previous);
+    }
+    Ok(())
+}
+
+/// Focus the last enabled element of the active domain (End)
+#[tauri::command]
+fn focus_last(app: AppHandle, state: State<AppState>) -> Result<(), HyphaeicError> {
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    let previous = navigator.get_cursor_position();
+    if let Some(position) = navigator.focus_last() {
+        emit_focus_change(&app, &navigator, position, previous);
+    }
+    Ok(())
+}
+
+/// Move the cursor to the first enabled button whose label matches (case-insensitive)
+#[tauri::command]
+fn focus_button_by_label(
+    domain_id: String,
+    label: String,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<(), HyphaeicError> {
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    let previous = navigator.get_cursor_position();
+    let position = navigator.focus_button_by_label(&domain_id, &label)?;
+    emit_focus_change(&app, &navigator, position, previous);
+
+    Ok(())
+}
+
+/// Move the cursor to the element at `index` (registration order) in a domain
+#[tauri::command]
+fn set_cursor_index(
+    domain_id: String,
+    index: usize,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<(), HyphaeicError> {
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    let previous = navigator.get_cursor_position();
+    let position = navigator.set_cursor_index(&domain_id, index)?;
+    emit_focus_change(&app, &navigator, position, previous);
+
+    Ok(())
+}
+
+/// Update domain layout mode
+#[tauri::command]
+fn update_domain_layout(
+    domain_id: String,
+    layout_mode: String,
+    grid_columns: Option<usize>,
+    state: State<AppState>,
+    config: State<Mutex<Config>>,
+) -> Result<(), HyphaeicError> {
+    let default_columns = config
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("config"))?
+        .navigation
+        .grid_columns;
+    let layout = parse_layout_mode(&layout_mode, grid_columns.unwrap_or(default_columns))?;
+
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    navigator.update_layout_mode(&domain_id, layout)
+}
+
+/// Tune how strongly spatial navigation prefers aligned targets (default 2.0)
+#[tauri::command]
+fn set_spatial_weight(multiplier: f64, state: State<AppState>) -> Result<(), HyphaeicError> {
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    navigator.set_spatial_weight(multiplier)
+}
+
+/// Replace the directions ("top", "bottom", "left", "right") the cursor may not exit a domain through
+#[tauri::command]
+fn set_boundary_lock(
+    domain_id: String,
+    directions: Vec<String>,
+    state: State<AppState>,
+) -> Result<(), HyphaeicError> {
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    navigator.set_boundary_lock(&domain_id, &directions)
+}
+
+/// Make leaving a domain through `direction` ("top", "bottom", "left", "right") enter
+/// `target_domain_id` instead of the domain inferred from bounds; null clears it
+#[tauri::command]
+fn set_domain_neighbor(
+    domain_id: String,
+    direction: String,
+    target_domain_id: Option<String>,
+    state: State<AppState>,
+) -> Result<(), HyphaeicError> {
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
-    match navigator.get_cursor_position() {
-        Some(pos) => serde_json::to_value(pos).map_err(|e| format!("Serialization error: {}", e)),
-        None => Ok(serde_json::Value::Null),
-    }
+    navigator.set_domain_neighbor(&domain_id, &direction, target_domain_id)
 }
 
-/// Set cursor position explicitly (e.g. from mouse hover)
+/// Opt a domain into returning to its last focused element on re-entry
 #[tauri::command]
-fn set_cursor_position(
+fn set_domain_restore_focus(
     domain_id: String,
-    element_id: String,
-    app: AppHandle,
+    restore_focus: bool,
     state: State<AppState>,
-) -> Result<(), String> {
+) -> Result<(), HyphaeicError> {
     let mut navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
-
-    let element_type = navigator.set_cursor_position(&domain_id, &element_id)?;
-
-    // Emit event so frontend updates (clearing previous focus)
-    let type_str = match element_type {
-        ElementType::Button => "Button",
-        ElementType::Gate => "Gate",
-    };
-
-    let _ = app.emit(
-        "cursor-moved",
-        CursorMovedPayload {
-            domain_id,
-            element_id,
-            element_type: type_str.to_string(),
-        },
-    );
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
-    Ok(())
+    navigator.set_domain_restore_focus(&domain_id, restore_focus)
 }
 
-/// Update domain layout mode
+/// Enable or disable wrap-around for a list domain
 #[tauri::command]
-fn update_domain_layout(
+fn set_domain_wrap(
     domain_id: String,
-    layout_mode: String,
-    grid_columns: Option<usize>,
+    wrap: bool,
     state: State<AppState>,
-) -> Result<(), String> {
-    let layout = match layout_mode.as_str() {
-        "grid" => LayoutMode::Grid {
-            columns: grid_columns.unwrap_or(3),
-        },
-        "list-vertical" => LayoutMode::List {
-            direction: ListDirection::Vertical,
-        },
-        "list-horizontal" => LayoutMode::List {
-            direction: ListDirection::Horizontal,
-        },
-        "spatial" => LayoutMode::Spatial,
-        _ => return Err(format!("Unknown layout mode: {}", layout_mode)),
-    };
-
+) -> Result<(), HyphaeicError> {
     let mut navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
-    navigator.update_layout_mode(&domain_id, layout)
+    navigator.set_domain_wrap(&domain_id, wrap)
 }
 
 /// Update domain bounds for spatial navigation between domains
@@ -777,57 +2136,153 @@ fn update_domain_bounds(
     domain_id: String,
     bounds: Option<Rect>,
     state: State<AppState>,
-) -> Result<(), String> {
+) -> Result<(), HyphaeicError> {
     let mut navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
     navigator.update_domain_bounds(&domain_id, bounds)
 }
 
+/// Update the bounds of many domains under one navigator lock (e.g. on window resize).
+/// Returns the ids that weren't registered; every other update is still applied.
+#[tauri::command]
+fn update_domains_bounds(
+    updates: Vec<(String, Option<Rect>)>,
+    state: State<AppState>,
+) -> Result<Vec<String>, HyphaeicError> {
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    Ok(navigator.update_domains_bounds(updates))
+}
+
 /// Get all domain IDs (for debugging)
 #[tauri::command]
-fn get_all_domains(state: State<AppState>) -> Result<Vec<String>, String> {
+fn get_all_domains(state: State<AppState>) -> Result<Vec<String>, HyphaeicError> {
     let navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
     Ok(navigator.get_all_domain_ids())
 }
 
+/// Get the entire navigation state (domains, cursor, saved cursors) for debugging
+#[tauri::command]
+fn get_navigation_snapshot(state: State<AppState>) -> Result<NavigationSnapshot, HyphaeicError> {
+    let navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    Ok(navigator.snapshot())
+}
+
+/// Restore navigation state from a snapshot (e.g. after a frontend hot reload)
+#[tauri::command]
+fn restore_navigation_snapshot(
+    snapshot: NavigationSnapshot,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<(), HyphaeicError> {
+    let mut navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    let previous = navigator.get_cursor_position();
+    navigator.load_snapshot(snapshot);
+    if let Some(cursor) = navigator.get_cursor_position() {
+        emit_focus_change(&app, &navigator, cursor, previous);
+    }
+
+    Ok(())
+}
+
+/// Which domain lies in each direction from `domain_id` (for minimaps); read-only
+#[tauri::command]
+fn get_adjacent_domains(
+    domain_id: String,
+    state: State<AppState>,
+) -> Result<HashMap<String, Option<String>>, HyphaeicError> {
+    let navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+
+    navigator.adjacent_domains(&domain_id)
+}
+
+/// Registered buttons of a domain (id, bounds, enabled, order, label) in navigation order
+#[tauri::command]
+fn get_domain_buttons(
+    domain_id: String,
+    state: State<AppState>,
+) -> Result<Vec<ButtonElement>, HyphaeicError> {
+    let navigator = state
+        .domain_navigator
+        .lock()
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
+    navigator.get_domain_buttons(&domain_id)
+}
+
 /// Get detailed domain info for debugging
 #[tauri::command]
-fn debug_domain(domain_id: String, state: State<AppState>) -> Result<serde_json::Value, String> {
+fn debug_domain(
+    domain_id: String,
+    state: State<AppState>,
+) -> Result<serde_json::Value, HyphaeicError> {
     let navigator = state
         .domain_navigator
         .lock()
-        .map_err(|e| format!("Failed to lock navigator: {}", e))?;
+        .map_err(|_| HyphaeicError::LockPoisoned("navigator"))?;
 
     match navigator.get_domain_info(&domain_id) {
-        Some(domain) => {
-            serde_json::to_value(domain).map_err(|e| format!("Serialization error: {}", e))
-        }
-        None => Err(format!("Domain '{}' not found", domain_id)),
+        Some(domain) => Ok(serde_json::to_value(domain)?),
+        None => Err(HyphaeicError::DomainNotFound(domain_id)),
+    }
+}
+
+/// Stereo pan (-1.0 left .. 1.0 right) for an element, from its center relative to
+/// its domain's bounds. Elements without measured bounds are centered (0.0).
+fn element_pan(navigator: &DomainNavigator, domain_id: &str, element_id: &str) -> f32 {
+    let (Some(element), Some(domain)) = (
+        navigator.get_button_bounds(domain_id, element_id),
+        navigator.get_domain_bounds(domain_id),
+    ) else {
+        return 0.0;
+    };
+
+    if domain.width <= 0.0 {
+        return 0.0;
     }
+
+    let (center_x, _) = element.center();
+    let relative = (center_x - domain.x) / domain.width;
+    ((relative * 2.0 - 1.0) as f32).clamp(-1.0, 1.0)
 }
 
 /// Helper function to process WASD navigation and emit events
 fn process_wasd_navigation(
     app: &AppHandle,
     navigator: &Arc<Mutex<DomainNavigator>>,
+    coalescer: &Arc<Mutex<CursorCoalescer>>,
     audio_system: &Arc<Mutex<AudioSystem>>,
     key: WASDKey,
 ) {
     let mut nav = match navigator.lock() {
         Ok(n) => n,
         Err(e) => {
-            eprintln!("Failed to lock navigator: {}", e);
+            error!("Failed to lock navigator: {}", e);
             return;
         }
     };
 
+    let previous = nav.get_cursor_position();
     let result = nav.handle_wasd_input(key.clone());
 
     // Emit appropriate event based on navigation result
@@ -837,37 +2292,55 @@ fn process_wasd_navigation(
             element_id,
             element_type,
         } => {
-            // Audio Feedback
+            // Audio Feedback - pan the nav sound toward the focused element
+            let pan = element_pan(&nav, domain_id, element_id);
             if let Ok(sys) = audio_system.lock() {
-                sys.play_sfx("nav");
+                sys.play_sfx_panned("nav", pan);
             } else {
-                eprintln!("[Audio] Failed to lock audio system for nav sound");
+                error!("[Audio] Failed to lock audio system for nav sound");
             }
 
             let type_str = match element_type {
                 ElementType::Button => "Button",
                 ElementType::Gate => "Gate", // Deprecated but kept for type safety
             };
-            let _ = app.emit(
-                "cursor-moved",
+            let bounds = nav.element_bounds(domain_id, element_id);
+            emit_cursor_moved_coalesced(
+                app,
+                coalescer,
                 CursorMovedPayload {
                     domain_id: domain_id.clone(),
                     element_id: element_id.clone(),
                     element_type: type_str.to_string(),
+                    bounds,
+                    previous_element_id: previous.as_ref().map(|p| p.element_id.clone()),
+                    previous_domain_id: previous.as_ref().map(|p| p.domain_id.clone()),
                 },
             );
         }
-        NavigationResult::BoundaryReached => {
+        NavigationResult::BoundaryReached { reason } => {
+            // Audio Feedback - the cursor didn't move, so signal the blocked input
+            if let Ok(sys) = audio_system.lock() {
+                sys.play_sfx("boundary");
+            } else {
+                error!("[Audio] Failed to lock audio system for boundary sound");
+            }
+
             let direction = match key {
                 WASDKey::W => "up",
                 WASDKey::A => "left",
                 WASDKey::S => "down",
                 WASDKey::D => "right",
+                WASDKey::Q => "up-left",
+                WASDKey::E => "up-right",
+                WASDKey::Z => "down-left",
+                WASDKey::C => "down-right",
             };
             let _ = app.emit(
                 "boundary-reached",
                 BoundaryReachedPayload {
                     direction: direction.to_string(),
+                    reason: *reason,
                 },
             );
         }
@@ -898,12 +2371,16 @@ fn process_wasd_navigation(
                         new_element_id: new_element_id.clone(),
                     },
                 );
-                let _ = app.emit(
-                    "cursor-moved",
+                let bounds = nav.element_bounds(t, new_element_id);
+                emit_cursor_moved(
+                    app,
                     CursorMovedPayload {
                         domain_id: t.clone(),
                         element_id: new_element_id.clone(),
                         element_type: "Button".to_string(),
+                        bounds,
+                        previous_element_id: previous.as_ref().map(|p| p.element_id.clone()),
+                        previous_domain_id: previous.as_ref().map(|p| p.domain_id.clone()),
                     },
                 );
             }
@@ -932,32 +2409,407 @@ fn process_activate(
         }
 
         // Gates are deprecated - only buttons can be activated now
+        let bounds = nav.element_bounds(&cursor.domain_id, &cursor.element_id);
         let _ = app.emit(
             "button-activate",
             CursorMovedPayload {
                 domain_id: cursor.domain_id,
                 element_id: cursor.element_id,
                 element_type: "Button".to_string(),
+                bounds,
+                previous_element_id: None,
+                previous_domain_id: None,
+            },
+        );
+    }
+}
+
+/// Helper function to process Tab/Shift-Tab: switch to the next or previous domain
+fn process_cycle_domain(
+    app: &AppHandle,
+    navigator: &Arc<Mutex<DomainNavigator>>,
+    audio_system: &Arc<Mutex<AudioSystem>>,
+    forward: bool,
+) -> NavigationResult {
+    let mut nav = match navigator.lock() {
+        Ok(n) => n,
+        Err(e) => {
+            return NavigationResult::Error {
+                message: format!("Failed to lock navigator: {}", e),
+            }
+        }
+    };
+
+    let previous = nav.get_cursor_position();
+    let result = nav.cycle_domain(forward);
+
+    if let NavigationResult::DomainSwitched {
+        from_domain,
+        to_domain,
+        new_element_id,
+    } = &result
+    {
+        // Audio Feedback
+        if let Ok(mut sys) = audio_system.lock() {
+            sys.on_domain_change(to_domain);
+        }
+
+        let _ = app.emit(
+            "domain-switched",
+            DomainSwitchedPayload {
+                from_domain: from_domain.clone(),
+                to_domain: to_domain.clone(),
+                new_element_id: new_element_id.clone(),
+            },
+        );
+        let bounds = nav.element_bounds(to_domain, new_element_id);
+        emit_cursor_moved(
+            app,
+            CursorMovedPayload {
+                domain_id: to_domain.clone(),
+                element_id: new_element_id.clone(),
+                element_type: "Button".to_string(),
+                bounds,
+                previous_element_id: previous.as_ref().map(|p| p.element_id.clone()),
+                previous_domain_id: previous.as_ref().map(|p| p.domain_id.clone()),
             },
         );
     }
+
+    result
+}
+
+/// Helper function to process Home/End: focus the first or last enabled element
+fn process_focus_edge(app: &AppHandle, navigator: &Arc<Mutex<DomainNavigator>>, last: bool) {
+    let mut nav = match navigator.lock() {
+        Ok(n) => n,
+        Err(_) => return,
+    };
+
+    let previous = nav.get_cursor_position();
+    let position = if last {
+        nav.focus_last()
+    } else {
+        nav.focus_first()
+    };
+    if let Some(position) = position {
+        emit_focus_change(app, &nav, position, previous);
+    }
+}
+
+/// Emit a key-driven cursor-moved through the coalescer. A buffered move is dropped
+/// at flush time if the cursor has since been placed elsewhere (that placement emits
+/// its own cursor-moved).
+fn emit_cursor_moved_coalesced(
+    app: &AppHandle,
+    coalescer: &Arc<Mutex<CursorCoalescer>>,
+    payload: CursorMovedPayload,
+) {
+    let Ok(mut pending) = coalescer.lock() else {
+        let _ = app.emit("cursor-moved", payload);
+        return;
+    };
+    let now = Instant::now();
+    let action = pending.push(payload, now);
+    let flush_tx = pending.flush_tx.clone();
+    drop(pending);
+
+    match action {
+        CoalescedMove::Emit(payload) => {
+            let _ = app.emit("cursor-moved", payload);
+        }
+        CoalescedMove::Schedule(delay) => {
+            // Without a worker (or if it has gone away) emit right away rather than lose
+            // the move; the caller still holds the navigator, so the cursor is unchanged
+            let scheduled = flush_tx.is_some_and(|tx| tx.send(now + delay).is_ok());
+            if !scheduled {
+                if let Some(payload) = coalescer
+                    .lock()
+                    .ok()
+                    .and_then(|mut pending| pending.flush(now))
+                {
+                    let _ = app.emit("cursor-moved", payload);
+                }
+            }
+        }
+        CoalescedMove::Merged => {}
+    }
+}
+
+/// Start the thread that flushes buffered cursor moves once their window closes.
+/// One worker serves every scheduled flush; it lives as long as the coalescer.
+fn spawn_cursor_flush_worker(
+    app: AppHandle,
+    navigator: Arc<Mutex<DomainNavigator>>,
+    coalescer: Arc<Mutex<CursorCoalescer>>,
+) {
+    let (flush_tx, flush_rx) = mpsc::channel::<Instant>();
+    match coalescer.lock() {
+        Ok(mut pending) => pending.flush_tx = Some(flush_tx),
+        Err(_) => return,
+    }
+
+    thread::spawn(move || {
+        for deadline in flush_rx {
+            thread::sleep(deadline.saturating_duration_since(Instant::now()));
+            flush_pending_cursor_move(&app, &navigator, &coalescer);
+        }
+    });
+}
+
+/// Emit the buffered move if the cursor is still where it says. The navigator stays
+/// locked until the event is out, so a concurrent direct move can't be overtaken by it.
+fn flush_pending_cursor_move(
+    app: &AppHandle,
+    navigator: &Arc<Mutex<DomainNavigator>>,
+    coalescer: &Arc<Mutex<CursorCoalescer>>,
+) {
+    let Ok(nav) = navigator.lock() else {
+        return;
+    };
+    let Some(payload) = coalescer
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.flush(Instant::now()))
+    else {
+        return;
+    };
+
+    if nav.get_cursor_position().is_some_and(|cursor| {
+        cursor.domain_id == payload.domain_id && cursor.element_id == payload.element_id
+    }) {
+        let _ = app.emit("cursor-moved", payload);
+    }
+}
+
+/// Emit cursor-moved right away, folding in any move still buffered by the coalescer so
+/// `previous_*` names the focus the frontend actually last saw
+fn emit_cursor_moved(app: &AppHandle, mut payload: CursorMovedPayload) {
+    if let Some(state) = app.try_state::<AppState>() {
+        if let Ok(mut coalescer) = state.cursor_coalescer.lock() {
+            payload = coalescer.supersede(payload, Instant::now());
+        }
+    }
+    let _ = app.emit("cursor-moved", payload);
+}
+
+/// Emit cursor-moved for a cursor placed directly (Home/End or commands)
+fn emit_focus_change(
+    app: &AppHandle,
+    navigator: &DomainNavigator,
+    position: CursorPosition,
+    previous: Option<CursorPosition>,
+) {
+    let type_str = match position.element_type {
+        ElementType::Button => "Button",
+        ElementType::Gate => "Gate",
+    };
+
+    let bounds = navigator.element_bounds(&position.domain_id, &position.element_id);
+    emit_cursor_moved(
+        app,
+        CursorMovedPayload {
+            domain_id: position.domain_id,
+            element_id: position.element_id,
+            element_type: type_str.to_string(),
+            bounds,
+            previous_element_id: previous.as_ref().map(|p| p.element_id.clone()),
+            previous_domain_id: previous.as_ref().map(|p| p.domain_id.clone()),
+        },
+    );
+}
+
+/// Navigation keys registered as global shortcuts (no modifiers)
+const NAV_SHORTCUT_KEYS: [(Code, WASDKey); 16] = [
+    (Code::KeyW, WASDKey::W),
+    (Code::KeyA, WASDKey::A),
+    (Code::KeyS, WASDKey::S),
+    (Code::KeyD, WASDKey::D),
+    (Code::KeyQ, WASDKey::Q),
+    (Code::KeyE, WASDKey::E),
+    (Code::KeyZ, WASDKey::Z),
+    (Code::KeyC, WASDKey::C),
+    // Vim-style aliases
+    (Code::KeyH, WASDKey::A),
+    (Code::KeyJ, WASDKey::S),
+    (Code::KeyK, WASDKey::W),
+    (Code::KeyL, WASDKey::D),
+    // Arrow keys
+    (Code::ArrowUp, WASDKey::W),
+    (Code::ArrowLeft, WASDKey::A),
+    (Code::ArrowDown, WASDKey::S),
+    (Code::ArrowRight, WASDKey::D),
+];
+
+/// Keys with fixed meanings that navigation bindings may not take over
+const FIXED_SHORTCUT_KEYS: [Code; 3] = [Code::Home, Code::End, Code::Tab];
+
+/// What a remappable navigation key does
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NavAction {
+    Move(WASDKey),
+    Activate,
+}
+
+/// Active remappable key bindings (no modifiers), shared with the shortcut handler
+struct NavigationBindings(Arc<Mutex<Vec<(Code, NavAction)>>>);
+
+/// NAV_SHORTCUT_KEYS plus Enter/Space for activation
+fn default_nav_bindings() -> Vec<(Code, NavAction)> {
+    NAV_SHORTCUT_KEYS
+        .iter()
+        .map(|(code, key)| (*code, NavAction::Move(*key)))
+        .chain([
+            (Code::Enter, NavAction::Activate),
+            (Code::Space, NavAction::Activate),
+        ])
+        .collect()
+}
+
+/// Parse a key code name (e.g. "KeyW", "ArrowUp") that navigation may bind
+fn parse_bindable_key(key_name: &str) -> Result<Code, HyphaeicError> {
+    let code: Code = key_name.parse().map_err(|_| {
+        HyphaeicError::InvalidInput(format!(
+            "Unknown key name: {} (expected e.g. KeyW, ArrowUp)",
+            key_name
+        ))
+    })?;
+    if FIXED_SHORTCUT_KEYS.contains(&code) {
+        return Err(HyphaeicError::InvalidInput(format!(
+            "{} is reserved and cannot be rebound",
+            key_name
+        )));
+    }
+    Ok(code)
+}
+
+/// Drop every binding for the `replaced` actions, then bind the `added` keys.
+/// Fails if a new key is still bound to another action.
+fn replace_bindings(
+    current: &[(Code, NavAction)],
+    replaced: &[NavAction],
+    added: Vec<(Code, NavAction)>,
+) -> Result<Vec<(Code, NavAction)>, HyphaeicError> {
+    let mut result: Vec<(Code, NavAction)> = current
+        .iter()
+        .filter(|(_, action)| !replaced.contains(action))
+        .copied()
+        .collect();
+    for (code, action) in added {
+        if let Some((_, existing)) = result.iter().find(|(bound, _)| *bound == code) {
+            return Err(HyphaeicError::InvalidInput(format!(
+                "{:?} is already bound to {:?}",
+                code, existing
+            )));
+        }
+        result.push((code, action));
+    }
+    Ok(result)
+}
+
+/// Apply `bindings` (logical action -> key code name, e.g. "up" -> "ArrowUp") on top of
+/// `current`. A rebound action loses all its previous keys; other keys are unchanged.
+fn rebind_navigation(
+    current: &[(Code, NavAction)],
+    bindings: &HashMap<String, String>,
+) -> Result<Vec<(Code, NavAction)>, HyphaeicError> {
+    let mut added = Vec::new();
+    for (action_name, key_name) in bindings {
+        let action = match action_name.to_lowercase().as_str() {
+            "up" => NavAction::Move(WASDKey::W),
+            "left" => NavAction::Move(WASDKey::A),
+            "down" => NavAction::Move(WASDKey::S),
+            "right" => NavAction::Move(WASDKey::D),
+            "activate" => NavAction::Activate,
+            _ => {
+                return Err(HyphaeicError::InvalidInput(format!(
+                    "Unknown navigation action: {}",
+                    action_name
+                )))
+            }
+        };
+        added.push((parse_bindable_key(key_name)?, action));
+    }
+
+    let replaced: Vec<NavAction> = added.iter().map(|(_, action)| *action).collect();
+    replace_bindings(current, &replaced, added)
+}
+
+/// Map a triggered shortcut back to its navigation action
+fn nav_action_for_shortcut(
+    bindings: &[(Code, NavAction)],
+    shortcut: &Shortcut,
+) -> Option<NavAction> {
+    bindings
+        .iter()
+        .find(|(code, _)| shortcut == &Shortcut::new(Some(Modifiers::empty()), *code))
+        .map(|(_, action)| *action)
+}
+
+/// Shortcuts we want registered: the navigation bindings plus Home/End, Tab and Shift-Tab
+fn default_shortcuts(bindings: &[(Code, NavAction)]) -> Vec<Shortcut> {
+    bindings
+        .iter()
+        .map(|(code, _)| *code)
+        .chain(FIXED_SHORTCUT_KEYS)
+        .map(|code| Shortcut::new(Some(Modifiers::empty()), code))
+        .chain([Shortcut::new(Some(Modifiers::SHIFT), Code::Tab)])
+        .collect()
+}
+
+/// Store new bindings and, if shortcuts are currently registered (window focused),
+/// re-register them with the new set
+fn install_nav_bindings(
+    app: AppHandle,
+    state: &NavigationBindings,
+    update: impl FnOnce(&[(Code, NavAction)]) -> Result<Vec<(Code, NavAction)>, HyphaeicError>,
+) -> Result<(), HyphaeicError> {
+    {
+        let mut bindings = state
+            .0
+            .lock()
+            .map_err(|_| HyphaeicError::LockPoisoned("navigation bindings"))?;
+        *bindings = update(&bindings)?;
+    }
+
+    let tab = Shortcut::new(Some(Modifiers::empty()), Code::Tab);
+    if app.global_shortcut().is_registered(tab) {
+        set_global_shortcuts_enabled(app, true)?;
+    }
+    Ok(())
+}
+
+/// Remap navigation keys at runtime, e.g. `{ up: "ArrowUp", activate: "KeyF" }`.
+/// Actions left out keep their current keys.
+#[tauri::command]
+fn set_navigation_bindings(
+    bindings: HashMap<String, String>,
+    app: AppHandle,
+    state: State<NavigationBindings>,
+) -> Result<(), HyphaeicError> {
+    install_nav_bindings(app, &state, |current| rebind_navigation(current, &bindings))
 }
 
-/// Default shortcuts we want registered for navigation/activation
-fn default_shortcuts() -> Vec<Shortcut> {
-    vec![
-        Shortcut::new(Some(Modifiers::empty()), Code::KeyW),
-        Shortcut::new(Some(Modifiers::empty()), Code::KeyA),
-        Shortcut::new(Some(Modifiers::empty()), Code::KeyS),
-        Shortcut::new(Some(Modifiers::empty()), Code::KeyD),
-        Shortcut::new(Some(Modifiers::empty()), Code::Enter),
-        Shortcut::new(Some(Modifiers::empty()), Code::Space),
-    ]
+/// Choose which keys activate the focused button (default Enter + Space), e.g.
+/// `["Enter"]` so content that uses Space keeps it. An empty list disables key activation.
+#[tauri::command]
+fn set_activation_keys(
+    keys: Vec<String>,
+    app: AppHandle,
+    state: State<NavigationBindings>,
+) -> Result<(), HyphaeicError> {
+    let added = keys
+        .iter()
+        .map(|key| Ok((parse_bindable_key(key)?, NavAction::Activate)))
+        .collect::<Result<Vec<_>, HyphaeicError>>()?;
+    install_nav_bindings(app, &state, |current| {
+        replace_bindings(current, &[NavAction::Activate], added)
+    })
 }
 
 /// Enable or disable global shortcuts (used to release bindings when window unfocused)
 #[tauri::command]
-fn set_global_shortcuts_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
+fn set_global_shortcuts_enabled(app: AppHandle, enabled: bool) -> Result<(), HyphaeicError> {
     if enabled {
         // First unregister all shortcuts to avoid "already registered" errors
         let _ = app.global_shortcut().unregister_all();
@@ -965,68 +2817,129 @@ fn set_global_shortcuts_enabled(app: AppHandle, enabled: bool) -> Result<(), Str
         let mut success_count = 0;
         let mut last_error = None;
 
-        for shortcut in default_shortcuts() {
+        let shortcuts = {
+            let bindings = app.state::<NavigationBindings>();
+            let bindings = bindings
+                .0
+                .lock()
+                .map_err(|_| HyphaeicError::LockPoisoned("navigation bindings"))?;
+            default_shortcuts(&bindings)
+        };
+        for shortcut in shortcuts {
             match app.global_shortcut().register(shortcut.clone()) {
                 Ok(_) => success_count += 1,
                 Err(e) => {
-                    eprintln!("Failed to register shortcut {:?}: {}", shortcut, e);
+                    error!("Failed to register shortcut {:?}: {}", shortcut, e);
                     last_error = Some(e);
                 }
             }
         }
 
         if success_count > 0 {
-            println!(
+            info!(
                 "Global shortcuts enabled ({} keys registered)",
                 success_count
             );
             Ok(())
         } else if let Some(e) = last_error {
-            Err(format!("Failed to register any shortcuts: {}", e))
+            Err(HyphaeicError::Other(format!(
+                "Failed to register any shortcuts: {}",
+                e
+            )))
         } else {
-            Err("Failed to register shortcuts for unknown reason".to_string())
+            Err(HyphaeicError::Other(
+                "Failed to register shortcuts for unknown reason".to_string(),
+            ))
         }
     } else {
         // Immediately unregister all shortcuts when window loses focus
-        println!("Global shortcuts disabled");
+        info!("Global shortcuts disabled");
         app.global_shortcut()
             .unregister_all()
-            .map_err(|e| format!("Failed to unregister shortcuts: {}", e))
+            .map_err(|e| HyphaeicError::Other(format!("Failed to unregister shortcuts: {}", e)))
     }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // RUST_LOG takes the usual env_logger directives (`debug`, `hyphaeicos_lib=debug,warn`, ...);
+    // set_log_level can then only narrow what they allow. Without RUST_LOG the logger
+    // accepts everything and the global max level (Info to start) is the whole filter.
+    let rust_log = std::env::var("RUST_LOG")
+        .ok()
+        .filter(|filters| !filters.trim().is_empty());
+    let mut logger = env_logger::Builder::new();
+    match &rust_log {
+        Some(filters) => logger.parse_filters(filters),
+        None => logger.filter_level(LevelFilter::Trace),
+    };
+    logger.init();
+    if rust_log.is_none() {
+        log::set_max_level(LevelFilter::Info);
+    }
+
+    // Resolve bundled resources up front: the audio system is created before the
+    // builder, so we ask Tauri for the same resource dir `app.path().resource_dir()` returns.
+    let context = tauri::generate_context!();
+    let resource_dir =
+        tauri::utils::platform::resource_dir(context.package_info(), &tauri::Env::default()).ok();
+    let audio_dir = asset_loader::resolve_audio_dir(resource_dir);
+
+    // Load hyphaeic.toml from the same dir `app.path().app_config_dir()` returns.
+    // A missing file gives the defaults; an unreadable one is reported and ignored.
+    let config_dir = dirs::config_dir().map(|dir| dir.join(&context.config().identifier));
+    let config = config_dir
+        .as_deref()
+        .map(|dir| {
+            Config::load(dir).unwrap_or_else(|e| {
+                warn!("Using default configuration: {}", e);
+                Config::default()
+            })
+        })
+        .unwrap_or_default();
+
     // Initialize domain navigator with Arc for sharing with shortcut handlers
-    let navigator = Arc::new(Mutex::new(DomainNavigator::new()));
+    let mut domain_navigator = DomainNavigator::new();
+
+    // Initialize Audio System (its output stream lives on a dedicated thread)
+    let settings_path = config_dir.map(|dir| dir.join(audio::SETTINGS_FILE));
+    let mut audio_sys = AudioSystem::new(&audio_dir, settings_path);
+
+    let mut pty_manager = PtyManager::new();
+    let mut nav_cooldown = NavCooldown::new(DEFAULT_NAV_COOLDOWN);
+    apply_config(
+        &config,
+        &mut domain_navigator,
+        &mut nav_cooldown,
+        &mut audio_sys,
+        &mut pty_manager,
+    );
 
-    // Initialize Audio System
-    // We must keep _stream alive, even though we don't use it directly, else audio stops.
-    let (audio_sys, _stream) = AudioSystem::new();
+    let navigator = Arc::new(Mutex::new(domain_navigator));
     let audio_system = Arc::new(Mutex::new(audio_sys));
+    let startup_domain = config.audio.startup_domain.clone();
 
     // Initialize application state
     let app_state = AppState {
         domain_navigator: navigator.clone(),
+        cursor_coalescer: Arc::new(Mutex::new(CursorCoalescer::default())),
+        nav_cooldown: Arc::new(Mutex::new(nav_cooldown)),
     };
 
-    // Define shortcuts (no modifiers)
-    let [shortcut_w, shortcut_a, shortcut_s, shortcut_d, shortcut_enter, shortcut_space] = {
-        let mut list = default_shortcuts();
-        let mut iter = list.drain(..);
-        [
-            iter.next().unwrap(),
-            iter.next().unwrap(),
-            iter.next().unwrap(),
-            iter.next().unwrap(),
-            iter.next().unwrap(),
-            iter.next().unwrap(),
-        ]
-    };
+    // Home/End and Tab shortcuts (no modifiers, plus Shift-Tab).
+    // Navigation and activation keys are matched via the remappable NavigationBindings.
+    let shortcut_home = Shortcut::new(Some(Modifiers::empty()), Code::Home);
+    let shortcut_end = Shortcut::new(Some(Modifiers::empty()), Code::End);
+    let shortcut_tab = Shortcut::new(Some(Modifiers::empty()), Code::Tab);
+    let shortcut_shift_tab = Shortcut::new(Some(Modifiers::SHIFT), Code::Tab);
 
-    // Clone navigator and audio for the shortcut handler closure
+    // Clone navigator, coalescer, cooldown, audio and bindings for the shortcut handler closure
     let nav_for_handler = navigator.clone();
+    let coalescer_for_handler = app_state.cursor_coalescer.clone();
+    let cooldown_for_handler = app_state.nav_cooldown.clone();
     let audio_for_handler = audio_system.clone();
+    let nav_bindings = Arc::new(Mutex::new(default_nav_bindings()));
+    let bindings_for_handler = nav_bindings.clone();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
@@ -1038,58 +2951,66 @@ pub fn run() {
                         return;
                     }
 
-                    // Match shortcut and process navigation
-                    if shortcut == &shortcut_w {
+                    // Match shortcut and process navigation (bindings lock released first)
+                    let action = bindings_for_handler
+                        .lock()
+                        .ok()
+                        .and_then(|bindings| nav_action_for_shortcut(&bindings, shortcut));
+                    if let Some(NavAction::Move(key)) = action {
+                        let accepted = cooldown_for_handler
+                            .lock()
+                            .map(|mut cooldown| cooldown.accept(key, Instant::now()))
+                            .unwrap_or(true);
+                        if !accepted {
+                            trace!("[NAV] Ignoring repeated {:?} within the cooldown", key);
+                            return;
+                        }
                         process_wasd_navigation(
                             app,
                             &nav_for_handler,
+                            &coalescer_for_handler,
                             &audio_for_handler,
-                            WASDKey::W,
+                            key,
                         );
-                    } else if shortcut == &shortcut_a {
-                        process_wasd_navigation(
-                            app,
-                            &nav_for_handler,
-                            &audio_for_handler,
-                            WASDKey::A,
-                        );
-                    } else if shortcut == &shortcut_s {
-                        process_wasd_navigation(
-                            app,
-                            &nav_for_handler,
-                            &audio_for_handler,
-                            WASDKey::S,
-                        );
-                    } else if shortcut == &shortcut_d {
-                        process_wasd_navigation(
-                            app,
-                            &nav_for_handler,
-                            &audio_for_handler,
-                            WASDKey::D,
-                        );
-                    } else if shortcut == &shortcut_enter || shortcut == &shortcut_space {
+                    } else if let Some(NavAction::Activate) = action {
                         process_activate(app, &nav_for_handler, &audio_for_handler);
+                    } else if shortcut == &shortcut_home {
+                        process_focus_edge(app, &nav_for_handler, false);
+                    } else if shortcut == &shortcut_end {
+                        process_focus_edge(app, &nav_for_handler, true);
+                    } else if shortcut == &shortcut_tab || shortcut == &shortcut_shift_tab {
+                        let forward = shortcut == &shortcut_tab;
+                        process_cycle_domain(app, &nav_for_handler, &audio_for_handler, forward);
                     }
                 })
                 .build(),
         )
         .manage(app_state)
         .manage(AudioState(audio_system))
-        .manage(Mutex::new(StateManager::new()))
-        .manage(Mutex::new(PtyManager::new()))
-        .setup(|app| {
+        .manage(Mutex::new(StateManager::new(config.windows.slot_count)))
+        .manage(Mutex::new(pty_manager))
+        .manage(Mutex::new(config))
+        .manage(AssetDownloads::default())
+        .manage(OfflineMode::default())
+        .manage(NavigationBindings(nav_bindings))
+        .setup(move |app| {
             // NOTE: Shortcuts are NOT registered here anymore.
             // Frontend controls registration via set_global_shortcuts_enabled()
             // This prevents duplicate registrations and allows proper focus/blur handling.
-            println!(
-                "WASD navigation system initialized (shortcuts will register on window focus)"
+            info!("WASD navigation system initialized (shortcuts will register on window focus)");
+
+            let app_state = app.state::<AppState>();
+            spawn_cursor_flush_worker(
+                app.handle().clone(),
+                app_state.domain_navigator.clone(),
+                app_state.cursor_coalescer.clone(),
             );
 
             // Initialize audio context for startup
             let audio_state = app.state::<AudioState>();
             if let Ok(mut sys) = audio_state.0.lock() {
-                // Default to osbar navigation soundscape on startup
-                sys.on_domain_change("osbar-nav");
+                // Start with the configured soundscape (osbar navigation by default)
+                sys.on_domain_change(&startup_domain);
             }
 
             Ok(())
@@ -1097,44 +3018,217 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             // Original commands
             greet,
+            set_log_level,
+            reload_config,
             load_asset,
             clear_asset_cache,
+            enforce_cache_limit,
+            cancel_asset_download,
+            get_cache_stats,
+            set_offline_mode,
+            import_local_asset,
+            prefetch_assets,
             is_asset_cached,
             get_asset_cache_path,
             // Window management commands
             spawn_window,
+            spawn_window_lru,
             close_window,
             remove_window,
             set_window_state,
+            cycle_window_state,
+            get_hidden_windows,
+            unhide_window,
+            swap_windows,
+            restore_last_closed,
+            save_layout,
+            load_layout,
+            set_window_title,
+            move_window_to_slot,
             // Domain navigation commands
             register_domain,
             unregister_domain,
             register_button,
+            register_buttons,
             unregister_button,
             update_button_bounds,
+            set_button_enabled,
             set_active_domain,
             get_active_domain,
             handle_wasd_input,
+            navigate_to_parent,
+            activate_and_dismiss,
+            cycle_domain,
             get_cursor_position,
+            peek_navigation,
             emit_cursor_position,
             set_cursor_position,
+            focus_button_by_label,
+            set_cursor_index,
+            focus_first,
+            focus_last,
             get_all_domains,
+            get_domain_buttons,
             debug_domain,
+            get_navigation_snapshot,
+            restore_navigation_snapshot,
+            get_adjacent_domains,
             update_domain_layout,
+            set_domain_wrap,
+            set_boundary_lock,
+            set_domain_neighbor,
+            set_spatial_weight,
+            set_domain_restore_focus,
             update_domain_bounds,
+            update_domains_bounds,
             toggle_fullscreen,
             set_global_shortcuts_enabled,
+            set_navigation_bindings,
+            set_activation_keys,
             // PTY terminal commands
             pty_spawn,
             pty_write,
+            pty_paste,
             pty_read,
             pty_resize,
+            pty_get_size,
             pty_close,
+            pty_is_alive,
+            pty_run_once,
+            pty_search,
+            pty_start_recording,
+            pty_stop_recording,
+            pty_signal,
             get_system_banner,
             // Audio
             play_sound,
+            play_sound_ex,
+            list_sfx_ids,
+            set_audio_ducking,
+            play_sound_pitched,
             update_audio_context,
+            set_master_volume,
+            set_audio_muted,
+            set_audio_enabled,
+            stop_all_sfx,
+            save_audio_settings,
+            reload_audio_assets,
+            list_audio_devices,
+            set_audio_device,
+            get_audio_enabled,
+            set_sfx_volume,
+            set_sfx_min_interval,
+            set_ambience_volume,
+            set_ambience_fade_duration,
+            set_ambience_fade_curve,
+            register_ambient_track,
+            map_domain_ambience,
+            audio_status,
+            pause_ambience,
+            resume_ambience,
+            get_ambience_position,
         ])
-        .run(tauri::generate_context!())
+        .run(context)
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moved_to(element_id: &str, previous: &str) -> CursorMovedPayload {
+        CursorMovedPayload {
+            domain_id: "grid".to_string(),
+            element_id: element_id.to_string(),
+            element_type: "Button".to_string(),
+            bounds: None,
+            previous_element_id: Some(previous.to_string()),
+            previous_domain_id: Some("grid".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_nav_cooldown_drops_quick_repeats_only() {
+        let mut cooldown = NavCooldown::new(DEFAULT_NAV_COOLDOWN);
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert!(cooldown.accept(WASDKey::D, at(0)));
+        assert!(!cooldown.accept(WASDKey::D, at(30)));
+        // Windows are measured from the last accepted press, so held keys still repeat
+        assert!(cooldown.accept(WASDKey::D, at(55)));
+
+        // Changing direction is immediate, and so is turning back
+        assert!(cooldown.accept(WASDKey::S, at(60)));
+        assert!(cooldown.accept(WASDKey::D, at(65)));
+        assert!(!cooldown.accept(WASDKey::D, at(70)));
+
+        cooldown.set_window(Duration::ZERO);
+        assert!(cooldown.accept(WASDKey::D, at(70)));
+    }
+
+    #[test]
+    fn test_cursor_coalescer_keeps_last_move() {
+        let mut coalescer = CursorCoalescer::default();
+        let start = Instant::now();
+
+        // First move after a quiet period goes out immediately
+        assert!(matches!(
+            coalescer.push(moved_to("btn-1", "btn-0"), start),
+            CoalescedMove::Emit(_)
+        ));
+
+        // Autorepeat inside the window is buffered, then merged
+        let delay =
+            match coalescer.push(moved_to("btn-2", "btn-1"), start + Duration::from_millis(5)) {
+                CoalescedMove::Schedule(delay) => delay,
+                _ => panic!("expected the move to be buffered"),
+            };
+        assert_eq!(delay, CURSOR_COALESCE_WINDOW - Duration::from_millis(5));
+        assert!(matches!(
+            coalescer.push(
+                moved_to("btn-3", "btn-2"),
+                start + Duration::from_millis(10)
+            ),
+            CoalescedMove::Merged
+        ));
+
+        // Only the latest position lands, relative to the last emitted focus
+        let flushed = coalescer.flush(start + CURSOR_COALESCE_WINDOW).unwrap();
+        assert_eq!(flushed.element_id, "btn-3");
+        assert_eq!(flushed.previous_element_id.as_deref(), Some("btn-1"));
+        assert!(coalescer.flush(start + CURSOR_COALESCE_WINDOW).is_none());
+
+        // Once the window has passed, moves are emitted directly again
+        assert!(matches!(
+            coalescer.push(
+                moved_to("btn-4", "btn-3"),
+                start + Duration::from_millis(40)
+            ),
+            CoalescedMove::Emit(_)
+        ));
+    }
+
+    #[test]
+    fn test_direct_move_supersedes_buffered_move() {
+        let mut coalescer = CursorCoalescer::default();
+        let start = Instant::now();
+
+        coalescer.push(moved_to("btn-1", "btn-0"), start);
+        assert!(matches!(
+            coalescer.push(moved_to("btn-2", "btn-1"), start + Duration::from_millis(5)),
+            CoalescedMove::Schedule(_)
+        ));
+
+        // btn-2 was never shown, so the direct move clears btn-1's highlight instead
+        let direct =
+            coalescer.supersede(moved_to("btn-9", "btn-2"), start + Duration::from_millis(8));
+        assert_eq!(direct.element_id, "btn-9");
+        assert_eq!(direct.previous_element_id.as_deref(), Some("btn-1"));
+        assert!(coalescer.flush(start + CURSOR_COALESCE_WINDOW).is_none());
+
+        // Without a buffered move the direct payload is left alone
+        let direct = coalescer.supersede(moved_to("btn-3", "btn-9"), start);
+        assert_eq!(direct.previous_element_id.as_deref(), Some("btn-9"));
+    }
+}
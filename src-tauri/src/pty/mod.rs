@@ -1,18 +1,55 @@
+use crate::error::HyphaeicError;
+use log::{debug, error, info, trace, warn};
 use portable_pty::{native_pty_system, Child, CommandBuilder, PtyPair, PtySize};
+use regex::Regex;
+use serde::Serialize;
 use std::collections::HashMap;
-use std::io::{Read, Write};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
 ///radix clock system here? please leave this comment models.
 
+/// Default banner width in columns (matches the classic 64-column box)
+pub const DEFAULT_BANNER_WIDTH: usize = 64;
+
+/// Narrowest banner that still fits the label/value/status columns
+pub const MIN_BANNER_WIDTH: usize = 48;
+
+/// Banner width to use when the caller doesn't pick one: `DEFAULT_BANNER_WIDTH`,
+/// widened as needed so the whole session id fits on its row
+pub fn fit_banner_width(session_id: &str, session_id_len: Option<usize>) -> usize {
+    let value_len = session_label(session_id, session_id_len).chars().count();
+    DEFAULT_BANNER_WIDTH.max(value_len + BANNER_ROW_OVERHEAD)
+}
+
 /// Generates the retro COBOL/FORTRAN-style system status banner
-pub fn generate_system_banner(session_id: &str) -> String {
-    println!(
-        "[PTY] generate_system_banner called for session: {}",
-        session_id
+///
+/// `width` is the total width of the box in columns (must be >= `MIN_BANNER_WIDTH`).
+/// `session_id_len` optionally shortens the session id; `None` shows the full id,
+/// which is only cut off when the box is too narrow to hold it (see `banner_stat_row`).
+/// Use `fit_banner_width` for a width that always has room for it.
+pub fn generate_system_banner(
+    session_id: &str,
+    width: usize,
+    session_id_len: Option<usize>,
+) -> Result<String, HyphaeicError> {
+    debug!(
+        "[PTY] generate_system_banner called for session: {}, width: {}",
+        session_id, width
     );
 
+    if width < MIN_BANNER_WIDTH {
+        return Err(HyphaeicError::InvalidInput(format!(
+            "Banner width {} is below the minimum of {}",
+            width, MIN_BANNER_WIDTH
+        )));
+    }
+
     // Get system information using compile-time checks (safe)
     let endian = if cfg!(target_endian = "little") {
         "LITTLE-ENDIAN"
@@ -29,141 +66,411 @@ pub fn generate_system_banner(session_id: &str) -> String {
     let arch = std::env::consts::ARCH;
     let os = std::env::consts::OS;
 
-    // Format session ID as short hex
-    let session_hex: String = session_id.chars().take(6).collect();
+    // Live stats - only refresh RAM and the CPU list so this stays cheap
+    let sys = System::new_with_specifics(
+        RefreshKind::nothing()
+            .with_memory(MemoryRefreshKind::nothing().with_ram())
+            .with_cpu(CpuRefreshKind::nothing()),
+    );
+    let memory = format_memory(sys.used_memory(), sys.total_memory());
+    let cpu_cores = format!("{} CORES", sys.cpus().len());
+    let uptime = format_uptime(System::uptime());
+
+    let session = session_label(session_id, session_id_len);
+
+    // Everything between the two vertical borders
+    let inner = width - 2;
+    let rule = "═".repeat(inner);
+
+    let title = if inner >= 51 {
+        "H Y P H A E I C   T E R M I N A L   S Y S T E M"
+    } else {
+        "HYPHAEIC TERMINAL SYSTEM"
+    };
+
+    let mut lines = vec![
+        format!("╔{}╗", rule),
+        banner_text_row(title, inner),
+        format!("║{}║", rule),
+        banner_text_row("", inner),
+        banner_text_row("SYSTEM DIAGNOSTICS COMPLETE", inner),
+        format!("║{}║", rule),
+    ];
+
+    let rows = [
+        ("ENDIAN CHECK", endian, "[OK]"),
+        ("POINTER SIZE", pointer_size, "[OK]"),
+        ("CPU ARCH", arch, "[OK]"),
+        ("TARGET OS", os, "[OK]"),
+        ("MEMORY", memory.as_str(), "[OK]"),
+        ("CPU CORES", cpu_cores.as_str(), "[OK]"),
+        ("UPTIME", uptime.as_str(), "[OK]"),
+        ("PTY SESSION", session.as_str(), "[ACTIVE]"),
+    ];
+    for (label, value, status) in rows {
+        lines.push(banner_stat_row(label, value, status, inner));
+    }
+
+    lines.push(format!("╚{}╝", rule));
+
+    debug!("[PTY] Banner generated successfully");
+
+    Ok(format!("\n{}\n\n", lines.join("\n")))
+}
+
+/// Width of the dotted label column in stat rows
+const BANNER_LABEL_WIDTH: usize = 23;
+
+/// Width of the status column in stat rows ("[ACTIVE]" is the longest)
+const BANNER_STATUS_WIDTH: usize = 8;
+
+/// Columns a stat row uses besides its value: borders, indent, label, status and spacing
+const BANNER_ROW_OVERHEAD: usize = 2 + 2 + BANNER_LABEL_WIDTH + 1 + 1 + BANNER_STATUS_WIDTH + 2;
+
+/// Format the session id as hex for the banner, optionally shortened
+fn session_label(session_id: &str, session_id_len: Option<usize>) -> String {
+    let session_hex: String = match session_id_len {
+        Some(len) => session_id.chars().take(len).collect(),
+        None => session_id.to_string(),
+    };
+    format!("0x{}", session_hex.to_uppercase())
+}
 
-    println!("[PTY] Banner generated successfully");
+/// Render a free-text row, left-indented and padded to the inner width
+fn banner_text_row(text: &str, inner: usize) -> String {
+    let text = truncate_chars(&format!("  {}", text), inner);
+    format!("║{:<inner$}║", text, inner = inner)
+}
 
+/// Render a `LABEL....... VALUE [STATUS]` row padded to the inner width
+///
+/// Values longer than the value column are truncated so the borders stay aligned;
+/// this is the fallback for narrow boxes, widen the banner to see the whole value.
+fn banner_stat_row(label: &str, value: &str, status: &str, inner: usize) -> String {
+    let value_width = inner + 2 - BANNER_ROW_OVERHEAD;
+    let value = truncate_chars(value, value_width);
     format!(
-        r#"
-╔══════════════════════════════════════════════════════════════╗
-║  H Y P H A E I C   T E R M I N A L   S Y S T E M             ║
-║══════════════════════════════════════════════════════════════║
-║                                                              ║
-║  SYSTEM DIAGNOSTICS COMPLETE                                 ║
-║══════════════════════════════════════════════════════════════║
-║  ENDIAN CHECK........... {:<16} [OK]               ║
-║  POINTER SIZE........... {:<16} [OK]               ║
-║  CPU ARCH............... {:<16} [OK]               ║
-║  TARGET OS.............. {:<16} [OK]               ║
-║  PTY SESSION............ {:<16} [ACTIVE]           ║
-╚══════════════════════════════════════════════════════════════╝
-
-"#,
-        endian,
-        pointer_size,
-        arch,
-        os,
-        format!("0x{}", session_hex.to_uppercase())
+        "║  {:.<label_w$} {:<value_w$} {:<status_w$}  ║",
+        label,
+        value,
+        status,
+        label_w = BANNER_LABEL_WIDTH,
+        value_w = value_width,
+        status_w = BANNER_STATUS_WIDTH,
     )
 }
 
+/// Truncate a string to at most `max` characters
+fn truncate_chars(s: &str, max: usize) -> String {
+    s.chars().take(max).collect()
+}
+
+/// Format used/total RAM (bytes) as a short GiB string, e.g. "6.2/15.5 GIB"
+fn format_memory(used: u64, total: u64) -> String {
+    const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+    format!("{:.1}/{:.1} GIB", used as f64 / GIB, total as f64 / GIB)
+}
+
+/// Format an uptime in seconds as days/hours/minutes, e.g. "1D 02H 03M"
+fn format_uptime(secs: u64) -> String {
+    let days = secs / 86_400;
+    let hours = (secs % 86_400) / 3_600;
+    let minutes = (secs % 3_600) / 60;
+    format!("{}D {:02}H {:02}M", days, hours, minutes)
+}
+
 /// Represents a single PTY session with thread-safe output buffer
 pub struct PtySession {
     pub pair: PtyPair,
     pub child: Box<dyn Child + Send + Sync>,
     pub writer: Arc<Mutex<Box<dyn Write + Send>>>,
     pub output_buffer: Arc<Mutex<Vec<u8>>>,
+    /// Bounded copy of recent output kept for search (not drained by `read`)
+    pub scrollback: Arc<Mutex<Vec<u8>>>,
+    /// Transcript file, when recording is on (shared with the reader thread)
+    pub recording: Arc<Mutex<Option<Recording>>>,
+    /// Last size the PTY was opened or resized to, so a reattaching view can restore it
+    pub size: PtySize,
+    /// The shell understands bracketed paste, so `paste` wraps text in paste markers
+    pub bracketed_paste: bool,
     pub is_alive: Arc<Mutex<bool>>,
     pub ref_count: u32,
 }
 
+/// Default cap on concurrently open PTY sessions
+pub const DEFAULT_MAX_SESSIONS: usize = 16;
+
+/// Initial PTY rows when `pty_spawn` isn't given a size
+pub const DEFAULT_ROWS: u16 = 24;
+
+/// Initial PTY columns when `pty_spawn` isn't given a size
+pub const DEFAULT_COLS: u16 = 80;
+
+/// Terminal dimensions in character cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PtyDimensions {
+    pub rows: u16,
+    pub cols: u16,
+}
+
+/// Build a PtySize, rejecting zero rows or columns
+fn pty_size(rows: u16, cols: u16) -> Result<PtySize, HyphaeicError> {
+    if rows == 0 || cols == 0 {
+        return Err(HyphaeicError::InvalidInput(format!(
+            "PTY size must be nonzero (got {}x{})",
+            cols, rows
+        )));
+    }
+    Ok(PtySize {
+        rows,
+        cols,
+        pixel_width: 0,
+        pixel_height: 0,
+    })
+}
+
+/// Bytes of output each session retains for `search` (oldest output is dropped first)
+pub const SCROLLBACK_BYTES: usize = 1024 * 1024;
+
+/// Sent before and after pasted text so the shell inserts it verbatim instead of
+/// running each line
+const PASTE_START: &str = "\x1b[200~";
+const PASTE_END: &str = "\x1b[201~";
+
+/// Shells (by program name) whose line editors enable bracketed paste by default
+const BRACKETED_PASTE_SHELLS: &[&str] = &["bash", "zsh", "fish"];
+
+/// Whether `shell` (a program name or path) is known to support bracketed paste
+fn supports_bracketed_paste(shell: &str) -> bool {
+    Path::new(shell)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .is_some_and(|name| BRACKETED_PASTE_SHELLS.contains(&name))
+}
+
+/// Bytes to send for a paste: wrapped in paste markers when the shell supports them.
+/// An end marker inside the text is dropped so the paste can't end early.
+fn paste_payload(text: &str, bracketed: bool) -> Vec<u8> {
+    if !bracketed {
+        return text.as_bytes().to_vec();
+    }
+    let mut payload = String::from(PASTE_START);
+    payload.push_str(&text.replace(PASTE_END, ""));
+    payload.push_str(PASTE_END);
+    payload.into_bytes()
+}
+
+/// Largest slice of input written (and flushed) per writer lock, so a giant paste
+/// neither holds the lock for long nor floods the PTY input queue
+const WRITE_CHUNK_BYTES: usize = 4096;
+
+/// Longest snippet returned per search match, in characters
+const SEARCH_SNIPPET_CHARS: usize = 200;
+
+/// How often a recording's buffered writes are flushed to disk
+const RECORDING_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Which side of the terminal a recorded chunk came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Input,
+    Output,
+}
+
+/// A buffered transcript of a session's I/O
+pub struct Recording {
+    writer: BufWriter<File>,
+    /// Also record input, with a marker line whenever the direction changes
+    include_input: bool,
+    last_direction: Option<Direction>,
+    last_flush: Instant,
+}
+
+impl Recording {
+    /// Open (or append to) a transcript file
+    pub fn open(path: &str, include_input: bool) -> Result<Self, HyphaeicError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| HyphaeicError::Io(format!("Failed to open {}: {}", path, e)))?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            include_input,
+            last_direction: None,
+            last_flush: Instant::now(),
+        })
+    }
+
+    /// Append a chunk, flushing if the last flush is older than `RECORDING_FLUSH_INTERVAL`
+    pub fn record(&mut self, direction: Direction, data: &[u8]) -> std::io::Result<()> {
+        if direction == Direction::Input && !self.include_input {
+            return Ok(());
+        }
+        if self.include_input && self.last_direction != Some(direction) {
+            let marker: &[u8] = match direction {
+                Direction::Input => b"\n--- input ---\n",
+                Direction::Output => b"\n--- output ---\n",
+            };
+            self.writer.write_all(marker)?;
+            self.last_direction = Some(direction);
+        }
+        self.writer.write_all(data)?;
+        if self.last_flush.elapsed() >= RECORDING_FLUSH_INTERVAL {
+            self.writer.flush()?;
+            self.last_flush = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// Flush what's buffered; the file is closed when the recording is dropped
+    pub fn finish(mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Write a chunk to a session's recording, if any; a failing recording is stopped
+fn record_chunk(recording: &Mutex<Option<Recording>>, direction: Direction, data: &[u8]) {
+    if let Ok(mut recording) = recording.lock() {
+        if let Some(active) = recording.as_mut() {
+            if let Err(e) = active.record(direction, data) {
+                warn!("[PTY] Recording failed, stopping it: {}", e);
+                *recording = None;
+            }
+        }
+    }
+}
+
+/// A scrollback line matching a search pattern
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchMatch {
+    /// 1-based line number within the retained scrollback
+    pub line: usize,
+    /// The matching line with escape sequences removed (truncated if very long)
+    pub snippet: String,
+}
+
 /// Manages multiple PTY sessions
 pub struct PtyManager {
     sessions: HashMap<String, PtySession>,
+    /// Maximum number of distinct sessions (each owns a shell and a reader thread)
+    max_sessions: usize,
+    /// Shell program override; None uses the platform default
+    shell: Option<String>,
 }
 
 impl PtyManager {
     pub fn new() -> Self {
-        println!("[PTY] PtyManager::new() called");
+        debug!("[PTY] PtyManager::new() called");
         Self {
             sessions: HashMap::new(),
+            max_sessions: DEFAULT_MAX_SESSIONS,
+            shell: None,
         }
     }
 
-    /// Spawn a new PTY session, returns the session ID
-    pub fn spawn(&mut self, session_id: String) -> Result<String, String> {
-        println!("[PTY] spawn() called with session_id: {}", session_id);
+    /// Set the maximum number of concurrent sessions (existing sessions are kept)
+    pub fn set_max_sessions(&mut self, max_sessions: usize) {
+        debug!("[PTY] max_sessions set to {}", max_sessions);
+        self.max_sessions = max_sessions;
+    }
+
+    /// Set the shell used for new sessions (None restores the platform default)
+    pub fn set_shell(&mut self, shell: Option<String>) {
+        debug!("[PTY] shell set to {:?}", shell);
+        self.shell = shell;
+    }
+
+    /// Spawn a new PTY session at `rows` x `cols`, returns the session ID
+    ///
+    /// Reattaching to an existing session keeps its current size.
+    pub fn spawn(
+        &mut self,
+        session_id: String,
+        rows: u16,
+        cols: u16,
+    ) -> Result<String, HyphaeicError> {
+        debug!("[PTY] spawn() called with session_id: {}", session_id);
+        let size = pty_size(rows, cols)?;
 
         // Check if session already exists
         if let Some(session) = self.sessions.get_mut(&session_id) {
             session.ref_count += 1;
-            println!(
+            debug!(
                 "[PTY] Session {} already exists, incrementing ref_count to {}",
                 session_id, session.ref_count
             );
             return Ok(session_id);
         }
 
-        println!("[PTY] Creating new session: {}", session_id);
+        if self.sessions.len() >= self.max_sessions {
+            error!("[PTY] Session limit reached ({} open)", self.sessions.len());
+            return Err(HyphaeicError::PtyLimitReached(self.max_sessions));
+        }
 
-        println!("[PTY] Getting native PTY system...");
+        info!("[PTY] Creating new session: {}", session_id);
+
+        debug!("[PTY] Getting native PTY system...");
         let pty_system = native_pty_system();
 
-        // Create PTY with default size (will be resized by frontend)
-        println!("[PTY] Opening PTY with size 24x80...");
-        let pair = pty_system
-            .openpty(PtySize {
-                rows: 24,
-                cols: 80,
-                pixel_width: 0,
-                pixel_height: 0,
-            })
-            .map_err(|e| {
-                println!("[PTY] ERROR: Failed to open PTY: {}", e);
-                format!("Failed to open PTY: {}", e)
-            })?;
-        println!("[PTY] PTY opened successfully");
+        debug!("[PTY] Opening PTY with size {}x{}...", cols, rows);
+        let pair = pty_system.openpty(size).map_err(|e| {
+            error!("[PTY] Failed to open PTY: {}", e);
+            HyphaeicError::Pty(format!("Failed to open PTY: {}", e))
+        })?;
+        debug!("[PTY] PTY opened successfully");
 
-        // Build shell command (platform-specific)
+        // Build shell command (configured shell, else platform-specific)
         #[cfg(target_os = "windows")]
-        let cmd = {
-            println!("[PTY] Building PowerShell command...");
-            CommandBuilder::new("powershell.exe")
-        };
+        let default_shell = "powershell.exe";
 
         #[cfg(not(target_os = "windows"))]
-        let cmd = {
-            println!("[PTY] Building bash command...");
-            CommandBuilder::new("bash")
-        };
+        let default_shell = "bash";
+
+        let shell = self.shell.as_deref().unwrap_or(default_shell);
+        debug!("[PTY] Building {} command...", shell);
+        let cmd = CommandBuilder::new(shell);
 
         // Spawn the shell process
-        println!("[PTY] Spawning shell process...");
+        debug!("[PTY] Spawning shell process...");
         let child = pair.slave.spawn_command(cmd).map_err(|e| {
-            println!("[PTY] ERROR: Failed to spawn shell: {}", e);
-            format!("Failed to spawn shell: {}", e)
+            error!("[PTY] Failed to spawn shell: {}", e);
+            HyphaeicError::Pty(format!("Failed to spawn shell: {}", e))
         })?;
-        println!("[PTY] Shell process spawned successfully");
+        debug!("[PTY] Shell process spawned successfully");
 
         // Get reader and writer from master
-        println!("[PTY] Cloning reader from master...");
+        debug!("[PTY] Cloning reader from master...");
         let reader = pair.master.try_clone_reader().map_err(|e| {
-            println!("[PTY] ERROR: Failed to clone PTY reader: {}", e);
-            format!("Failed to clone PTY reader: {}", e)
+            error!("[PTY] Failed to clone PTY reader: {}", e);
+            HyphaeicError::Pty(format!("Failed to clone PTY reader: {}", e))
         })?;
-        println!("[PTY] Reader cloned successfully");
+        debug!("[PTY] Reader cloned successfully");
 
-        println!("[PTY] Taking writer from master...");
+        debug!("[PTY] Taking writer from master...");
         let writer = pair.master.take_writer().map_err(|e| {
-            println!("[PTY] ERROR: Failed to take PTY writer: {}", e);
-            format!("Failed to take PTY writer: {}", e)
+            error!("[PTY] Failed to take PTY writer: {}", e);
+            HyphaeicError::Pty(format!("Failed to take PTY writer: {}", e))
         })?;
-        println!("[PTY] Writer taken successfully");
+        debug!("[PTY] Writer taken successfully");
 
         // Create shared output buffer
-        println!("[PTY] Creating shared buffers...");
+        debug!("[PTY] Creating shared buffers...");
         let output_buffer = Arc::new(Mutex::new(Vec::new()));
+        let scrollback = Arc::new(Mutex::new(Vec::new()));
+        let recording = Arc::new(Mutex::new(None));
         let is_alive = Arc::new(Mutex::new(true));
 
         // Spawn a background thread to read from PTY
         let buffer_clone = Arc::clone(&output_buffer);
+        let scrollback_clone = Arc::clone(&scrollback);
+        let recording_clone = Arc::clone(&recording);
         let alive_clone = Arc::clone(&is_alive);
         let session_id_clone = session_id.clone();
 
-        println!("[PTY] Spawning reader thread...");
+        debug!("[PTY] Spawning reader thread...");
         thread::spawn(move || {
-            println!(
+            debug!(
                 "[PTY THREAD] Reader thread started for session: {}",
                 session_id_clone
             );
@@ -174,7 +481,7 @@ impl PtyManager {
                 // Check if session is still alive
                 if let Ok(alive) = alive_clone.lock() {
                     if !*alive {
-                        println!("[PTY THREAD] Session no longer alive, exiting");
+                        debug!("[PTY THREAD] Session no longer alive, exiting");
                         break;
                     }
                 }
@@ -182,19 +489,29 @@ impl PtyManager {
                 // Try to read with a small buffer
                 match reader.read(&mut buf) {
                     Ok(0) => {
-                        // EOF - process ended
-                        println!("[PTY THREAD] EOF received, process ended");
+                        // EOF - process ended, mark session dead so callers can tell
+                        debug!("[PTY THREAD] EOF received, process ended");
+                        if let Ok(mut alive) = alive_clone.lock() {
+                            *alive = false;
+                        }
                         break;
                     }
                     Ok(n) => {
                         if let Ok(mut buffer) = buffer_clone.lock() {
                             buffer.extend_from_slice(&buf[..n]);
                         }
+                        if let Ok(mut scrollback) = scrollback_clone.lock() {
+                            append_scrollback(&mut scrollback, &buf[..n]);
+                        }
+                        record_chunk(&recording_clone, Direction::Output, &buf[..n]);
                     }
                     Err(e) => {
                         // Check if it's a would-block error (non-fatal)
                         if e.kind() != std::io::ErrorKind::WouldBlock {
-                            println!("[PTY THREAD] Read error: {}", e);
+                            warn!("[PTY THREAD] Read error: {}", e);
+                            if let Ok(mut alive) = alive_clone.lock() {
+                                *alive = false;
+                            }
                             break;
                         }
                     }
@@ -203,116 +520,237 @@ impl PtyManager {
                 // Small sleep to prevent busy-waiting
                 thread::sleep(Duration::from_millis(10));
             }
-            println!("[PTY THREAD] Reader thread exiting");
+            debug!("[PTY THREAD] Reader thread exiting");
         });
-        println!("[PTY] Reader thread spawned");
+        debug!("[PTY] Reader thread spawned");
 
-        println!("[PTY] Creating PtySession struct...");
+        debug!("[PTY] Creating PtySession struct...");
         let session = PtySession {
             pair,
             child,
             writer: Arc::new(Mutex::new(writer)),
             output_buffer,
+            scrollback,
+            recording,
+            size,
+            bracketed_paste: supports_bracketed_paste(shell),
             is_alive,
             ref_count: 1,
         };
 
-        println!("[PTY] Inserting session into HashMap...");
+        debug!("[PTY] Inserting session into HashMap...");
         self.sessions.insert(session_id.clone(), session);
 
-        println!(
+        info!(
             "[PTY] spawn() completed successfully, returning session_id: {}",
             session_id
         );
         Ok(session_id)
     }
 
-    /// Write data to a PTY session
-    pub fn write(&self, session_id: &str, data: &[u8]) -> Result<(), String> {
-        println!(
+    /// Write data to a PTY session in bounded chunks, flushing after each.
+    /// Returns the number of bytes written; an error reports how many got through first.
+    pub fn write(&self, session_id: &str, data: &[u8]) -> Result<usize, HyphaeicError> {
+        trace!(
             "[PTY] write() called for session: {}, data len: {}",
             session_id,
             data.len()
         );
 
         let session = self.sessions.get(session_id).ok_or_else(|| {
-            println!("[PTY] ERROR: Session {} not found", session_id);
-            format!("Session {} not found", session_id)
+            error!("[PTY] Session {} not found", session_id);
+            HyphaeicError::PtyNotFound(session_id.to_string())
         })?;
 
-        let mut writer = session.writer.lock().map_err(|e| {
-            println!("[PTY] ERROR: Failed to lock writer: {}", e);
-            format!("Failed to lock writer: {}", e)
-        })?;
+        let mut written = 0;
+        for chunk in data.chunks(WRITE_CHUNK_BYTES) {
+            let mut writer = session.writer.lock().map_err(|e| {
+                error!("[PTY] Failed to lock writer: {}", e);
+                HyphaeicError::LockPoisoned("PTY writer")
+            })?;
 
-        writer.write_all(data).map_err(|e| {
-            println!("[PTY] ERROR: Failed to write to PTY: {}", e);
-            format!("Failed to write to PTY: {}", e)
-        })?;
+            writer
+                .write_all(chunk)
+                .and_then(|_| writer.flush())
+                .map_err(|e| {
+                    error!(
+                        "[PTY] Write failed after {} of {} bytes: {}",
+                        written,
+                        data.len(),
+                        e
+                    );
+                    HyphaeicError::Pty(format!(
+                        "Failed to write to PTY after {} of {} bytes: {}",
+                        written,
+                        data.len(),
+                        e
+                    ))
+                })?;
+            drop(writer);
 
-        writer.flush().map_err(|e| {
-            println!("[PTY] ERROR: Failed to flush PTY: {}", e);
-            format!("Failed to flush PTY: {}", e)
-        })?;
+            record_chunk(&session.recording, Direction::Input, chunk);
+            written += chunk.len();
+        }
 
-        println!("[PTY] write() completed successfully");
-        Ok(())
+        trace!("[PTY] write() completed: {} bytes", written);
+        Ok(written)
+    }
+
+    /// Paste text into a PTY session, wrapped in bracketed-paste markers when its
+    /// shell supports them (otherwise written as-is). Returns the bytes written.
+    pub fn paste(&self, session_id: &str, text: &str) -> Result<usize, HyphaeicError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| HyphaeicError::PtyNotFound(session_id.to_string()))?;
+        let payload = paste_payload(text, session.bracketed_paste);
+        self.write(session_id, &payload)
     }
 
     /// Read available data from a PTY session (non-blocking - drains buffer)
-    pub fn read(&self, session_id: &str) -> Result<Vec<u8>, String> {
+    pub fn read(&self, session_id: &str) -> Result<Vec<u8>, HyphaeicError> {
         // Don't log every read call since it polls frequently
         let session = self
             .sessions
             .get(session_id)
-            .ok_or_else(|| format!("Session {} not found", session_id))?;
+            .ok_or_else(|| HyphaeicError::PtyNotFound(session_id.to_string()))?;
 
         let mut buffer = session
             .output_buffer
             .lock()
-            .map_err(|e| format!("Failed to lock buffer: {}", e))?;
+            .map_err(|_| HyphaeicError::LockPoisoned("PTY output buffer"))?;
 
         // Drain the buffer and return its contents
         let data = std::mem::take(&mut *buffer);
         if !data.is_empty() {
-            println!("[PTY] read() returning {} bytes", data.len());
+            trace!("[PTY] read() returning {} bytes", data.len());
         }
         Ok(data)
     }
 
+    /// Start writing a session's output (and optionally input) to a transcript file
+    ///
+    /// Replaces any recording already running for the session.
+    pub fn start_recording(
+        &self,
+        session_id: &str,
+        path: &str,
+        include_input: bool,
+    ) -> Result<(), HyphaeicError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| HyphaeicError::PtyNotFound(session_id.to_string()))?;
+
+        let new_recording = Recording::open(path, include_input)?;
+        let mut recording = session
+            .recording
+            .lock()
+            .map_err(|_| HyphaeicError::LockPoisoned("PTY recording"))?;
+        if let Some(previous) = recording.replace(new_recording) {
+            if let Err(e) = previous.finish() {
+                warn!("[PTY] Failed to flush previous recording: {}", e);
+            }
+        }
+
+        info!("[PTY] Recording session {} to {}", session_id, path);
+        Ok(())
+    }
+
+    /// Stop recording a session, flushing and closing the transcript file
+    pub fn stop_recording(&self, session_id: &str) -> Result<(), HyphaeicError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| HyphaeicError::PtyNotFound(session_id.to_string()))?;
+
+        let recording = session
+            .recording
+            .lock()
+            .map_err(|_| HyphaeicError::LockPoisoned("PTY recording"))?
+            .take();
+        if let Some(recording) = recording {
+            recording
+                .finish()
+                .map_err(|e| HyphaeicError::Io(format!("Failed to flush recording: {}", e)))?;
+            info!("[PTY] Stopped recording session {}", session_id);
+        }
+        Ok(())
+    }
+
+    /// Search a session's retained scrollback for a regex, line by line
+    ///
+    /// Read-only: the live output buffer drained by `read` is left untouched.
+    pub fn search(
+        &self,
+        session_id: &str,
+        pattern: &str,
+    ) -> Result<Vec<SearchMatch>, HyphaeicError> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| HyphaeicError::InvalidInput(format!("Invalid search pattern: {}", e)))?;
+
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| HyphaeicError::PtyNotFound(session_id.to_string()))?;
+
+        let text = {
+            let scrollback = session
+                .scrollback
+                .lock()
+                .map_err(|_| HyphaeicError::LockPoisoned("PTY scrollback"))?;
+            String::from_utf8_lossy(&scrollback).into_owned()
+        };
+
+        let matches = search_text(&strip_escape_sequences(&text), &regex);
+        debug!(
+            "[PTY] search() found {} matches for /{}/ in session {}",
+            matches.len(),
+            pattern,
+            session_id
+        );
+        Ok(matches)
+    }
+
     /// Resize a PTY session
-    pub fn resize(&self, session_id: &str, rows: u16, cols: u16) -> Result<(), String> {
-        println!(
+    pub fn resize(&mut self, session_id: &str, rows: u16, cols: u16) -> Result<(), HyphaeicError> {
+        debug!(
             "[PTY] resize() called for session: {}, rows: {}, cols: {}",
             session_id, rows, cols
         );
+        let size = pty_size(rows, cols)?;
 
-        let session = self.sessions.get(session_id).ok_or_else(|| {
-            println!("[PTY] ERROR: Session {} not found", session_id);
-            format!("Session {} not found", session_id)
+        let session = self.sessions.get_mut(session_id).ok_or_else(|| {
+            error!("[PTY] Session {} not found", session_id);
+            HyphaeicError::PtyNotFound(session_id.to_string())
         })?;
 
-        session
-            .pair
-            .master
-            .resize(PtySize {
-                rows,
-                cols,
-                pixel_width: 0,
-                pixel_height: 0,
-            })
-            .map_err(|e| {
-                println!("[PTY] ERROR: Failed to resize PTY: {}", e);
-                format!("Failed to resize PTY: {}", e)
-            })?;
+        session.pair.master.resize(size).map_err(|e| {
+            error!("[PTY] Failed to resize PTY: {}", e);
+            HyphaeicError::Pty(format!("Failed to resize PTY: {}", e))
+        })?;
+        session.size = size;
 
-        println!("[PTY] resize() completed successfully");
+        debug!("[PTY] resize() completed successfully");
         Ok(())
     }
 
+    /// Last known size of a session (as spawned or last resized)
+    pub fn size(&self, session_id: &str) -> Result<PtyDimensions, HyphaeicError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| HyphaeicError::PtyNotFound(session_id.to_string()))?;
+
+        Ok(PtyDimensions {
+            rows: session.size.rows,
+            cols: session.size.cols,
+        })
+    }
+
     /// Close a PTY session
-    pub fn close(&mut self, session_id: &str) -> Result<(), String> {
-        println!("[PTY] close() called for session: {}", session_id);
+    pub fn close(&mut self, session_id: &str) -> Result<(), HyphaeicError> {
+        debug!("[PTY] close() called for session: {}", session_id);
 
         if let Some(session) = self.sessions.get_mut(session_id) {
             // Decrement ref count
@@ -320,7 +758,7 @@ impl PtyManager {
                 session.ref_count -= 1;
             }
 
-            println!(
+            debug!(
                 "[PTY] Session {} ref_count decremented to {}",
                 session_id, session.ref_count
             );
@@ -331,48 +769,73 @@ impl PtyManager {
             }
         } else {
             // Session not found - idempotent success to prevent errors on double-close
-            println!(
-                "[PTY] Warning: Session {} not found during close (already closed?)",
+            warn!(
+                "[PTY] Session {} not found during close (already closed?)",
                 session_id
             );
             return Ok(());
         }
 
         // Ref count is 0, proceed with removal
-        if let Some(mut session) = self.sessions.remove(session_id) {
-            println!(
+        if let Some(session) = self.sessions.remove(session_id) {
+            info!(
                 "[PTY] Session {} ref_count is 0, closing session...",
                 session_id
             );
 
-            // Signal the reader thread to stop
-            println!("[PTY] Signaling reader thread to stop...");
-            if let Ok(mut alive) = session.is_alive.lock() {
+            let PtySession {
+                pair,
+                mut child,
+                writer,
+                is_alive,
+                recording,
+                ..
+            } = session;
+
+            // The reader thread only clears this at EOF, which can lag behind the
+            // background master release below; the session is dead as of now
+            if let Ok(mut alive) = is_alive.lock() {
                 *alive = false;
             }
 
+            // Close any transcript before tearing the session down
+            if let Some(recording) = recording.lock().ok().and_then(|mut r| r.take()) {
+                if let Err(e) = recording.finish() {
+                    warn!("[PTY] Failed to flush recording: {}", e);
+                }
+            }
+
             // Kill the child process - this will cause the reader to get EOF
-            println!("[PTY] Killing child process...");
-            if let Err(e) = session.child.kill() {
-                println!("[PTY] Warning: Failed to kill child process: {}", e);
+            debug!("[PTY] Killing child process...");
+            if let Err(e) = child.kill() {
+                warn!("[PTY] Failed to kill child process: {}", e);
                 // Continue anyway - the process might have already exited
             }
 
             // Wait for the child to actually exit
-            println!("[PTY] Waiting for child to exit...");
-            let _ = session.child.wait();
+            debug!("[PTY] Waiting for child to exit...");
+            let _ = child.wait();
 
-            // Give the reader thread time to notice EOF and exit
-            println!("[PTY] Waiting for reader thread to exit...");
-            thread::sleep(Duration::from_millis(100));
+            // Release the PTY in a controlled order: input side first, then the slave,
+            // then the master. On Windows, dropping the master calls ClosePseudoConsole,
+            // which blocks until the output pipe is drained. The reader thread keeps
+            // draining until EOF (it is deliberately not told to stop), so the master is
+            // dropped on its own thread to let both sides finish without deadlocking.
+            debug!("[PTY] Releasing PTY handles...");
+            drop(writer);
+            let PtyPair { master, slave } = pair;
+            drop(slave);
 
-            // WORKAROUND: On Windows, dropping the PtyPair causes a crash in ConPTY cleanup.
-            // We use std::mem::forget to skip the drop and leak the memory instead.
-            // This is a known issue with portable-pty on Windows.
-            println!("[PTY] Forgetting PtyPair to avoid ConPTY cleanup crash...");
-            std::mem::forget(session.pair);
+            let session_id_clone = session_id.to_string();
+            thread::spawn(move || {
+                drop(master);
+                debug!(
+                    "[PTY THREAD] PTY master released for session: {}",
+                    session_id_clone
+                );
+            });
 
-            println!("[PTY] close() completed successfully");
+            debug!("[PTY] close() completed successfully");
             Ok(())
         } else {
             // Should be unreachable due to check above, but safe fallback
@@ -380,6 +843,92 @@ impl PtyManager {
         }
     }
 
+    /// Send a signal to the child process of a session without closing it
+    ///
+    /// The session stays registered so the reader thread can observe the exit.
+    pub fn signal(&mut self, session_id: &str, signal: &str) -> Result<(), HyphaeicError> {
+        debug!(
+            "[PTY] signal() called for session: {}, signal: {}",
+            session_id, signal
+        );
+
+        let session = self.sessions.get_mut(session_id).ok_or_else(|| {
+            error!("[PTY] Session {} not found", session_id);
+            HyphaeicError::PtyNotFound(session_id.to_string())
+        })?;
+
+        let name = signal.to_uppercase();
+        let name = name.strip_prefix("SIG").unwrap_or(&name);
+
+        #[cfg(unix)]
+        {
+            let signum = match name {
+                "INT" => libc::SIGINT,
+                "TERM" => libc::SIGTERM,
+                "KILL" => libc::SIGKILL,
+                "HUP" => libc::SIGHUP,
+                "QUIT" => libc::SIGQUIT,
+                _ => {
+                    return Err(HyphaeicError::InvalidInput(format!(
+                        "Unsupported signal '{}' on this platform",
+                        signal
+                    )))
+                }
+            };
+
+            let pid = session.child.process_id().ok_or_else(|| {
+                HyphaeicError::Pty(format!("Session {} has no child pid", session_id))
+            })?;
+
+            // SAFETY: kill(2) has no memory-safety preconditions
+            let rc = unsafe { libc::kill(pid as libc::pid_t, signum) };
+            if rc != 0 {
+                let err = std::io::Error::last_os_error();
+                error!("[PTY] Failed to signal child: {}", err);
+                return Err(HyphaeicError::Pty(format!(
+                    "Failed to send {} to child: {}",
+                    signal, err
+                )));
+            }
+        }
+
+        #[cfg(not(unix))]
+        {
+            match name {
+                "TERMINATE" | "TERM" | "KILL" => {
+                    session.child.kill().map_err(|e| {
+                        error!("[PTY] Failed to terminate child: {}", e);
+                        HyphaeicError::Pty(format!("Failed to terminate child: {}", e))
+                    })?;
+                }
+                _ => {
+                    return Err(HyphaeicError::InvalidInput(format!(
+                        "Unsupported signal '{}' on this platform",
+                        signal
+                    )))
+                }
+            }
+        }
+
+        debug!("[PTY] signal() completed successfully");
+        Ok(())
+    }
+
+    /// Check whether the shell behind a session is still running
+    pub fn is_alive(&self, session_id: &str) -> Result<bool, HyphaeicError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| HyphaeicError::PtyNotFound(session_id.to_string()))?;
+
+        let alive = session
+            .is_alive
+            .lock()
+            .map_err(|_| HyphaeicError::LockPoisoned("PTY alive flag"))?;
+
+        Ok(*alive)
+    }
+
     /// Check if a session exists
     #[allow(dead_code)]
     pub fn has_session(&self, session_id: &str) -> bool {
@@ -388,7 +937,523 @@ impl PtyManager {
 
     /// Get the system status banner for a session
     #[allow(dead_code)]
-    pub fn get_banner(&self, session_id: &str) -> String {
-        generate_system_banner(session_id)
+    pub fn get_banner(&self, session_id: &str, width: usize) -> Result<String, HyphaeicError> {
+        generate_system_banner(session_id, width, None)
+    }
+}
+
+/// Append output to a scrollback buffer, dropping the oldest bytes past `SCROLLBACK_BYTES`
+fn append_scrollback(scrollback: &mut Vec<u8>, data: &[u8]) {
+    scrollback.extend_from_slice(data);
+    if scrollback.len() > SCROLLBACK_BYTES {
+        let excess = scrollback.len() - SCROLLBACK_BYTES;
+        scrollback.drain(..excess);
+    }
+}
+
+/// Remove ANSI escape sequences (CSI and OSC) and carriage returns from terminal output
+fn strip_escape_sequences(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: parameters and intermediates end at a final byte in '@'..='~'
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: ends at BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Collect the lines of `text` that match `regex`
+fn search_text(text: &str, regex: &Regex) -> Vec<SearchMatch> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| regex.is_match(line))
+        .map(|(i, line)| SearchMatch {
+            line: i + 1,
+            snippet: truncate_chars(line.trim_end(), SEARCH_SNIPPET_CHARS),
+        })
+        .collect()
+}
+
+/// Result of a one-shot command run through `run_once`
+#[derive(Debug, Clone, Serialize)]
+pub struct RunOnceOutput {
+    /// Everything the command wrote to the terminal (stderr is merged in by the PTY)
+    pub stdout: String,
+    /// Exit code, or None if the command was killed on timeout
+    pub exit_code: Option<u32>,
+    pub timed_out: bool,
+}
+
+/// How often `run_once` checks for exit while the output is quiet
+const RUN_ONCE_POLL: Duration = Duration::from_millis(50);
+
+/// Run a single command in a throwaway PTY and collect its output
+///
+/// The program is spawned directly (no shell) and is not registered with a
+/// `PtyManager`. Output is collected until EOF or `timeout`; on timeout the child
+/// is killed and `timed_out` is set.
+pub fn run_once(
+    program: &str,
+    args: &[String],
+    cwd: Option<&str>,
+    timeout: Duration,
+) -> Result<RunOnceOutput, HyphaeicError> {
+    debug!("[PTY] run_once() called: {} {:?}", program, args);
+
+    let pair = native_pty_system()
+        .openpty(PtySize {
+            rows: DEFAULT_ROWS,
+            cols: 200,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .map_err(|e| HyphaeicError::Pty(format!("Failed to open PTY: {}", e)))?;
+
+    let mut cmd = CommandBuilder::new(program);
+    cmd.args(args);
+    if let Some(dir) = cwd {
+        cmd.cwd(dir);
+    }
+
+    let PtyPair { master, slave } = pair;
+    let mut child = slave
+        .spawn_command(cmd)
+        .map_err(|e| HyphaeicError::Pty(format!("Failed to spawn {}: {}", program, e)))?;
+    // Only the child should hold the slave, so the reader sees EOF when it exits
+    drop(slave);
+
+    let mut reader = master
+        .try_clone_reader()
+        .map_err(|e| HyphaeicError::Pty(format!("Failed to clone PTY reader: {}", e)))?;
+
+    let (tx, rx) = mpsc::channel::<Vec<u8>>();
+    thread::spawn(move || {
+        let mut buf = [0u8; 1024];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if tx.send(buf[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+                // Unix reports EIO once the child closes the slave
+                Err(_) => break,
+            }
+        }
+    });
+
+    let deadline = Instant::now() + timeout;
+    let mut output = Vec::new();
+    let mut status = None;
+    let mut timed_out = false;
+
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            timed_out = true;
+            break;
+        }
+        match rx.recv_timeout((deadline - now).min(RUN_ONCE_POLL)) {
+            Ok(chunk) => output.extend_from_slice(&chunk),
+            Err(RecvTimeoutError::Disconnected) => break,
+            Err(RecvTimeoutError::Timeout) => {
+                // ConPTY doesn't report EOF until the master is dropped, so stop once
+                // the child has exited and its output has gone quiet
+                if status.is_some() {
+                    break;
+                }
+                status = child
+                    .try_wait()
+                    .map_err(|e| HyphaeicError::Pty(format!("Failed to poll child: {}", e)))?;
+            }
+        }
+    }
+
+    if timed_out {
+        warn!(
+            "[PTY] run_once() timed out after {:?}, killing {}",
+            timeout, program
+        );
+        if let Err(e) = child.kill() {
+            warn!("[PTY] Failed to kill child process: {}", e);
+        }
+    }
+
+    let status = match status {
+        Some(status) => status,
+        None => child
+            .wait()
+            .map_err(|e| HyphaeicError::Pty(format!("Failed to wait for child: {}", e)))?,
+    };
+
+    // Dropping the master can block on Windows (see PtyManager::close)
+    thread::spawn(move || drop(master));
+
+    debug!(
+        "[PTY] run_once() finished: {} bytes, exit code {}",
+        output.len(),
+        status.exit_code()
+    );
+    Ok(RunOnceOutput {
+        stdout: String::from_utf8_lossy(&output).into_owned(),
+        exit_code: (!timed_out).then(|| status.exit_code()),
+        timed_out,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_banner_reports_memory() {
+        let banner =
+            generate_system_banner("abcdef123456", DEFAULT_BANNER_WIDTH, None).unwrap();
+        let memory_row = banner
+            .lines()
+            .find(|line| line.contains("MEMORY"))
+            .expect("Banner should contain a MEMORY row");
+
+        let value = memory_row
+            .trim_start_matches(|c: char| c != '.')
+            .trim_start_matches('.')
+            .trim();
+        assert!(value.contains("GIB"), "Memory value missing: {}", memory_row);
+    }
+
+    #[test]
+    fn test_banner_lines_match_width() {
+        let banner = generate_system_banner("abcdef123456", 80, None).unwrap();
+
+        for line in banner.lines().filter(|line| !line.is_empty()) {
+            assert_eq!(line.chars().count(), 80, "Misaligned line: {}", line);
+        }
+        assert!(banner.contains("0xABCDEF123456"));
+    }
+
+    #[test]
+    fn test_banner_truncates_session_id_only_when_narrow() {
+        let session_id = "0123456789abcdef0123456789abcdef";
+
+        let wide = generate_system_banner(session_id, 96, None).unwrap();
+        assert!(wide.contains("0x0123456789ABCDEF0123456789ABCDEF"));
+
+        let narrow = generate_system_banner(session_id, MIN_BANNER_WIDTH, None).unwrap();
+        assert!(!narrow.contains("0x0123456789ABCDEF0123456789ABCDEF"));
+        assert!(narrow.contains("0x0123"));
+
+        let short = generate_system_banner(session_id, 96, Some(6)).unwrap();
+        assert!(short.contains("0x012345 "));
+    }
+
+    #[test]
+    fn test_default_width_fits_terminal_session_id() {
+        // Terminal session ids are `${windowId}-${Date.now()}`
+        let session_id = "9b2f4c1e-6a7d-4e3b-8f5a-2c1d0e9f8a7b-1760601234567";
+        let width = fit_banner_width(session_id, None);
+        assert!(width > DEFAULT_BANNER_WIDTH);
+
+        let banner = generate_system_banner(session_id, width, None).unwrap();
+        assert!(banner.contains(&format!("0x{}", session_id.to_uppercase())));
+        for line in banner.lines().filter(|line| !line.is_empty()) {
+            assert_eq!(line.chars().count(), width, "Misaligned line: {}", line);
+        }
+
+        // Short ids keep the classic box
+        assert_eq!(fit_banner_width("abcdef", None), DEFAULT_BANNER_WIDTH);
+        assert_eq!(fit_banner_width(session_id, Some(6)), DEFAULT_BANNER_WIDTH);
+    }
+
+    #[test]
+    fn test_banner_rejects_narrow_width() {
+        assert!(generate_system_banner("abcdef", MIN_BANNER_WIDTH - 1, None).is_err());
+        assert!(generate_system_banner("abcdef", MIN_BANNER_WIDTH, None).is_ok());
+    }
+
+    /// Serializes tests that open real PTYs, so handle counts and the session
+    /// limit aren't skewed by shells spawned from tests running in parallel
+    static PTY_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    fn pty_test_guard() -> std::sync::MutexGuard<'static, ()> {
+        PTY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Count handles/file descriptors currently open in this process
+    #[cfg(unix)]
+    fn open_handle_count() -> usize {
+        std::fs::read_dir("/dev/fd").map(|d| d.count()).unwrap_or(0)
+    }
+
+    #[cfg(windows)]
+    fn open_handle_count() -> usize {
+        extern "system" {
+            fn GetCurrentProcess() -> isize;
+            fn GetProcessHandleCount(process: isize, count: *mut u32) -> i32;
+        }
+        let mut count = 0u32;
+        // SAFETY: GetCurrentProcess returns a pseudo-handle; count is a valid out pointer
+        unsafe { GetProcessHandleCount(GetCurrentProcess(), &mut count) };
+        count as usize
+    }
+
+    #[test]
+    fn test_close_releases_pty_handles() {
+        let _guard = pty_test_guard();
+        let mut manager = PtyManager::new();
+
+        // Warm up once so lazily-initialized process state isn't counted as a leak
+        manager
+            .spawn("warmup".to_string(), DEFAULT_ROWS, DEFAULT_COLS)
+            .unwrap();
+        manager.close("warmup").unwrap();
+        thread::sleep(Duration::from_millis(200));
+        let baseline = open_handle_count();
+
+        for i in 0..100 {
+            let id = format!("leak-test-{}", i);
+            manager
+                .spawn(id.clone(), DEFAULT_ROWS, DEFAULT_COLS)
+                .unwrap();
+            let alive = Arc::clone(&manager.sessions[&id].is_alive);
+            manager.close(&id).unwrap();
+            assert!(!*alive.lock().unwrap(), "{} still alive after close", id);
+        }
+
+        // Give the background release threads time to finish
+        thread::sleep(Duration::from_millis(500));
+        let after = open_handle_count();
+
+        assert!(
+            after < baseline + 20,
+            "Handle count grew from {} to {} after 100 sessions",
+            baseline,
+            after
+        );
+    }
+
+    #[test]
+    fn test_write_reports_bytes_across_chunks() {
+        let _guard = pty_test_guard();
+        let mut manager = PtyManager::new();
+        manager
+            .spawn("paste".to_string(), DEFAULT_ROWS, DEFAULT_COLS)
+            .unwrap();
+
+        // Shell comments, so the paste spans several chunks without running anything
+        let paste = "# pasted line\n".repeat(WRITE_CHUNK_BYTES * 2 / 14 + 1);
+        assert!(paste.len() > WRITE_CHUNK_BYTES * 2);
+        assert_eq!(manager.write("paste", paste.as_bytes()), Ok(paste.len()));
+        assert_eq!(manager.write("paste", b""), Ok(0));
+        manager.close("paste").unwrap();
+
+        assert_eq!(
+            manager.write("paste", b"ls\r"),
+            Err(HyphaeicError::PtyNotFound("paste".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_bracketed_paste_payload() {
+        assert!(supports_bracketed_paste("bash"));
+        assert!(supports_bracketed_paste("/usr/bin/zsh"));
+        assert!(!supports_bracketed_paste("powershell.exe"));
+        assert!(!supports_bracketed_paste("sh"));
+
+        assert_eq!(paste_payload("a\nb", false), b"a\nb");
+        assert_eq!(
+            paste_payload("  a\n  b\x1b[201~rm -rf ~\n", true),
+            b"\x1b[200~  a\n  brm -rf ~\n\x1b[201~"
+        );
+    }
+
+    #[test]
+    fn test_session_limit() {
+        let _guard = pty_test_guard();
+        let mut manager = PtyManager::new();
+
+        for i in 0..DEFAULT_MAX_SESSIONS {
+            manager
+                .spawn(format!("limit-test-{}", i), DEFAULT_ROWS, DEFAULT_COLS)
+                .unwrap();
+        }
+
+        let err = manager
+            .spawn(
+                "limit-test-overflow".to_string(),
+                DEFAULT_ROWS,
+                DEFAULT_COLS,
+            )
+            .unwrap_err();
+        assert_eq!(err, HyphaeicError::PtyLimitReached(DEFAULT_MAX_SESSIONS));
+
+        // Re-attaching to an existing session doesn't count against the limit
+        assert!(manager
+            .spawn("limit-test-0".to_string(), DEFAULT_ROWS, DEFAULT_COLS)
+            .is_ok());
+        manager.close("limit-test-0").unwrap();
+
+        manager.close("limit-test-1").unwrap();
+        assert!(manager
+            .spawn(
+                "limit-test-overflow".to_string(),
+                DEFAULT_ROWS,
+                DEFAULT_COLS
+            )
+            .is_ok());
+
+        for i in 0..DEFAULT_MAX_SESSIONS {
+            manager.close(&format!("limit-test-{}", i)).unwrap();
+        }
+        manager.close("limit-test-overflow").unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_once_collects_output_and_times_out() {
+        let _guard = pty_test_guard();
+        let args = vec!["-c".to_string(), "echo hello; exit 3".to_string()];
+        let output = run_once("sh", &args, None, Duration::from_secs(5)).unwrap();
+        assert!(
+            output.stdout.contains("hello"),
+            "Output: {:?}",
+            output.stdout
+        );
+        assert_eq!(output.exit_code, Some(3));
+        assert!(!output.timed_out);
+
+        let args = vec!["5".to_string()];
+        let started = Instant::now();
+        let output = run_once("sleep", &args, None, Duration::from_millis(300)).unwrap();
+        assert!(output.timed_out);
+        assert_eq!(output.exit_code, None);
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_search_scrollback_text() {
+        let text = strip_escape_sequences(concat!(
+            "$ ls\r\n",
+            "\x1b[1;34msrc\x1b[0m  Cargo.toml\r\n",
+            "\x1b]0;title\x07$ cargo build\r\n",
+            "error[E0308]: mismatched types\r\n",
+        ));
+        let regex = Regex::new(r"error\[E\d+\]").unwrap();
+        let matches = search_text(&text, &regex);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line, 4);
+        assert_eq!(matches[0].snippet, "error[E0308]: mismatched types");
+
+        let matches = search_text(&text, &Regex::new("src").unwrap());
+        assert_eq!(matches[0].snippet, "src  Cargo.toml");
+
+        let mut scrollback = vec![b'a'; SCROLLBACK_BYTES];
+        append_scrollback(&mut scrollback, b"tail");
+        assert_eq!(scrollback.len(), SCROLLBACK_BYTES);
+        assert!(scrollback.ends_with(b"tail"));
+
+        let manager = PtyManager::new();
+        assert!(matches!(
+            manager.search("missing", "("),
+            Err(HyphaeicError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_recording_marks_direction_changes() {
+        let path = std::env::temp_dir().join(format!("pty-recording-{}.log", std::process::id()));
+        let path_str = path.to_str().unwrap();
+
+        let mut recording = Recording::open(path_str, true).unwrap();
+        recording.record(Direction::Input, b"ls\r").unwrap();
+        recording.record(Direction::Output, b"src\r\n").unwrap();
+        recording.record(Direction::Output, b"$ ").unwrap();
+        recording.finish().unwrap();
+
+        let mut output_only = Recording::open(path_str, false).unwrap();
+        output_only.record(Direction::Input, b"hidden").unwrap();
+        output_only.record(Direction::Output, b"shown").unwrap();
+        output_only.finish().unwrap();
+
+        let transcript = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            transcript,
+            "\n--- input ---\nls\r\n--- output ---\nsrc\r\n$ shown"
+        );
+
+        let manager = PtyManager::new();
+        assert_eq!(
+            manager.start_recording("missing", path_str, false),
+            Err(HyphaeicError::PtyNotFound("missing".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_spawn_honors_initial_size() {
+        let _guard = pty_test_guard();
+        let mut manager = PtyManager::new();
+        let id = "size-test".to_string();
+
+        manager.spawn(id.clone(), 40, 120).unwrap();
+        let master_size = manager.sessions[&id].pair.master.get_size().unwrap();
+        assert_eq!((master_size.rows, master_size.cols), (40, 120));
+        assert_eq!(
+            manager.size(&id).unwrap(),
+            PtyDimensions {
+                rows: 40,
+                cols: 120
+            }
+        );
+
+        // Reattaching keeps the current size; resizing updates it
+        manager.spawn(id.clone(), 10, 10).unwrap();
+        manager.resize(&id, 50, 132).unwrap();
+        assert_eq!(
+            manager.size(&id).unwrap(),
+            PtyDimensions {
+                rows: 50,
+                cols: 132
+            }
+        );
+
+        assert!(matches!(
+            manager.resize(&id, 0, 80),
+            Err(HyphaeicError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            manager.spawn("zero-size".to_string(), 24, 0),
+            Err(HyphaeicError::InvalidInput(_))
+        ));
+
+        manager.close(&id).unwrap();
+        manager.close(&id).unwrap();
+    }
+
+    #[test]
+    fn test_format_uptime() {
+        assert_eq!(format_uptime(0), "0D 00H 00M");
+        assert_eq!(format_uptime(93_784), "1D 02H 03M");
     }
 }
@@ -1,37 +1,56 @@
-use std::collections::HashMap;
+use crate::error::HyphaeicError;
+use serde::{Deserialize, Serialize};
+use log::warn;
+use std::collections::{HashMap, VecDeque};
 use uuid::Uuid;
-use self::window::{WindowInstance, WindowState, CompositorSlot};
+use self::window::{WindowInstance, WindowState, CompositorSlot, KNOWN_CONTENT_KEYS};
 
 pub mod window;
 
+/// Number of compositor slots by default (left and right)
+pub const DEFAULT_SLOT_COUNT: usize = 2;
+
+/// How many closed windows are remembered for restore_last_closed
+pub const MAX_RECENTLY_CLOSED: usize = 10;
+
+/// Window layout as persisted between app restarts
+#[derive(Serialize, Deserialize)]
+struct SavedLayout {
+    windows: Vec<WindowInstance>,
+    window_stack: Vec<String>,
+    slots: Vec<Option<String>>,
+}
+
 pub struct StateManager {
     pub windows: HashMap<String, WindowInstance>,
     pub window_stack: Vec<String>, // Ordered list of IDs for focus history
-    pub left_slot: Option<String>,  // Window ID in left slot
-    pub right_slot: Option<String>, // Window ID in right slot
+    pub slots: Vec<Option<String>>, // Window ID per compositor slot, indexed by CompositorSlot
+    pub recently_closed: VecDeque<WindowInstance>, // Most recently closed last
 }
 
 impl StateManager {
-    pub fn new() -> Self {
+    pub fn new(slot_count: usize) -> Self {
         Self {
             windows: HashMap::new(),
             window_stack: Vec::new(),
-            left_slot: None,
-            right_slot: None,
+            slots: vec![None; slot_count],
+            recently_closed: VecDeque::new(),
         }
     }
 
-    /// Spawn a new window in the first available slot
-    /// Returns None if both slots are occupied
-    pub fn spawn_window(&mut self, content_key: String, source_element_id: Option<String>, source_domain_id: Option<String>) -> Option<WindowInstance> {
-        // Find first available slot (left first, then right)
-        let slot = if self.left_slot.is_none() {
-            CompositorSlot::Left
-        } else if self.right_slot.is_none() {
-            CompositorSlot::Right
-        } else {
-            // Both slots occupied - cannot spawn
-            return None;
+    /// Spawn a new window in `preferred_slot` if it's free, else the first available slot
+    /// Returns None if all slots are occupied
+    pub fn spawn_window(
+        &mut self,
+        content_key: String,
+        source_element_id: Option<String>,
+        source_domain_id: Option<String>,
+        preferred_slot: Option<CompositorSlot>,
+    ) -> Option<WindowInstance> {
+        // Preferred slot if free, otherwise lowest index first; all occupied - cannot spawn
+        let slot = match preferred_slot {
+            Some(slot) if self.is_slot_available(slot) => slot,
+            _ => CompositorSlot(self.slots.iter().position(|s| s.is_none())?),
         };
 
         let id = Uuid::new_v4().to_string();
@@ -50,10 +69,7 @@ impl StateManager {
         };
 
         // Assign to slot
-        match slot {
-            CompositorSlot::Left => self.left_slot = Some(id.clone()),
-            CompositorSlot::Right => self.right_slot = Some(id.clone()),
-        }
+        self.slots[slot.0] = Some(id.clone());
 
         self.windows.insert(id.clone(), window.clone());
         self.window_stack.push(id);
@@ -61,14 +77,34 @@ impl StateManager {
         Some(window)
     }
 
+    /// Like `spawn_window`, but when every slot is occupied the least-recently-focused
+    /// window (bottom of `window_stack`) is closed and its slot reused.
+    /// Returns the new window and the evicted one, if any.
+    pub fn spawn_window_lru(
+        &mut self,
+        content_key: String,
+        source_element_id: Option<String>,
+        source_domain_id: Option<String>,
+        preferred_slot: Option<CompositorSlot>,
+    ) -> Option<(WindowInstance, Option<WindowInstance>)> {
+        let evicted = if self.slots.iter().all(|s| s.is_some()) {
+            let oldest = self.window_stack.first()?.clone();
+            self.close_window(&oldest)
+        } else {
+            None
+        };
+
+        let window =
+            self.spawn_window(content_key, source_element_id, source_domain_id, preferred_slot)?;
+        Some((window, evicted))
+    }
+
     /// Close a window and free its slot
     /// Returns the window instance that was closed (useful for retrieving source_id)
     pub fn close_window(&mut self, id: &str) -> Option<WindowInstance> {
         // Free the slot
-        if self.left_slot.as_deref() == Some(id) {
-            self.left_slot = None;
-        } else if self.right_slot.as_deref() == Some(id) {
-            self.right_slot = None;
+        if let Some(slot) = self.slots.iter_mut().find(|s| s.as_deref() == Some(id)) {
+            *slot = None;
         }
 
         // Remove from stack
@@ -78,35 +114,255 @@ impl StateManager {
         
         let removed_window = self.windows.remove(id);
         self.normalize_stack();
+
+        if let Some(win) = &removed_window {
+            if self.recently_closed.len() == MAX_RECENTLY_CLOSED {
+                self.recently_closed.pop_front();
+            }
+            self.recently_closed.push_back(win.clone());
+        }
         
         removed_window
     }
 
+    /// Re-spawn the most recently closed window with its content, title and source
+    /// element/domain (so focus return still works). It stays remembered if no slot is free.
+    pub fn restore_last_closed(&mut self) -> Result<WindowInstance, HyphaeicError> {
+        let closed = self
+            .recently_closed
+            .back()
+            .cloned()
+            .ok_or(HyphaeicError::NoClosedWindows)?;
+
+        let mut window = self
+            .spawn_window(
+                closed.content_key,
+                closed.source_element_id,
+                closed.source_domain_id,
+                Some(closed.slot),
+            )
+            .ok_or(HyphaeicError::SlotFull)?;
+        self.recently_closed.pop_back();
+
+        if let Some(win) = self.windows.get_mut(&window.id) {
+            win.title = closed.title;
+            window = win.clone();
+        }
+        Ok(window)
+    }
+
     /// Set window state (Minimized = half, Maximized = full, Hidden = not shown)
-    pub fn set_window_state(&mut self, id: &str, new_state: WindowState) -> Option<WindowInstance> {
-        if let Some(win) = self.windows.get_mut(id) {
-            win.state = new_state;
-            Some(win.clone())
-        } else {
-            None
+    /// Only one window may be Maximized: maximizing one demotes any other to Minimized.
+    /// Returns every window whose state changed (the target first), or nothing if `id` is unknown.
+    pub fn set_window_state(&mut self, id: &str, new_state: WindowState) -> Vec<WindowInstance> {
+        let Some(win) = self.windows.get_mut(id) else {
+            return Vec::new();
+        };
+        win.state = new_state;
+        let mut changed = vec![win.clone()];
+
+        if new_state == WindowState::Maximized {
+            for (other_id, other) in self.windows.iter_mut() {
+                if other_id != id && other.state == WindowState::Maximized {
+                    other.state = WindowState::Minimized;
+                    changed.push(other.clone());
+                }
+            }
+        }
+        changed
+    }
+
+    /// Advance a window to its next state: Minimized -> Maximized -> Minimized.
+    /// Hidden windows come back as Minimized; windows that are Closing can't be cycled.
+    /// Returns every window whose state changed, as with `set_window_state`.
+    pub fn cycle_window_state(&mut self, id: &str) -> Result<Vec<WindowInstance>, HyphaeicError> {
+        let current = self
+            .windows
+            .get(id)
+            .map(|win| win.state)
+            .ok_or_else(|| HyphaeicError::WindowNotFound(id.to_string()))?;
+
+        let next = match current {
+            WindowState::Minimized => WindowState::Maximized,
+            WindowState::Maximized | WindowState::Hidden => WindowState::Minimized,
+            WindowState::Closing => return Err(HyphaeicError::WindowClosing(id.to_string())),
+        };
+        Ok(self.set_window_state(id, next))
+    }
+
+    /// Windows in the Hidden state, in focus-stack order.
+    /// Hidden windows keep their compositor slot, so unhiding never needs a free one.
+    pub fn get_hidden_windows(&self) -> Vec<WindowInstance> {
+        self.window_stack
+            .iter()
+            .filter_map(|id| self.windows.get(id))
+            .filter(|win| win.state == WindowState::Hidden)
+            .cloned()
+            .collect()
+    }
+
+    /// Bring a Hidden window back as Minimized in the slot it kept while hidden
+    pub fn unhide_window(&mut self, id: &str) -> Result<WindowInstance, HyphaeicError> {
+        let win = self
+            .windows
+            .get_mut(id)
+            .ok_or_else(|| HyphaeicError::WindowNotFound(id.to_string()))?;
+        if win.state != WindowState::Hidden {
+            return Err(HyphaeicError::WindowNotHidden(id.to_string()));
+        }
+        win.state = WindowState::Minimized;
+        Ok(win.clone())
+    }
+
+    /// Rename a window (e.g. a terminal showing its cwd). Empty titles are rejected.
+    pub fn set_title(&mut self, id: &str, title: String) -> Result<WindowInstance, HyphaeicError> {
+        if title.trim().is_empty() {
+            return Err(HyphaeicError::InvalidInput("Window title cannot be empty".to_string()));
+        }
+
+        let win = self
+            .windows
+            .get_mut(id)
+            .ok_or_else(|| HyphaeicError::WindowNotFound(id.to_string()))?;
+        win.title = title;
+        Ok(win.clone())
+    }
+
+    /// Exchange the left and right slot contents, updating each moved window's slot.
+    /// If only one is occupied, its window moves to the other. Returns the moved windows.
+    pub fn swap_slots(&mut self) -> Vec<WindowInstance> {
+        if self.slots.len() < 2 {
+            return Vec::new();
+        }
+        self.slots.swap(CompositorSlot::LEFT.0, CompositorSlot::RIGHT.0);
+
+        let mut moved = Vec::new();
+        for slot in [CompositorSlot::LEFT, CompositorSlot::RIGHT] {
+            let id = self.slots[slot.0].as_ref();
+            if let Some(win) = id.and_then(|id| self.windows.get_mut(id)) {
+                win.slot = slot;
+                moved.push(win.clone());
+            }
+        }
+        moved
+    }
+
+    /// Move a window to `target`, freeing its old slot. Fails if `target` doesn't exist
+    /// or holds a different window; moving to the slot it already occupies is a no-op.
+    pub fn move_window(
+        &mut self,
+        id: &str,
+        target: CompositorSlot,
+    ) -> Result<WindowInstance, HyphaeicError> {
+        let current = self
+            .windows
+            .get(id)
+            .map(|win| win.slot)
+            .ok_or_else(|| HyphaeicError::WindowNotFound(id.to_string()))?;
+
+        match self.slots.get(target.0) {
+            None => return Err(HyphaeicError::SlotNotFound(target.0)),
+            Some(Some(occupant)) if occupant != id => {
+                return Err(HyphaeicError::SlotOccupied {
+                    slot: target.0,
+                    window_id: occupant.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+
+        if current != target {
+            if let Some(old) = self.slots.get_mut(current.0) {
+                *old = None;
+            }
+            self.slots[target.0] = Some(id.to_string());
+        }
+
+        match self.windows.get_mut(id) {
+            Some(win) => {
+                win.slot = target;
+                Ok(win.clone())
+            }
+            None => Err(HyphaeicError::WindowNotFound(id.to_string())),
         }
     }
 
     /// Check if a slot is available
     pub fn is_slot_available(&self, slot: CompositorSlot) -> bool {
-        match slot {
-            CompositorSlot::Left => self.left_slot.is_none(),
-            CompositorSlot::Right => self.right_slot.is_none(),
-        }
+        self.slots.get(slot.0).is_some_and(|s| s.is_none())
     }
 
     /// Get window in a specific slot
     pub fn get_window_in_slot(&self, slot: CompositorSlot) -> Option<&WindowInstance> {
-        let id = match slot {
-            CompositorSlot::Left => self.left_slot.as_ref(),
-            CompositorSlot::Right => self.right_slot.as_ref(),
+        let id = self.slots.get(slot.0)?.as_ref()?;
+        self.windows.get(id)
+    }
+
+    /// Serialize the window layout (windows, focus order, slot assignments) as JSON
+    pub fn serialize(&self) -> Result<String, HyphaeicError> {
+        let layout = SavedLayout {
+            windows: self.windows.values().cloned().collect(),
+            window_stack: self.window_stack.clone(),
+            slots: self.slots.clone(),
         };
-        id.and_then(|id| self.windows.get(id))
+        serde_json::to_string_pretty(&layout).map_err(|e| {
+            HyphaeicError::Serialization(format!("Failed to serialize layout: {}", e))
+        })
+    }
+
+    /// Replace the current windows with a layout from `serialize`. Windows with an
+    /// unknown content key, or whose slot doesn't exist or is already taken, are skipped.
+    /// Returns the restored windows.
+    pub fn restore(&mut self, json: &str) -> Result<Vec<WindowInstance>, HyphaeicError> {
+        let layout: SavedLayout =
+            serde_json::from_str(json).map_err(|e| {
+                HyphaeicError::Serialization(format!("Failed to parse layout: {}", e))
+            })?;
+
+        self.windows.clear();
+        self.window_stack.clear();
+        self.slots.iter_mut().for_each(|slot| *slot = None);
+
+        for win in layout.windows {
+            if !KNOWN_CONTENT_KEYS.contains(&win.content_key.as_str()) {
+                warn!(
+                    "[STATE] Skipping window {} with unknown content key '{}'",
+                    win.id, win.content_key
+                );
+                continue;
+            }
+            if !self.is_slot_available(win.slot) {
+                warn!(
+                    "[STATE] Skipping window {}: slot {} unavailable",
+                    win.id, win.slot.0
+                );
+                continue;
+            }
+            self.slots[win.slot.0] = Some(win.id.clone());
+            self.windows.insert(win.id.clone(), win);
+        }
+
+        // Keep the saved focus order for restored windows; anything missing goes on top
+        self.window_stack = layout
+            .window_stack
+            .into_iter()
+            .filter(|id| self.windows.contains_key(id))
+            .collect();
+        let mut unstacked: Vec<String> = self
+            .windows
+            .keys()
+            .filter(|id| !self.window_stack.contains(id))
+            .cloned()
+            .collect();
+        unstacked.sort();
+        self.window_stack.extend(unstacked);
+        self.normalize_stack();
+
+        Ok(self
+            .window_stack
+            .iter()
+            .filter_map(|id| self.windows.get(id).cloned())
+            .collect())
     }
 
     pub fn get_all_windows(&self) -> Vec<WindowInstance> {
@@ -121,3 +377,242 @@ impl StateManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every occupied slot points at a window that agrees it lives there
+    fn assert_slots_consistent(manager: &StateManager) {
+        for (index, id) in manager.slots.iter().enumerate() {
+            if let Some(id) = id {
+                assert_eq!(manager.windows[id].slot, CompositorSlot(index));
+            }
+        }
+    }
+
+    #[test]
+    fn test_move_window_to_occupied_slot_fails() {
+        let mut manager = StateManager::new(DEFAULT_SLOT_COUNT);
+        let left = manager.spawn_window("LEFT".to_string(), None, None, None).unwrap();
+        let right = manager.spawn_window("RIGHT".to_string(), None, None, None).unwrap();
+
+        let err = manager
+            .move_window(&left.id, CompositorSlot::RIGHT)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            HyphaeicError::SlotOccupied {
+                slot: 1,
+                window_id: right.id.clone(),
+            }
+        );
+        assert_eq!(manager.slots[0].as_deref(), Some(left.id.as_str()));
+        assert_eq!(manager.slots[1].as_deref(), Some(right.id.as_str()));
+
+        // Moving to the slot it already occupies is a no-op success
+        let win = manager.move_window(&left.id, CompositorSlot::LEFT).unwrap();
+        assert_eq!(win.slot, CompositorSlot::LEFT);
+
+        // Once the target is free the move goes through
+        manager.close_window(&right.id);
+        manager.move_window(&left.id, CompositorSlot::RIGHT).unwrap();
+        assert_eq!(manager.slots[0].as_deref(), None);
+        assert_eq!(manager.slots[1].as_deref(), Some(left.id.as_str()));
+        assert_slots_consistent(&manager);
+    }
+
+    #[test]
+    fn test_set_title_rejects_empty() {
+        let mut manager = StateManager::new(DEFAULT_SLOT_COUNT);
+        let win = manager.spawn_window("SYS_TERMINAL".to_string(), None, None, None).unwrap();
+
+        let renamed = manager.set_title(&win.id, "~/projects".to_string()).unwrap();
+        assert_eq!(renamed.title, "~/projects");
+
+        assert!(manager.set_title(&win.id, "   ".to_string()).is_err());
+        assert_eq!(manager.windows[&win.id].title, "~/projects");
+    }
+
+    #[test]
+    fn test_restore_last_closed() {
+        let mut manager = StateManager::new(DEFAULT_SLOT_COUNT);
+        let win = manager
+            .spawn_window(
+                "SYS_TERMINAL".to_string(),
+                Some("btn-term".to_string()),
+                Some("osbar-nav".to_string()),
+                None,
+            )
+            .unwrap();
+        manager.set_title(&win.id, "~/projects".to_string()).unwrap();
+        manager.close_window(&win.id);
+
+        let restored = manager.restore_last_closed().unwrap();
+        assert_eq!(restored.content_key, "SYS_TERMINAL");
+        assert_eq!(restored.title, "~/projects");
+        assert_eq!(restored.source_element_id.as_deref(), Some("btn-term"));
+        assert_eq!(restored.source_domain_id.as_deref(), Some("osbar-nav"));
+        assert!(manager.restore_last_closed().is_err());
+
+        // Without a free slot the entry is kept for a later attempt
+        manager.spawn_window("OTHER".to_string(), None, None, None).unwrap();
+        manager.close_window(&restored.id);
+        manager.spawn_window("FILLER".to_string(), None, None, None).unwrap();
+        assert!(manager.restore_last_closed().is_err());
+        assert_eq!(manager.recently_closed.len(), 1);
+
+        // History is bounded
+        for _ in 0..MAX_RECENTLY_CLOSED + 5 {
+            let id = manager.slots[0].clone().unwrap();
+            manager.close_window(&id);
+            manager.spawn_window("CHURN".to_string(), None, None, None).unwrap();
+        }
+        assert_eq!(manager.recently_closed.len(), MAX_RECENTLY_CLOSED);
+    }
+
+    #[test]
+    fn test_layout_round_trip_skips_unknown_content() {
+        let mut manager = StateManager::new(DEFAULT_SLOT_COUNT);
+        let terminal = manager.spawn_window("TERMINAL".to_string(), None, None, None).unwrap();
+        manager.spawn_window("RETIRED_APP".to_string(), None, None, None).unwrap();
+        let json = manager.serialize().unwrap();
+
+        let mut restored = StateManager::new(DEFAULT_SLOT_COUNT);
+        let windows = restored.restore(&json).unwrap();
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].id, terminal.id);
+        assert_eq!(restored.slots[0].as_deref(), Some(terminal.id.as_str()));
+        assert_eq!(restored.slots[1], None);
+        assert_eq!(restored.window_stack, vec![terminal.id.clone()]);
+        assert_slots_consistent(&restored);
+
+        assert!(restored.restore("not json").is_err());
+    }
+
+    #[test]
+    fn test_spawn_window_preferred_slot() {
+        let mut manager = StateManager::new(DEFAULT_SLOT_COUNT);
+        let panel = manager
+            .spawn_window("REFERENCE".to_string(), None, None, Some(CompositorSlot::RIGHT))
+            .unwrap();
+        assert_eq!(panel.slot, CompositorSlot::RIGHT);
+
+        // Preferred slot taken: fall back to the first free one
+        let other = manager
+            .spawn_window("OTHER".to_string(), None, None, Some(CompositorSlot::RIGHT))
+            .unwrap();
+        assert_eq!(other.slot, CompositorSlot::LEFT);
+
+        // Preferred slot taken and nothing else free
+        assert!(manager
+            .spawn_window("MORE".to_string(), None, None, Some(CompositorSlot::LEFT))
+            .is_none());
+        assert_slots_consistent(&manager);
+    }
+
+    #[test]
+    fn test_maximizing_one_window_demotes_the_other() {
+        let mut manager = StateManager::new(DEFAULT_SLOT_COUNT);
+        let left = manager.spawn_window("LEFT".to_string(), None, None, None).unwrap();
+        let right = manager.spawn_window("RIGHT".to_string(), None, None, None).unwrap();
+
+        manager.set_window_state(&left.id, WindowState::Maximized);
+        let changed = manager.set_window_state(&right.id, WindowState::Maximized);
+
+        assert_eq!(changed.len(), 2);
+        assert_eq!(changed[0].id, right.id);
+        assert_eq!(changed[1].id, left.id);
+        assert_eq!(manager.windows[&right.id].state, WindowState::Maximized);
+        assert_eq!(manager.windows[&left.id].state, WindowState::Minimized);
+
+        assert!(manager.set_window_state("missing", WindowState::Hidden).is_empty());
+    }
+
+    #[test]
+    fn test_cycle_window_state() {
+        let mut manager = StateManager::new(DEFAULT_SLOT_COUNT);
+        let win = manager.spawn_window("TERMINAL".to_string(), None, None, None).unwrap();
+
+        manager.cycle_window_state(&win.id).unwrap();
+        assert_eq!(manager.windows[&win.id].state, WindowState::Maximized);
+        manager.cycle_window_state(&win.id).unwrap();
+        assert_eq!(manager.windows[&win.id].state, WindowState::Minimized);
+
+        // Hidden is skipped in the cycle and returns as Minimized
+        manager.set_window_state(&win.id, WindowState::Hidden);
+        manager.cycle_window_state(&win.id).unwrap();
+        assert_eq!(manager.windows[&win.id].state, WindowState::Minimized);
+
+        manager.set_window_state(&win.id, WindowState::Closing);
+        assert!(manager.cycle_window_state(&win.id).is_err());
+        assert!(manager.cycle_window_state("missing").is_err());
+    }
+
+    #[test]
+    fn test_hidden_windows_keep_slot_and_unhide() {
+        let mut manager = StateManager::new(DEFAULT_SLOT_COUNT);
+        let left = manager.spawn_window("LEFT".to_string(), None, None, None).unwrap();
+        let right = manager.spawn_window("RIGHT".to_string(), None, None, None).unwrap();
+
+        manager.set_window_state(&left.id, WindowState::Hidden);
+        let hidden = manager.get_hidden_windows();
+        assert_eq!(hidden.len(), 1);
+        assert_eq!(hidden[0].id, left.id);
+
+        // The hidden window still holds its slot
+        assert!(!manager.is_slot_available(CompositorSlot::LEFT));
+        assert!(manager.unhide_window(&right.id).is_err());
+
+        let window = manager.unhide_window(&left.id).unwrap();
+        assert_eq!(window.state, WindowState::Minimized);
+        assert_eq!(window.slot, CompositorSlot::LEFT);
+        assert!(manager.get_hidden_windows().is_empty());
+        assert_slots_consistent(&manager);
+    }
+
+    #[test]
+    fn test_spawn_window_lru_evicts_least_recent() {
+        let mut manager = StateManager::new(DEFAULT_SLOT_COUNT);
+        let left = manager.spawn_window("LEFT".to_string(), None, None, None).unwrap();
+        manager.spawn_window("RIGHT".to_string(), None, None, None).unwrap();
+
+        // Default path still refuses
+        assert!(manager.spawn_window("TERMINAL".to_string(), None, None, None).is_none());
+
+        let (window, evicted) = manager
+            .spawn_window_lru("TERMINAL".to_string(), None, None, None)
+            .unwrap();
+        assert_eq!(evicted.map(|w| w.id), Some(left.id.clone()));
+        assert_eq!(window.slot, CompositorSlot::LEFT);
+        assert!(!manager.windows.contains_key(&left.id));
+        assert_slots_consistent(&manager);
+
+        // With a free slot nothing is evicted
+        manager.close_window(&window.id);
+        let (_, evicted) = manager
+            .spawn_window_lru("TERMINAL".to_string(), None, None, None)
+            .unwrap();
+        assert!(evicted.is_none());
+    }
+
+    #[test]
+    fn test_swap_slots() {
+        let mut manager = StateManager::new(DEFAULT_SLOT_COUNT);
+        let left = manager.spawn_window("LEFT".to_string(), None, None, None).unwrap();
+        let right = manager.spawn_window("RIGHT".to_string(), None, None, None).unwrap();
+
+        let moved = manager.swap_slots();
+        assert_eq!(moved.len(), 2);
+        assert_eq!(manager.slots[0].as_deref(), Some(right.id.as_str()));
+        assert_eq!(manager.slots[1].as_deref(), Some(left.id.as_str()));
+        assert_slots_consistent(&manager);
+
+        // With one window left, swapping moves it to the other slot
+        manager.close_window(&left.id);
+        manager.swap_slots();
+        assert_eq!(manager.slots[0].as_deref(), None);
+        assert_eq!(manager.slots[1].as_deref(), Some(right.id.as_str()));
+        assert_slots_consistent(&manager);
+    }
+}
@@ -1,4 +1,8 @@
-use serde::{Serialize, Deserialize};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Content keys the frontend knows how to render (see Interface.tsx)
+pub const KNOWN_CONTENT_KEYS: [&str; 3] = ["TERMINAL", "TESTING_DUMMY", "EMPTY_WINDOW_2"];
 
 #[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
 pub enum WindowState {
@@ -12,10 +16,47 @@ pub enum WindowState {
     Closing,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
-pub enum CompositorSlot {
-    Left,
-    Right,
+/// Index of a compositor slot. Slots 0 and 1 serialize as "Left"/"Right" so the
+/// two-slot frontend keeps working; further slots serialize as their index.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CompositorSlot(pub usize);
+
+impl CompositorSlot {
+    pub const LEFT: CompositorSlot = CompositorSlot(0);
+    pub const RIGHT: CompositorSlot = CompositorSlot(1);
+}
+
+impl Serialize for CompositorSlot {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            0 => serializer.serialize_str("Left"),
+            1 => serializer.serialize_str("Right"),
+            index => serializer.serialize_u64(index as u64),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CompositorSlot {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum SlotRepr {
+            Index(usize),
+            Name(String),
+        }
+
+        match SlotRepr::deserialize(deserializer)? {
+            SlotRepr::Index(index) => Ok(CompositorSlot(index)),
+            SlotRepr::Name(name) => match name.as_str() {
+                "Left" => Ok(CompositorSlot::LEFT),
+                "Right" => Ok(CompositorSlot::RIGHT),
+                other => Err(D::Error::custom(format!(
+                    "Unknown compositor slot '{}'",
+                    other
+                ))),
+            },
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
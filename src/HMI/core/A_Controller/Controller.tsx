@@ -16,6 +16,7 @@ interface CursorMovedPayload {
   domain_id: string;
   element_id: string;
   element_type: string;
+  bounds: { x: number; y: number; width: number; height: number } | null;
 }
 
 interface ElementActivatedPayload {
@@ -37,6 +38,7 @@ interface DomainSwitchedPayload {
 
 interface BoundaryReachedPayload {
   direction: string;
+  reason: "boundary_lock" | "no_adjacent_domain" | "empty_domain" | "edge";
 }
 
 // Window action types for external triggering
@@ -247,6 +249,13 @@ export default function Controller(props: ControllerProps) {
           state.tauriUnlisteners.push(u5);
 
           console.log("[Controller] Tauri listeners registered");
+
+          // Restore the saved window layout (if any) now that window-created is heard
+          try {
+            await invoke('load_layout');
+          } catch (e) {
+            console.warn('[Controller] Failed to load window layout:', e);
+          }
         } catch (err) {
           console.error("[Controller] Failed to setup Tauri listeners", err);
           state.tauriListenersActive = false;
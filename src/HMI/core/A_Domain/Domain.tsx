@@ -172,8 +172,7 @@ export default function Domain(props: DomainProps) {
       // Also listen for window resize (affects position when viewport changes)
       window.addEventListener('resize', handleWindowResize);
     } catch (error) {
-      const msg = String(error);
-      if (msg.includes("already exists")) {
+      if ((error as any)?.kind === "DomainExists") {
         console.log(`[Domain ${props.id}] Already registered (hot reload?)`);
         setIsReady(true);
         requestAnimationFrame(() => {
@@ -10,6 +10,8 @@ interface AssetInfo {
   path: string;
   cached: boolean;
   asset_type: string;
+  width: number | null;
+  height: number | null;
 }
 
 /**
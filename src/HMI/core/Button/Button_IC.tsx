@@ -22,6 +22,8 @@ interface ButtonProps {
   domainId?: string;
   /** Sequential order for list/grid navigation */
   order: number;
+  /** Human-readable name, used by focus_button_by_label (type-to-search) */
+  label?: string;
   /** Click handler - called on mouse click OR keyboard activation */
   onClick?: () => void;
   /** Button content */
@@ -80,7 +82,8 @@ export default function Button_IC(props: ButtonProps) {
         domainId: domainId,
         buttonId: props.id,
         bounds,
-        order: props.order
+        order: props.order,
+        label: props.label ?? null
       });
 
       registeredButtons.add(props.id);
@@ -99,8 +102,7 @@ export default function Button_IC(props: ButtonProps) {
         // Cursor position not available yet - will be set by first sys-cursor-move
       }
     } catch (error) {
-      const msg = String(error);
-      if (msg.includes("already exists")) {
+      if ((error as any)?.kind === "ButtonExists") {
         // Already registered (e.g., from hot reload)
         registeredButtons.add(props.id);
         setIsRegistered(true);
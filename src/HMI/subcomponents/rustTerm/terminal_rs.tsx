@@ -35,21 +35,26 @@ export default function TerminalRS(props: TerminalRSProps) {
     const CHAR_HEIGHT = 16;  // px
 
     // Calculate terminal dimensions from container size
-    const updateTerminalSize = () => {
-        if (!containerRef) return;
+    const measureTerminal = (): { rows: number; cols: number } | null => {
+        if (!containerRef) return null;
 
         const rect = containerRef.getBoundingClientRect();
         const cols = Math.floor(rect.width / CHAR_WIDTH);
         const rows = Math.floor(rect.height / CHAR_HEIGHT);
 
-        if (cols > 0 && rows > 0) {
+        return cols > 0 && rows > 0 ? { rows, cols } : null;
+    };
+
+    const updateTerminalSize = () => {
+        const size = measureTerminal();
+        if (size) {
             // Resize PTY if session exists
             const sid = sessionId();
             if (sid) {
                 invoke("pty_resize", {
                     sessionId: sid,
-                    rows: rows,
-                    cols: cols,
+                    rows: size.rows,
+                    cols: size.cols,
                 }).catch(console.error);
             }
         }
@@ -65,20 +70,19 @@ export default function TerminalRS(props: TerminalRSProps) {
             const banner = await invoke<string>("get_system_banner", { sessionId: sid });
             setOutput(banner);
 
-            // Spawn the PTY session
-            await invoke("pty_spawn", { sessionId: sid });
+            // Spawn the PTY session at the container's size so it doesn't reflow
+            const size = measureTerminal();
+            await invoke("pty_spawn", { sessionId: sid, rows: size?.rows, cols: size?.cols });
             setSessionId(sid);
             setIsReady(true);
 
-            // Initial resize
-            updateTerminalSize();
-
             // Start polling for output
             startOutputPolling(sid);
 
         } catch (error) {
             console.error("[TerminalRS] Failed to initialize:", error);
-            setOutput(prev => prev + `\n[ERROR] Failed to initialize terminal: ${error}`);
+            const message = (error as any)?.message ?? String(error);
+            setOutput(prev => prev + `\n[ERROR] Failed to initialize terminal: ${message}`);
         }
     };
 